@@ -6,7 +6,10 @@
 
 #![allow(missing_docs)]
 use criterion::{criterion_group, criterion_main, Criterion};
-use frontmatter_gen::{extract, parser, Format, Frontmatter, Value};
+use frontmatter_gen::{
+    extract, extract_ref, parser, Format, Frontmatter, FrontmatterRef,
+    Value,
+};
 use std::hint::black_box;
 
 // Benchmarks the `extract` function for extracting frontmatter from content.
@@ -132,19 +135,65 @@ fn benchmark_to_format(c: &mut Criterion) {
     });
 }
 
+// Benchmarks the borrowed view against the owned front matter.
+//
+// This benchmark measures what an indexer pays to read a few keys from
+// a document with many string values: `extract` copies every string,
+// while `extract_ref` borrows them from the source.
+#[allow(dead_code)]
+fn benchmark_borrowed(c: &mut Criterion) {
+    let mut toml = String::from("+++\ntitle = \"My Post\"\n");
+    let mut json = String::from("{\"title\": \"My Post\"");
+    for i in 0..200 {
+        let value = format!("a long description of entry number {i}");
+        toml.push_str(&format!("key{i} = \"{value}\"\n"));
+        json.push_str(&format!(", \"key{i}\": \"{value}\""));
+    }
+    toml.push_str("+++\nBody");
+    json.push_str("}\nBody");
+
+    for (name, content) in [("TOML", &toml), ("JSON", &json)] {
+        let _ =
+            c.bench_function(&format!("extract {name} owned"), |b| {
+                b.iter(|| {
+                    let (frontmatter, _) =
+                        extract(black_box(content)).unwrap();
+                    frontmatter.get("title").cloned()
+                })
+            });
+        let _ = c.bench_function(
+            &format!("extract {name} borrowed"),
+            |b| {
+                b.iter(|| {
+                    let (frontmatter, _) =
+                        extract_ref(black_box(content)).unwrap();
+                    frontmatter.get("title").cloned()
+                })
+            },
+        );
+    }
+
+    let raw = &json[..json.len() - "\nBody".len()];
+    let _ = c.bench_function("parse JSON borrowed", |b| {
+        b.iter(|| FrontmatterRef::parse(black_box(raw), Format::Json))
+    });
+}
+
 // Defines the Criterion benchmark group for this crate.
 //
 // This group includes benchmarks for:
 // - Extracting frontmatter
 // - Parsing frontmatter in YAML, TOML, and JSON formats
 // - Converting frontmatter to YAML, TOML, and JSON formats
+// - Extracting into owned and borrowed front matter
 criterion_group!(
     benches,
     benchmark_extract,
     benchmark_parse_yaml,
     benchmark_parse_toml,
     benchmark_parse_json,
-    benchmark_to_format
+    benchmark_to_format,
+    benchmark_borrowed
 );
 
 // Defines the Criterion benchmark entry point.
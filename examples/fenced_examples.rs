@@ -4,8 +4,9 @@
 //! # Fenced Code Block Handling Example
 //!
 //! This example demonstrates how to use the `frontmatter-gen` library to handle content
-//! with fenced code blocks containing path traversal patterns. The `validate_input`
-//! function ensures that such patterns inside fenced blocks are ignored during validation.
+//! with fenced code blocks containing path traversal patterns. Only the front matter
+//! values are checked, by the `SecurityPolicy`, so patterns in the body are never
+//! rejected.
 //!
 //! ## Usage
 //!
@@ -107,20 +108,23 @@ fn test_fenced_code_handling() {
         assert!(handle_fenced_code_example().is_ok());
     }
 
-    /// Tests detection of path traversal outside fenced code blocks.
+    /// Tests that path traversal in the body is left alone.
     #[test]
     fn test_path_traversal_outside_fenced_code() {
         let content = r#"---
 title: Example
 ---
 
-../malicious/path
+../relative/path
 "#;
 
         let options = ParseOptions::default();
         let result = validate_input(content, &options);
 
-        assert!(result.is_err(), "Validation should detect path traversal outside fenced code blocks");
+        assert!(
+            result.is_ok(),
+            "Validation should not inspect the body"
+        );
     }
 
     /// Tests path traversal handling inside fenced code blocks.
@@ -91,7 +91,7 @@ fn value_examples() -> Result<(), Box<dyn std::error::Error>> {
     assert_eq!(string_value.as_str().unwrap(), "Hello");
 
     // Number value example
-    let number_value = Value::Number(PI);
+    let number_value = Value::Float(PI);
     println!("    ✅ Number value: {:?}", number_value);
     assert_eq!(number_value.as_f64().unwrap(), PI);
 
@@ -102,7 +102,7 @@ fn value_examples() -> Result<(), Box<dyn std::error::Error>> {
 
     // Array value example
     let array_value =
-        Value::Array(vec![Value::Number(1.0), Value::Number(2.0)]);
+        Value::Array(vec![Value::Integer(1), Value::Integer(2)]);
     println!("    ✅ Array value: {:?}", array_value);
     assert_eq!(array_value.array_len().unwrap(), 2);
 
@@ -135,7 +135,7 @@ fn frontmatter_examples() -> Result<(), Box<dyn std::error::Error>> {
         Value::String("My Post".to_string()),
     );
     let _ =
-        frontmatter.insert("views".to_string(), Value::Number(100.0));
+        frontmatter.insert("views".to_string(), Value::Integer(100));
     println!("    ✅ Frontmatter with two entries: {:?}", frontmatter);
 
     let title = frontmatter.get("title").unwrap().as_str().unwrap();
@@ -235,7 +235,7 @@ fn test_value_types() {
         assert!(string_val.is_string());
         assert_eq!(string_val.as_str().unwrap(), "test");
 
-        let num_val = Value::Number(42.0);
+        let num_val = Value::Integer(42);
         assert!(num_val.is_number());
         assert_eq!(num_val.as_f64().unwrap(), 42.0);
     }
@@ -0,0 +1,551 @@
+// batch.rs
+
+//! # Batch Module
+//!
+//! This module extracts front matter from every matching file under a
+//! directory. The tree is walked with include and exclude globs, and
+//! the files are read and parsed in parallel as tasks on the current
+//! tokio runtime.
+//!
+//! Results are keyed by the path of each file relative to the root,
+//! with `/` as the separator on every platform. Files that fail are
+//! collected separately, so one bad file never hides the others.
+//!
+//! ## Usage Example
+//!
+//! ```rust,no_run
+//! use frontmatter_gen::batch::{extract_dir, BatchOptions};
+//!
+//! # async fn run() -> Result<(), frontmatter_gen::Error> {
+//! let options = BatchOptions {
+//!     exclude: vec!["drafts/**".to_string()],
+//!     ..BatchOptions::default()
+//! };
+//! let report = extract_dir("content", &options).await?;
+//!
+//! for (path, frontmatter) in &report.frontmatter {
+//!     println!("{path}: {:?}", frontmatter.get("title"));
+//! }
+//! for (path, error) in &report.errors {
+//!     eprintln!("{path}: {error}");
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use std::{
+    collections::BTreeMap,
+    num::NonZeroUsize,
+    path::{Path, PathBuf},
+    sync::Arc,
+    thread,
+};
+
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use tokio::task::JoinSet;
+
+use crate::{
+    error::Error, extract_with_delimiters_and_options,
+    extractor::Delimiters, to_format, Format, Frontmatter,
+    ParseOptions, Result, Value,
+};
+
+/// Options for [`extract_dir`].
+#[derive(Debug, Clone)]
+pub struct BatchOptions {
+    /// Globs a file's relative path must match, such as `**/*.md`
+    pub include: Vec<String>,
+    /// Globs that leave out a file, or a directory and all it holds
+    pub exclude: Vec<String>,
+    /// Fences to recognise around the front matter. Org and AsciiDoc
+    /// files are also read for their headers, as
+    /// [`Delimiters::for_path`] does.
+    pub delimiters: Delimiters,
+    /// Maximum number of files read and parsed at once
+    pub concurrency: NonZeroUsize,
+    /// Limits and security policy every file is parsed under
+    pub parse_options: ParseOptions,
+}
+
+impl Default for BatchOptions {
+    fn default() -> Self {
+        Self {
+            include: [
+                "**/*.md",
+                "**/*.markdown",
+                "**/*.mdx",
+                "**/*.org",
+                "**/*.adoc",
+                "**/*.asciidoc",
+            ]
+            .iter()
+            .map(ToString::to_string)
+            .collect(),
+            exclude: Vec::new(),
+            delimiters: Delimiters::default(),
+            concurrency: thread::available_parallelism()
+                .unwrap_or(NonZeroUsize::MIN),
+            parse_options: ParseOptions::from_env(),
+        }
+    }
+}
+
+/// The outcome of [`extract_dir`], keyed by relative path.
+#[derive(Debug, Default)]
+pub struct BatchReport {
+    /// Front matter of every file that was extracted
+    pub frontmatter: BTreeMap<String, Frontmatter>,
+    /// Error of every file that could not be read or extracted
+    pub errors: BTreeMap<String, Error>,
+}
+
+impl BatchReport {
+    /// Returns whether every matching file was extracted.
+    #[must_use]
+    pub fn is_success(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    /// Combines the front matter of every file into one document,
+    /// keyed by relative path.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use frontmatter_gen::{batch::BatchReport, Frontmatter};
+    ///
+    /// let mut report = BatchReport::default();
+    /// let _ = report.frontmatter.insert("a.md".to_string(), Frontmatter::new());
+    /// assert!(report.to_document().contains_key("a.md"));
+    /// ```
+    #[must_use]
+    pub fn to_document(&self) -> Frontmatter {
+        let mut document = Frontmatter::new();
+        for (path, frontmatter) in &self.frontmatter {
+            let _ = document.insert(
+                path.clone(),
+                Value::Object(Box::new(frontmatter.clone())),
+            );
+        }
+        document
+    }
+
+    /// Serialises the front matter as newline-delimited JSON: one
+    /// object per file, keyed by its relative path.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ConversionError` if a value cannot be
+    /// serialised.
+    pub fn to_ndjson(&self) -> Result<String> {
+        let mut lines = String::new();
+        for (path, frontmatter) in &self.frontmatter {
+            let mut line = Frontmatter::new();
+            let _ = line.insert(
+                path.clone(),
+                Value::Object(Box::new(frontmatter.clone())),
+            );
+            lines.push_str(&to_format(&line, Format::Json)?);
+            lines.push('\n');
+        }
+        Ok(lines)
+    }
+}
+
+/// Extracts the front matter of every matching file under `root`.
+///
+/// The tree is walked without following symbolic links. A file is
+/// extracted when its path relative to `root` matches an include glob
+/// and no exclude glob; a directory matching an exclude glob is not
+/// entered. Globs use `/` as the separator, and `*` also matches it.
+///
+/// Files are read and parsed as tasks spawned on the current tokio
+/// runtime, at most `options.concurrency` at a time, so they run in
+/// parallel on a multi-threaded runtime.
+///
+/// # Arguments
+///
+/// * `root` - The directory to walk.
+/// * `options` - Globs, fences, concurrency and parse options to use.
+///
+/// # Errors
+///
+/// Returns `Error::Other` if a glob is invalid, or `Error::IoError` if
+/// a directory cannot be read.
+/// Errors from single files are collected in [`BatchReport::errors`]
+/// instead.
+///
+/// # Panics
+///
+/// Panics if called outside a tokio runtime.
+pub async fn extract_dir(
+    root: impl AsRef<Path>,
+    options: &BatchOptions,
+) -> Result<BatchReport> {
+    let include = glob_set(&options.include)?;
+    let exclude = glob_set(&options.exclude)?;
+    let files = walk(root.as_ref(), &include, &exclude).await?;
+
+    let delimiters = Arc::new(options.delimiters.clone());
+    let parse_options = options.parse_options;
+    let mut report = BatchReport::default();
+    let mut tasks = JoinSet::new();
+    for (key, path) in files {
+        if tasks.len() >= options.concurrency.get() {
+            collect(&mut report, tasks.join_next().await);
+        }
+        let delimiters = Arc::clone(&delimiters);
+        let _ = tasks.spawn(async move {
+            let result =
+                extract_file(&path, &delimiters, &parse_options).await;
+            (key, result)
+        });
+    }
+    while let Some(joined) = tasks.join_next().await {
+        collect(&mut report, Some(joined));
+    }
+
+    Ok(report)
+}
+
+/// The result of one extraction task: the relative path, and the front
+/// matter or the error.
+type Outcome = (String, Result<Frontmatter>);
+
+/// Adds the outcome of a finished task to `report`.
+fn collect(
+    report: &mut BatchReport,
+    joined: Option<
+        std::result::Result<Outcome, tokio::task::JoinError>,
+    >,
+) {
+    match joined {
+        Some(Ok((key, Ok(frontmatter)))) => {
+            let _ = report.frontmatter.insert(key, frontmatter);
+        }
+        Some(Ok((key, Err(error)))) => {
+            let _ = report.errors.insert(key, error);
+        }
+        // A task only fails to join if it panicked, which `extract`
+        // does not; re-raise the panic here rather than lose the file.
+        Some(Err(error)) if error.is_panic() => {
+            std::panic::resume_unwind(error.into_panic())
+        }
+        Some(Err(_)) | None => {}
+    }
+}
+
+/// Reads `path` and extracts its front matter.
+async fn extract_file(
+    path: &Path,
+    delimiters: &Delimiters,
+    options: &ParseOptions,
+) -> Result<Frontmatter> {
+    let content = tokio::fs::read_to_string(path).await?;
+    extract_with_delimiters_and_options(
+        &content,
+        &delimiters.for_path(path),
+        options,
+    )
+    .map(|(frontmatter, _)| frontmatter)
+}
+
+/// Compiles `patterns` into one set.
+fn glob_set(patterns: &[String]) -> Result<GlobSet> {
+    let mut builder = GlobSetBuilder::new();
+    for pattern in patterns {
+        let glob = Glob::new(pattern).map_err(|e| {
+            Error::Other(format!("Invalid glob `{pattern}`: {e}"))
+        })?;
+        let _ = builder.add(glob);
+    }
+    builder.build().map_err(|e| Error::Other(e.to_string()))
+}
+
+/// Lists the files under `root` to extract, as relative keys with
+/// their full paths, in no particular order.
+async fn walk(
+    root: &Path,
+    include: &GlobSet,
+    exclude: &GlobSet,
+) -> Result<Vec<(String, PathBuf)>> {
+    let mut files = Vec::new();
+    let mut pending = vec![root.to_path_buf()];
+
+    while let Some(dir) = pending.pop() {
+        let read_error = |e| Error::IoError {
+            context: format!(
+                "Failed to read directory `{}`",
+                dir.display()
+            ),
+            source: Arc::new(e),
+        };
+        let mut entries =
+            tokio::fs::read_dir(&dir).await.map_err(read_error)?;
+        while let Some(entry) =
+            entries.next_entry().await.map_err(read_error)?
+        {
+            let path = entry.path();
+            let Some(key) = relative_key(root, &path) else {
+                continue;
+            };
+            if exclude.is_match(&key) {
+                continue;
+            }
+            let file_type =
+                entry.file_type().await.map_err(read_error)?;
+            if file_type.is_dir() {
+                pending.push(path);
+            } else if file_type.is_file() && include.is_match(&key) {
+                files.push((key, path));
+            }
+        }
+    }
+
+    Ok(files)
+}
+
+/// Returns `path` relative to `root`, joined with `/`, or `None` if a
+/// component is not valid UTF-8.
+fn relative_key(root: &Path, path: &Path) -> Option<String> {
+    let relative = path.strip_prefix(root).ok()?;
+    let parts = relative
+        .components()
+        .map(|component| component.as_os_str().to_str())
+        .collect::<Option<Vec<_>>>()?;
+    Some(parts.join("/"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    /// Creates `files` under a new temporary directory.
+    fn tree(files: &[(&str, &str)]) -> TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        for (path, content) in files {
+            let path = dir.path().join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, content).unwrap();
+        }
+        dir
+    }
+
+    mod extract_dir_tests {
+        use super::*;
+
+        #[tokio::test(flavor = "multi_thread")]
+        async fn test_extracts_nested_files_by_relative_path() {
+            let dir = tree(&[
+                ("index.md", "---\ntitle: Home\n---\n"),
+                ("posts/a.md", "+++\ntitle = \"A\"\n+++\n"),
+                ("posts/2024/b.md", "{\"title\": \"B\"}\n"),
+                ("notes.txt", "---\ntitle: Ignored\n---\n"),
+            ]);
+
+            let report =
+                extract_dir(dir.path(), &BatchOptions::default())
+                    .await
+                    .unwrap();
+
+            assert!(report.is_success());
+            let keys: Vec<_> =
+                report.frontmatter.keys().map(String::as_str).collect();
+            assert_eq!(
+                keys,
+                ["index.md", "posts/2024/b.md", "posts/a.md"]
+            );
+            assert_eq!(
+                report.frontmatter["posts/a.md"]
+                    .get("title")
+                    .and_then(Value::as_str),
+                Some("A")
+            );
+        }
+
+        #[tokio::test]
+        async fn test_extracts_org_and_asciidoc_headers() {
+            let dir = tree(&[
+                ("guide.org", "#+TITLE: Guide\n\n* Heading\n"),
+                ("manual.adoc", "= Manual\n:version: 2\n\nText\n"),
+            ]);
+
+            let report =
+                extract_dir(dir.path(), &BatchOptions::default())
+                    .await
+                    .unwrap();
+
+            assert!(report.is_success());
+            for (path, title) in
+                [("guide.org", "Guide"), ("manual.adoc", "Manual")]
+            {
+                assert_eq!(
+                    report.frontmatter[path]
+                        .get("title")
+                        .and_then(Value::as_str),
+                    Some(title)
+                );
+            }
+        }
+
+        #[tokio::test]
+        async fn test_collects_errors_separately() {
+            let dir = tree(&[
+                ("good.md", "---\ntitle: Good\n---\n"),
+                ("plain.md", "No front matter here"),
+                ("broken.md", "---\ntitle: [unclosed\n---\n"),
+            ]);
+            let options = BatchOptions {
+                concurrency: NonZeroUsize::MIN,
+                ..BatchOptions::default()
+            };
+
+            let report =
+                extract_dir(dir.path(), &options).await.unwrap();
+
+            assert!(!report.is_success());
+            assert_eq!(report.frontmatter.len(), 1);
+            assert!(report.frontmatter.contains_key("good.md"));
+            assert!(report.errors.contains_key("plain.md"));
+            assert!(report.errors.contains_key("broken.md"));
+        }
+
+        #[tokio::test]
+        async fn test_include_and_exclude_globs() {
+            let dir = tree(&[
+                ("a.md", "---\ntitle: A\n---\n"),
+                ("page.mdx", "---\ntitle: Page\n---\n"),
+                ("drafts/b.md", "---\ntitle: B\n---\n"),
+                ("posts/drafts/c.md", "---\ntitle: C\n---\n"),
+            ]);
+            let options = BatchOptions {
+                include: vec!["*.md".to_string()],
+                exclude: vec!["drafts".to_string()],
+                ..BatchOptions::default()
+            };
+
+            let report =
+                extract_dir(dir.path(), &options).await.unwrap();
+
+            let keys: Vec<_> =
+                report.frontmatter.keys().map(String::as_str).collect();
+            assert_eq!(keys, ["a.md", "posts/drafts/c.md"]);
+        }
+
+        #[tokio::test]
+        async fn test_custom_delimiters() {
+            let dir =
+                tree(&[("a.md", ";;;\n{\"title\": \"A\"}\n;;;\nBody")]);
+            let options = BatchOptions {
+                delimiters: Delimiters::extended(),
+                ..BatchOptions::default()
+            };
+
+            let report =
+                extract_dir(dir.path(), &options).await.unwrap();
+
+            assert!(report.frontmatter.contains_key("a.md"));
+        }
+
+        #[tokio::test]
+        async fn test_parse_options_apply_to_every_file() {
+            let dir = tree(&[
+                ("flat.md", "---\ntitle: A\n---\n"),
+                ("nested.md", "---\nauthor:\n  name: B\n---\n"),
+            ]);
+            let options = BatchOptions {
+                parse_options: ParseOptions::builder()
+                    .max_depth(1)
+                    .build()
+                    .unwrap(),
+                ..BatchOptions::default()
+            };
+
+            let report =
+                extract_dir(dir.path(), &options).await.unwrap();
+
+            assert!(report.frontmatter.contains_key("flat.md"));
+            assert!(matches!(
+                report.errors["nested.md"],
+                Error::NestingTooDeep { .. }
+            ));
+        }
+
+        #[tokio::test]
+        async fn test_invalid_glob() {
+            let dir = tree(&[]);
+            let options = BatchOptions {
+                include: vec!["[".to_string()],
+                ..BatchOptions::default()
+            };
+
+            let result = extract_dir(dir.path(), &options).await;
+
+            assert!(matches!(result, Err(Error::Other(_))));
+        }
+
+        #[tokio::test]
+        async fn test_missing_root() {
+            let dir = tree(&[]);
+            let result = extract_dir(
+                dir.path().join("missing"),
+                &BatchOptions::default(),
+            )
+            .await;
+
+            let Err(Error::IoError { context, source }) = result else {
+                panic!("expected an I/O error, got {result:?}");
+            };
+            assert!(context.contains("missing"));
+            assert_eq!(source.kind(), std::io::ErrorKind::NotFound);
+        }
+    }
+
+    mod output_tests {
+        use super::*;
+
+        fn report() -> BatchReport {
+            let mut report = BatchReport::default();
+            for (path, title) in [("b.md", "B"), ("a.md", "A")] {
+                let mut frontmatter = Frontmatter::new();
+                let _ = frontmatter.insert(
+                    "title".to_string(),
+                    Value::String(title.to_string()),
+                );
+                let _ = report
+                    .frontmatter
+                    .insert(path.to_string(), frontmatter);
+            }
+            report
+        }
+
+        #[test]
+        fn test_to_document() {
+            let document = report().to_document();
+
+            let keys: Vec<_> =
+                document.0.keys().map(String::as_str).collect();
+            assert_eq!(keys, ["a.md", "b.md"]);
+            assert_eq!(
+                document
+                    .get("a.md")
+                    .and_then(Value::as_object)
+                    .and_then(|fm| fm.get("title"))
+                    .and_then(Value::as_str),
+                Some("A")
+            );
+        }
+
+        #[test]
+        fn test_to_ndjson() {
+            let ndjson = report().to_ndjson().unwrap();
+
+            assert_eq!(
+                ndjson,
+                "{\"a.md\":{\"title\":\"A\"}}\n{\"b.md\":{\"title\":\"B\"}}\n"
+            );
+        }
+    }
+}
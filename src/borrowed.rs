@@ -0,0 +1,662 @@
+// borrowed.rs
+
+//! # Borrowed Front Matter Module
+//!
+//! [`FrontmatterRef`] and [`ValueRef`] are borrowed counterparts of
+//! `Frontmatter` and `Value`. Their strings and keys point into the
+//! source text wherever the format allows it, so a tool that scans many
+//! documents for a few keys does not copy the rest.
+//!
+//! JSON and TOML strings are borrowed unless they contain escape
+//! sequences, which have to be decoded into a new string. YAML is parsed
+//! into owned values, so a YAML view owns all of its strings. A view
+//! converts to the owned types on demand with
+//! [`FrontmatterRef::into_frontmatter`] or [`ValueRef::into_value`].
+//!
+//! ## Usage Example
+//!
+//! ```rust
+//! use frontmatter_gen::extract_ref;
+//!
+//! let content = "+++\ntitle = \"My Post\"\ndraft = false\n+++\nBody";
+//! let (frontmatter, body) = extract_ref(content)?;
+//!
+//! let title = frontmatter.get("title").unwrap();
+//! assert_eq!(title.as_str(), Some("My Post"));
+//! assert!(title.is_borrowed());
+//! assert_eq!(body, "Body");
+//!
+//! let owned = frontmatter.into_frontmatter();
+//! assert_eq!(owned.get("draft").and_then(|v| v.as_bool()), Some(false));
+//! # Ok::<(), frontmatter_gen::Error>(())
+//! ```
+
+use indexmap::IndexMap;
+use serde::{
+    de::{self, MapAccess, SeqAccess, Visitor},
+    Deserialize, Deserializer,
+};
+use std::{borrow::Cow, fmt, sync::Arc};
+
+use crate::{
+    error::Error,
+    parser::{self, locate, ParseOptions},
+    types::{
+        Datetime, Format, Frontmatter, Value, TAGGED_TAG_FIELD,
+        TAGGED_VALUE_FIELD, TOML_DATETIME_FIELD,
+    },
+};
+
+/// A front matter value that may borrow its strings from the source.
+///
+/// The variants mirror those of [`Value`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum ValueRef<'a> {
+    /// Represents a null value.
+    #[default]
+    Null,
+    /// Represents a string value, borrowed where possible.
+    String(Cow<'a, str>),
+    /// Represents a signed integer value.
+    Integer(i64),
+    /// Represents a floating-point value.
+    Float(f64),
+    /// Represents a date, time or date-time value.
+    DateTime(Datetime),
+    /// Represents a boolean value.
+    Boolean(bool),
+    /// Represents an array of values.
+    Array(Vec<ValueRef<'a>>),
+    /// Represents an object.
+    Object(Box<FrontmatterRef<'a>>),
+    /// Represents a tagged value, containing a tag and a value.
+    Tagged(Cow<'a, str>, Box<ValueRef<'a>>),
+}
+
+impl<'a> ValueRef<'a> {
+    /// Returns the value as a string slice, if it is of type `String`.
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            ValueRef::String(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    /// Returns the value as an integer, if it is of type `Integer`.
+    pub const fn as_i64(&self) -> Option<i64> {
+        match self {
+            ValueRef::Integer(i) => Some(*i),
+            _ => None,
+        }
+    }
+
+    /// Returns the value as a float, if it is numeric.
+    #[allow(clippy::cast_precision_loss)]
+    pub const fn as_f64(&self) -> Option<f64> {
+        match self {
+            ValueRef::Float(f) => Some(*f),
+            ValueRef::Integer(i) => Some(*i as f64),
+            _ => None,
+        }
+    }
+
+    /// Returns the value as a boolean, if it is of type `Boolean`.
+    pub const fn as_bool(&self) -> Option<bool> {
+        match self {
+            ValueRef::Boolean(b) => Some(*b),
+            _ => None,
+        }
+    }
+
+    /// Returns the value as a slice of values, if it is an `Array`.
+    pub fn as_array(&self) -> Option<&[ValueRef<'a>]> {
+        match self {
+            ValueRef::Array(arr) => Some(arr),
+            _ => None,
+        }
+    }
+
+    /// Returns the value as an object, if it is of type `Object`.
+    pub fn as_object(&self) -> Option<&FrontmatterRef<'a>> {
+        match self {
+            ValueRef::Object(obj) => Some(obj),
+            _ => None,
+        }
+    }
+
+    /// Returns `true` if the value is `Null`.
+    pub const fn is_null(&self) -> bool {
+        matches!(self, ValueRef::Null)
+    }
+
+    /// Returns `true` if the value is a string that points into the
+    /// source text.
+    pub const fn is_borrowed(&self) -> bool {
+        matches!(self, ValueRef::String(Cow::Borrowed(_)))
+    }
+
+    /// Converts the value into an owned [`Value`], copying any borrowed
+    /// strings.
+    #[must_use]
+    pub fn into_value(self) -> Value {
+        match self {
+            ValueRef::Null => Value::Null,
+            ValueRef::String(s) => Value::String(s.into_owned()),
+            ValueRef::Integer(i) => Value::Integer(i),
+            ValueRef::Float(f) => Value::Float(f),
+            ValueRef::DateTime(dt) => Value::DateTime(dt),
+            ValueRef::Boolean(b) => Value::Boolean(b),
+            ValueRef::Array(arr) => Value::Array(
+                arr.into_iter().map(ValueRef::into_value).collect(),
+            ),
+            ValueRef::Object(obj) => {
+                Value::Object(Box::new(obj.into_frontmatter()))
+            }
+            ValueRef::Tagged(tag, value) => Value::Tagged(
+                tag.into_owned(),
+                Box::new(value.into_value()),
+            ),
+        }
+    }
+}
+
+impl From<ValueRef<'_>> for Value {
+    fn from(value: ValueRef<'_>) -> Self {
+        value.into_value()
+    }
+}
+
+impl From<Value> for ValueRef<'_> {
+    fn from(value: Value) -> Self {
+        match value {
+            Value::Null => ValueRef::Null,
+            Value::String(s) => ValueRef::String(Cow::Owned(s)),
+            Value::Integer(i) => ValueRef::Integer(i),
+            Value::Float(f) => ValueRef::Float(f),
+            Value::DateTime(dt) => ValueRef::DateTime(dt),
+            Value::Boolean(b) => ValueRef::Boolean(b),
+            Value::Array(arr) => ValueRef::Array(
+                arr.into_iter().map(Into::into).collect(),
+            ),
+            Value::Object(obj) => {
+                ValueRef::Object(Box::new((*obj).into()))
+            }
+            Value::Tagged(tag, value) => ValueRef::Tagged(
+                Cow::Owned(tag),
+                Box::new((*value).into()),
+            ),
+        }
+    }
+}
+
+/// Front matter whose keys and strings may borrow from the source.
+///
+/// # Examples
+///
+/// ```rust
+/// use frontmatter_gen::{borrowed::FrontmatterRef, Format};
+///
+/// let raw = r#"{"title": "Post", "tags": ["a", "b\u0021"]}"#;
+/// let frontmatter = FrontmatterRef::parse(raw, Format::Json)?;
+///
+/// let tags = frontmatter.get("tags").and_then(|v| v.as_array()).unwrap();
+/// assert!(tags[0].is_borrowed());
+/// // Escapes are decoded into an owned string.
+/// assert_eq!(tags[1].as_str(), Some("b!"));
+/// assert!(!tags[1].is_borrowed());
+/// # Ok::<(), frontmatter_gen::Error>(())
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FrontmatterRef<'a>(pub IndexMap<Cow<'a, str>, ValueRef<'a>>);
+
+impl<'a> FrontmatterRef<'a> {
+    /// Parses raw front matter in the given format, borrowing strings
+    /// from `raw` where the format allows it.
+    ///
+    /// The same structure limits apply as for [`parser::parse`].
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error` if the front matter is not valid in `format`, the
+    /// format is unsupported, or the structure exceeds the limits.
+    pub fn parse(raw: &'a str, format: Format) -> Result<Self, Error> {
+        Self::parse_with_options(raw, format, &ParseOptions::default())
+    }
+
+    /// Parses raw front matter under the limits of `options`, borrowing
+    /// strings from `raw` where the format allows it.
+    ///
+    /// The structure limits apply when `options.validate` is set, as for
+    /// [`parser::parse_with_options`].
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error` if the front matter is not valid in `format`, the
+    /// format is unsupported, or the structure exceeds the limits.
+    pub fn parse_with_options(
+        raw: &'a str,
+        format: Format,
+        options: &ParseOptions,
+    ) -> Result<Self, Error> {
+        let trimmed = raw.trim();
+        let frontmatter: Self = match format {
+            Format::Json => serde_json::from_str(trimmed)
+                .map_err(|e| Error::JsonParseError(Arc::new(e))),
+            Format::Toml => {
+                toml::from_str(trimmed).map_err(Error::TomlParseError)
+            }
+            // Parse YAML through its own value type so that tags and
+            // timestamps keep their meaning, and the other formats
+            // through the parser, which knows their shapes.
+            Format::Yaml
+            | Format::Json5
+            | Format::Ron
+            | Format::Org
+            | Format::AsciiDoc
+            | Format::Unsupported => {
+                return parser::parse_with_options(
+                    raw,
+                    format,
+                    Some(*options),
+                )
+                .map(Into::into);
+            }
+        }
+        .map_err(|e| locate(e, raw, trimmed))?;
+
+        if options.validate {
+            frontmatter
+                .check(options.max_depth.get(), options.max_keys)?;
+        }
+        Ok(frontmatter)
+    }
+
+    /// Returns the value of a key, if present.
+    pub fn get(&self, key: &str) -> Option<&ValueRef<'a>> {
+        self.0.get(key)
+    }
+
+    /// Returns `true` if the front matter contains the key.
+    pub fn contains_key(&self, key: &str) -> bool {
+        self.0.contains_key(key)
+    }
+
+    /// Returns the number of top-level keys.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns `true` if there are no keys.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Returns an iterator over the entries, in source order.
+    pub fn iter(
+        &self,
+    ) -> indexmap::map::Iter<'_, Cow<'a, str>, ValueRef<'a>> {
+        self.0.iter()
+    }
+
+    /// Converts the view into an owned [`Frontmatter`], copying any
+    /// borrowed keys and strings.
+    #[must_use]
+    pub fn into_frontmatter(self) -> Frontmatter {
+        Frontmatter(
+            self.0
+                .into_iter()
+                .map(|(key, value)| {
+                    (key.into_owned(), value.into_value())
+                })
+                .collect(),
+        )
+    }
+
+    /// Checks the nesting depth and the number of keys, as
+    /// [`parser::validate_frontmatter`] does for `Frontmatter`.
+    fn check(
+        &self,
+        max_depth: usize,
+        max_keys: usize,
+    ) -> Result<(), Error> {
+        if self.len() > max_keys {
+            return Err(Error::ContentTooLarge {
+                size: self.len(),
+                max: max_keys,
+            });
+        }
+
+        let mut stack: Vec<(&ValueRef<'a>, usize)> =
+            self.0.values().map(|value| (value, 1)).collect();
+        while let Some((value, depth)) = stack.pop() {
+            if depth > max_depth {
+                return Err(Error::NestingTooDeep {
+                    depth,
+                    max: max_depth,
+                });
+            }
+            match value {
+                ValueRef::Array(arr) => {
+                    stack.extend(arr.iter().map(|v| (v, depth + 1)));
+                }
+                ValueRef::Object(obj) => {
+                    stack
+                        .extend(obj.0.values().map(|v| (v, depth + 1)));
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+}
+
+impl From<FrontmatterRef<'_>> for Frontmatter {
+    fn from(frontmatter: FrontmatterRef<'_>) -> Self {
+        frontmatter.into_frontmatter()
+    }
+}
+
+impl From<Frontmatter> for FrontmatterRef<'_> {
+    fn from(frontmatter: Frontmatter) -> Self {
+        Self(
+            frontmatter
+                .into_iter()
+                .map(|(key, value)| (Cow::Owned(key), value.into()))
+                .collect(),
+        )
+    }
+}
+
+impl<'a, 'b> IntoIterator for &'b FrontmatterRef<'a> {
+    type Item = (&'b Cow<'a, str>, &'b ValueRef<'a>);
+    type IntoIter = indexmap::map::Iter<'b, Cow<'a, str>, ValueRef<'a>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter()
+    }
+}
+
+impl<'de: 'a, 'a> Deserialize<'de> for ValueRef<'a> {
+    fn deserialize<D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(ValueRefVisitor)
+    }
+}
+
+impl<'de: 'a, 'a> Deserialize<'de> for FrontmatterRef<'a> {
+    fn deserialize<D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Self, D::Error> {
+        deserializer.deserialize_map(FrontmatterRefVisitor)
+    }
+}
+
+/// A map key or string that borrows from the input when it can.
+struct Str<'a>(Cow<'a, str>);
+
+impl<'de: 'a, 'a> Deserialize<'de> for Str<'a> {
+    fn deserialize<D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Self, D::Error> {
+        deserializer.deserialize_str(StrVisitor)
+    }
+}
+
+/// Builds a `Str`, keeping borrowed input borrowed.
+struct StrVisitor;
+
+impl<'de> Visitor<'de> for StrVisitor {
+    type Value = Str<'de>;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a string")
+    }
+
+    fn visit_borrowed_str<E>(self, s: &'de str) -> Result<Str<'de>, E> {
+        Ok(Str(Cow::Borrowed(s)))
+    }
+
+    fn visit_str<E>(self, s: &str) -> Result<Str<'de>, E> {
+        Ok(Str(Cow::Owned(s.to_string())))
+    }
+
+    fn visit_string<E>(self, s: String) -> Result<Str<'de>, E> {
+        Ok(Str(Cow::Owned(s)))
+    }
+}
+
+/// Builds a `ValueRef` from any self-describing format, as
+/// `ValueVisitor` builds a `Value`.
+struct ValueRefVisitor;
+
+impl<'de> Visitor<'de> for ValueRefVisitor {
+    type Value = ValueRef<'de>;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a front matter value")
+    }
+
+    fn visit_unit<E>(self) -> Result<ValueRef<'de>, E> {
+        Ok(ValueRef::Null)
+    }
+
+    fn visit_none<E>(self) -> Result<ValueRef<'de>, E> {
+        Ok(ValueRef::Null)
+    }
+
+    fn visit_some<D: Deserializer<'de>>(
+        self,
+        deserializer: D,
+    ) -> Result<ValueRef<'de>, D::Error> {
+        ValueRef::deserialize(deserializer)
+    }
+
+    fn visit_bool<E>(self, b: bool) -> Result<ValueRef<'de>, E> {
+        Ok(ValueRef::Boolean(b))
+    }
+
+    fn visit_i64<E>(self, i: i64) -> Result<ValueRef<'de>, E> {
+        Ok(ValueRef::Integer(i))
+    }
+
+    // Integers beyond `i64` become floats, as they do for `Value`.
+    #[allow(clippy::cast_precision_loss)]
+    fn visit_u64<E>(self, u: u64) -> Result<ValueRef<'de>, E> {
+        Ok(i64::try_from(u)
+            .map_or(ValueRef::Float(u as f64), ValueRef::Integer))
+    }
+
+    fn visit_f64<E>(self, f: f64) -> Result<ValueRef<'de>, E> {
+        Ok(ValueRef::Float(f))
+    }
+
+    fn visit_borrowed_str<E>(
+        self,
+        s: &'de str,
+    ) -> Result<ValueRef<'de>, E> {
+        Ok(ValueRef::String(Cow::Borrowed(s)))
+    }
+
+    fn visit_str<E>(self, s: &str) -> Result<ValueRef<'de>, E> {
+        Ok(ValueRef::String(Cow::Owned(s.to_string())))
+    }
+
+    fn visit_string<E>(self, s: String) -> Result<ValueRef<'de>, E> {
+        Ok(ValueRef::String(Cow::Owned(s)))
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(
+        self,
+        mut seq: A,
+    ) -> Result<ValueRef<'de>, A::Error> {
+        let mut arr = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+        while let Some(value) = seq.next_element()? {
+            arr.push(value);
+        }
+        Ok(ValueRef::Array(arr))
+    }
+
+    fn visit_map<A: MapAccess<'de>>(
+        self,
+        mut map: A,
+    ) -> Result<ValueRef<'de>, A::Error> {
+        let Some(Str(first)) = map.next_key()? else {
+            return Ok(ValueRef::Object(Box::default()));
+        };
+
+        // TOML datetimes arrive as a map with a single private field.
+        if first == TOML_DATETIME_FIELD {
+            let Str(s) = map.next_value()?;
+            return s.parse().map(ValueRef::DateTime).map_err(|_| {
+                de::Error::invalid_value(
+                    de::Unexpected::Str(&s),
+                    &"an RFC 3339 datetime",
+                )
+            });
+        }
+
+        // Tagged values arrive as a map with a private tag field
+        // followed by a private value field.
+        if first == TAGGED_TAG_FIELD {
+            let Str(tag) = map.next_value()?;
+            return match map.next_key()? {
+                Some(Str(key)) if key == TAGGED_VALUE_FIELD => Ok(
+                    ValueRef::Tagged(tag, Box::new(map.next_value()?)),
+                ),
+                _ => Err(de::Error::missing_field(TAGGED_VALUE_FIELD)),
+            };
+        }
+
+        let mut obj = FrontmatterRef::default();
+        let _ = obj.0.insert(first, map.next_value()?);
+        while let Some((Str(key), value)) = map.next_entry()? {
+            let _ = obj.0.insert(key, value);
+        }
+        Ok(ValueRef::Object(Box::new(obj)))
+    }
+}
+
+/// Builds a `FrontmatterRef` from a map.
+struct FrontmatterRefVisitor;
+
+impl<'de> Visitor<'de> for FrontmatterRefVisitor {
+    type Value = FrontmatterRef<'de>;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a front matter map")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(
+        self,
+        mut map: A,
+    ) -> Result<FrontmatterRef<'de>, A::Error> {
+        let mut fm = FrontmatterRef(IndexMap::with_capacity(
+            map.size_hint().unwrap_or(0),
+        ));
+        while let Some((Str(key), value)) = map.next_entry()? {
+            let _ = fm.0.insert(key, value);
+        }
+        Ok(fm)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod parse_tests {
+        use super::*;
+
+        #[test]
+        fn test_json_borrows_unescaped_strings() {
+            let raw =
+                r#"{"title": "Post", "quote": "say \"hi\"", "n": 3}"#;
+            let frontmatter =
+                FrontmatterRef::parse(raw, Format::Json).unwrap();
+
+            let (key, title) = frontmatter.iter().next().unwrap();
+            assert!(matches!(key, Cow::Borrowed("title")));
+            assert!(title.is_borrowed());
+            let quote = frontmatter.get("quote").unwrap();
+            assert_eq!(quote.as_str(), Some("say \"hi\""));
+            assert!(!quote.is_borrowed());
+            assert_eq!(
+                frontmatter.get("n").and_then(ValueRef::as_i64),
+                Some(3)
+            );
+        }
+
+        #[test]
+        fn test_toml_borrows_strings_and_keeps_datetimes() {
+            let raw = "title = 'C:\\path'\ndate = 2024-01-02\n[author]\nname = \"Jane\"\n";
+            let frontmatter =
+                FrontmatterRef::parse(raw, Format::Toml).unwrap();
+
+            let title = frontmatter.get("title").unwrap();
+            assert_eq!(title.as_str(), Some("C:\\path"));
+            assert!(title.is_borrowed());
+            assert!(matches!(
+                frontmatter.get("date"),
+                Some(ValueRef::DateTime(_))
+            ));
+            let author = frontmatter
+                .get("author")
+                .and_then(ValueRef::as_object)
+                .unwrap();
+            assert!(author.get("name").unwrap().is_borrowed());
+        }
+
+        #[test]
+        fn test_converts_to_the_same_owned_front_matter() {
+            for (raw, format) in [
+                (
+                    r#"{"a": [1, 2.5, null, true], "b": {"c": "d\n"}, "e": {}}"#,
+                    Format::Json,
+                ),
+                (
+                    "a = [1, 2.5, true]\nd = 1979-05-27T07:32:00Z\n[b]\nc = \"d\\n\"\n",
+                    Format::Toml,
+                ),
+                (
+                    "a: [1, 2.5, ~, true]\nb: { c: \"d\\n\" }\nt: !custom x\n",
+                    Format::Yaml,
+                ),
+            ] {
+                let borrowed = FrontmatterRef::parse(raw, format).unwrap();
+                let owned = parser::parse(raw, format).unwrap();
+                assert_eq!(borrowed.clone().into_frontmatter(), owned);
+                assert_eq!(FrontmatterRef::from(owned), borrowed);
+            }
+        }
+
+        #[test]
+        fn test_errors_and_limits() {
+            let err = FrontmatterRef::parse(
+                "{\n  \"a\": [1,\n}",
+                Format::Json,
+            )
+            .unwrap_err();
+            assert!(matches!(err.inner(), Error::JsonParseError(_)));
+            assert_eq!(err.context().and_then(|c| c.line), Some(3));
+
+            let nested =
+                format!("{}1{}", "[".repeat(40), "]".repeat(40));
+            let err = FrontmatterRef::parse(
+                &format!("{{\"a\": {nested}}}"),
+                Format::Json,
+            )
+            .unwrap_err();
+            assert!(matches!(
+                err,
+                Error::NestingTooDeep { max: 32, .. }
+            ));
+
+            assert!(FrontmatterRef::parse(
+                "a = 1",
+                Format::Unsupported
+            )
+            .is_err());
+        }
+    }
+}
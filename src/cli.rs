@@ -9,7 +9,8 @@
 //! ## Features
 //!
 //! - Command-line argument parsing using clap
-//! - Subcommands for different operations (extract, validate)
+//! - Subcommands for different operations (extract, validate, get, set,
+//!   diff)
 //! - Error handling and user-friendly messages
 //!
 //! ## Usage
@@ -20,13 +21,60 @@
 //!
 //! # Validate frontmatter
 //! cargo run --features="cli" validate input.md --required title,date
+//!
+//! # Validate frontmatter against a schema
+//! cargo run --features="cli" validate input.md --schema schema.toml
+//!
+//! # Reject `../` in path-like keys such as `image`, and YAML anchors
+//! cargo run --features="cli" validate input.md --policy path-traversal,yaml-anchor
+//!
+//! # Validate frontmatter against a JSON Schema
+//! cargo run --features="cli" validate input.md --json-schema schema.json
+//!
+//! # Report problems as SARIF for code scanning (or `json` for one
+//! # object per line)
+//! cargo run --features="cli" validate input.md --message-format sarif
+//!
+//! # Extract every Markdown file under a directory, one JSON line each
+//! cargo run --features="cli" extract content --recursive --format ndjson
+//!
+//! # Only some files, skipping drafts
+//! cargo run --features="cli" extract content -r --include 'posts/**' \
+//!     --exclude '**/drafts'
+//!
+//! # Recognise a custom fence, such as `;;;` around JSON
+//! cargo run --features="cli" extract input.md --delimiter 'json:;;;'
+//!
+//! # Read a nested value
+//! cargo run --features="cli" get input.md authors[0].email
+//!
+//! # Update a nested value in place
+//! cargo run --features="cli" set input.md seo.og.image cover.png
+//!
+//! # Show how the frontmatter of two files differs
+//! cargo run --features="cli" diff old.md new.md
+//!
+//! # Print the difference as a JSON Patch
+//! cargo run --features="cli" diff old.md new.md --json-patch
 //! ```
 
 use anyhow::{Context, Result};
-use clap::{Parser, Subcommand};
-use std::path::PathBuf;
-
-use crate::{extract, to_format, Format};
+use clap::{Parser, Subcommand, ValueEnum};
+use std::path::{Path, PathBuf};
+
+use crate::{
+    batch::{extract_dir, BatchOptions},
+    diagnostics::{self, Diagnostic, Diagnostics, Severity},
+    diff,
+    editor::Editor,
+    extract_with_delimiters_and_options,
+    extractor::{Delimiter, Delimiters},
+    json_schema, path,
+    schema::Schema,
+    to_format,
+    types::Plain,
+    Format, ParseOptions, SecurityPolicy, Value,
+};
 
 /// Command line arguments parser
 #[derive(Parser, Debug)]
@@ -41,17 +89,42 @@ pub struct Cli {
 enum Commands {
     /// Extract frontmatter from a file
     Extract {
-        /// Input file path
+        /// Input file path, or directory with `--recursive`
         #[arg(required = true)]
         input: PathBuf,
 
-        /// Output format (yaml, toml, json)
+        /// Output format (yaml, toml, json, json5, ron, org, asciidoc,
+        /// or ndjson with `--recursive`)
         #[arg(short, long, default_value = "yaml")]
         format: String,
 
         /// Output file path (optional)
         #[arg(short, long)]
         output: Option<PathBuf>,
+
+        /// Extract every matching file under the input directory
+        #[arg(short, long)]
+        recursive: bool,
+
+        /// Glob of files to extract with `--recursive` (default:
+        /// Markdown, MDX, Org and AsciiDoc files)
+        #[arg(long, requires = "recursive")]
+        include: Vec<String>,
+
+        /// Glob of files or directories to skip with `--recursive`
+        #[arg(long, requires = "recursive")]
+        exclude: Vec<String>,
+
+        /// Additional front matter fence, as `FORMAT:OPEN[:CLOSE...]`
+        #[arg(
+            long = "delimiter",
+            value_name = "FORMAT:OPEN[:CLOSE...]"
+        )]
+        delimiters: Vec<Delimiter>,
+
+        /// How to report problems
+        #[arg(long, value_enum, default_value_t = MessageFormat::Human)]
+        message_format: MessageFormat,
     },
 
     /// Validate frontmatter in a file
@@ -63,7 +136,104 @@ enum Commands {
         /// Required fields (comma-separated)
         #[arg(short, long)]
         required: Option<String>,
+
+        /// Schema file to validate against (TOML, YAML or JSON)
+        #[arg(short, long)]
+        schema: Option<PathBuf>,
+
+        /// JSON Schema file to validate against
+        #[arg(long)]
+        json_schema: Option<PathBuf>,
+
+        /// Additional front matter fence, as `FORMAT:OPEN[:CLOSE...]`
+        #[arg(
+            long = "delimiter",
+            value_name = "FORMAT:OPEN[:CLOSE...]"
+        )]
+        delimiters: Vec<Delimiter>,
+
+        /// Security policy rules to check, such as
+        /// `path-traversal,null-byte` or `strict`
+        #[arg(long, value_name = "RULES")]
+        policy: Option<SecurityPolicy>,
+
+        /// How to report problems
+        #[arg(long, value_enum, default_value_t = MessageFormat::Human)]
+        message_format: MessageFormat,
+    },
+
+    /// Print the value at a path, such as `authors[0].email`
+    Get {
+        /// Input file path
+        #[arg(required = true)]
+        input: PathBuf,
+
+        /// Dotted path to the value
+        #[arg(required = true)]
+        path: String,
+
+        /// Additional front matter fence, as `FORMAT:OPEN[:CLOSE...]`
+        #[arg(
+            long = "delimiter",
+            value_name = "FORMAT:OPEN[:CLOSE...]"
+        )]
+        delimiters: Vec<Delimiter>,
     },
+
+    /// Set the value at a path, editing the file in place
+    Set {
+        /// Input file path
+        #[arg(required = true)]
+        input: PathBuf,
+
+        /// Dotted path to the value
+        #[arg(required = true)]
+        path: String,
+
+        /// New value, as JSON or a plain scalar
+        #[arg(required = true)]
+        value: String,
+
+        /// Additional front matter fence, as `FORMAT:OPEN[:CLOSE...]`
+        #[arg(
+            long = "delimiter",
+            value_name = "FORMAT:OPEN[:CLOSE...]"
+        )]
+        delimiters: Vec<Delimiter>,
+    },
+
+    /// Show how the frontmatter of two files differs
+    Diff {
+        /// Original file path
+        #[arg(required = true)]
+        old: PathBuf,
+
+        /// Changed file path
+        #[arg(required = true)]
+        new: PathBuf,
+
+        /// Print an RFC 6902 JSON Patch instead of a list of changes
+        #[arg(long)]
+        json_patch: bool,
+
+        /// Additional front matter fence, as `FORMAT:OPEN[:CLOSE...]`
+        #[arg(
+            long = "delimiter",
+            value_name = "FORMAT:OPEN[:CLOSE...]"
+        )]
+        delimiters: Vec<Delimiter>,
+    },
+}
+
+/// How `extract` and `validate` report problems
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+enum MessageFormat {
+    /// Compiler-style messages on standard error
+    Human,
+    /// One JSON object per problem on standard output
+    Json,
+    /// A SARIF 2.1.0 log on standard output, for code scanning
+    Sarif,
 }
 
 impl Cli {
@@ -82,10 +252,71 @@ pub async fn process(&self) -> Result<()> {
                 input,
                 format,
                 output,
-            } => process_extract(input, format, output).await,
-            Commands::Validate { input, required } => {
-                process_validate(input, required).await
+                recursive,
+                include,
+                exclude,
+                delimiters,
+                message_format,
+            } => {
+                if *recursive {
+                    let globs = Globs { include, exclude };
+                    process_extract_dir(
+                        input,
+                        format,
+                        output,
+                        globs,
+                        delimiters,
+                        *message_format,
+                    )
+                    .await
+                } else {
+                    process_extract(
+                        input,
+                        format,
+                        output,
+                        delimiters,
+                        *message_format,
+                    )
+                    .await
+                }
+            }
+            Commands::Validate {
+                input,
+                required,
+                schema,
+                json_schema,
+                delimiters,
+                policy,
+                message_format,
+            } => {
+                process_validate(
+                    input,
+                    required,
+                    schema,
+                    json_schema,
+                    delimiters,
+                    policy.as_ref(),
+                    *message_format,
+                )
+                .await
             }
+            Commands::Get {
+                input,
+                path,
+                delimiters,
+            } => process_get(input, path, delimiters).await,
+            Commands::Set {
+                input,
+                path,
+                value,
+                delimiters,
+            } => process_set(input, path, value, delimiters).await,
+            Commands::Diff {
+                old,
+                new,
+                json_patch,
+                delimiters,
+            } => process_diff(old, new, *json_patch, delimiters).await,
         }
     }
 }
@@ -97,6 +328,8 @@ pub async fn process(&self) -> Result<()> {
 /// * `input` - Path to input file
 /// * `format` - Output format
 /// * `output` - Optional output file path
+/// * `delimiters` - Fences to recognise besides `---` and `+++`
+/// * `message_format` - How to report a failure to extract
 ///
 /// # Errors
 ///
@@ -109,6 +342,8 @@ async fn process_extract(
     input: &PathBuf,
     format: &str,
     output: &Option<PathBuf>,
+    delimiters: &[Delimiter],
+    message_format: MessageFormat,
 ) -> Result<()> {
     // Read input file
     let content =
@@ -117,14 +352,34 @@ async fn process_extract(
         })?;
 
     // Extract frontmatter
-    let (frontmatter, remaining) = extract(&content)
-        .with_context(|| "Failed to extract frontmatter")?;
+    let options = ParseOptions::builder().env().build()?;
+    let extracted = extract_with_delimiters_and_options(
+        &content,
+        &registry(delimiters).for_path(input),
+        &options,
+    );
+    let (frontmatter, remaining) =
+        extracted.map_err(|e| match message_format {
+            MessageFormat::Human => {
+                parse_failure(input, "Failed to extract frontmatter", e)
+            }
+            _ => {
+                let mut diagnostics = Diagnostics::new();
+                diagnostics.push(Diagnostic::from_error(&e));
+                report(message_format, input, &diagnostics);
+                anyhow::anyhow!("Failed to extract frontmatter")
+            }
+        })?;
 
     // Convert to specified format
     let output_format = match format.to_lowercase().as_str() {
         "yaml" => Format::Yaml,
         "toml" => Format::Toml,
         "json" => Format::Json,
+        "json5" => Format::Json5,
+        "ron" => Format::Ron,
+        "org" => Format::Org,
+        "asciidoc" | "adoc" => Format::AsciiDoc,
         _ => {
             return Err(anyhow::anyhow!(
                 "Unsupported format: {}",
@@ -162,50 +417,504 @@ async fn process_extract(
     Ok(())
 }
 
+/// Include and exclude globs of `extract --recursive`
+#[derive(Clone, Copy, Debug)]
+struct Globs<'a> {
+    include: &'a [String],
+    exclude: &'a [String],
+}
+
+/// Process extract command for every matching file under a directory
+///
+/// Files are extracted in parallel. The front matter of those that
+/// succeed is written as one document keyed by relative path, or as
+/// one JSON line per file with the `ndjson` format. Files that fail are
+/// reported separately, and make the command fail once the rest are
+/// written.
+///
+/// # Arguments
+///
+/// * `input` - Directory to walk
+/// * `format` - Output format
+/// * `output` - Optional output file path
+/// * `globs` - Files to extract, or the defaults if `include` is empty
+/// * `delimiters` - Fences to recognise besides `---` and `+++`
+/// * `message_format` - How to report files that fail
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - A glob is invalid or a directory cannot be read
+/// - Output format is unsupported
+/// - Output cannot be written
+/// - Any file fails to extract
+async fn process_extract_dir(
+    input: &Path,
+    format: &str,
+    output: &Option<PathBuf>,
+    globs: Globs<'_>,
+    delimiters: &[Delimiter],
+    message_format: MessageFormat,
+) -> Result<()> {
+    let output_format = match format.to_lowercase().as_str() {
+        "yaml" => Some(Format::Yaml),
+        "toml" => Some(Format::Toml),
+        "json" => Some(Format::Json),
+        "json5" => Some(Format::Json5),
+        "ron" => Some(Format::Ron),
+        "org" => Some(Format::Org),
+        "asciidoc" | "adoc" => Some(Format::AsciiDoc),
+        "ndjson" => None,
+        _ => {
+            return Err(anyhow::anyhow!(
+                "Unsupported format: {}",
+                format
+            ))
+        }
+    };
+
+    let mut options = BatchOptions {
+        exclude: globs.exclude.to_vec(),
+        delimiters: registry(delimiters),
+        parse_options: ParseOptions::builder().env().build()?,
+        ..BatchOptions::default()
+    };
+    if !globs.include.is_empty() {
+        options.include = globs.include.to_vec();
+    }
+    let report =
+        extract_dir(input, &options).await.with_context(|| {
+            format!(
+                "Failed to walk input directory: {}",
+                input.display()
+            )
+        })?;
+
+    let formatted = match output_format {
+        Some(output_format) => {
+            to_format(&report.to_document(), output_format)
+        }
+        None => report.to_ndjson(),
+    }
+    .with_context(|| "Failed to format frontmatter")?;
+
+    if let Some(output_path) = output {
+        tokio::fs::write(output_path, formatted)
+            .await
+            .with_context(|| {
+                format!(
+                    "Failed to write to output file: {}",
+                    output_path.display()
+                )
+            })?;
+        log::info!(
+            "Frontmatter of {} files extracted to `{}`",
+            report.frontmatter.len(),
+            output_path.display()
+        );
+    } else {
+        print!("{formatted}");
+    }
+
+    if report.is_success() {
+        return Ok(());
+    }
+    let failures: Vec<_> = report
+        .errors
+        .iter()
+        .map(|(path, error)| {
+            let mut diagnostics = Diagnostics::new();
+            diagnostics.push(Diagnostic::from_error(error));
+            (input.join(path).display().to_string(), diagnostics)
+        })
+        .collect();
+    report_files(
+        message_format,
+        failures
+            .iter()
+            .map(|(name, diagnostics)| (name.as_str(), diagnostics)),
+    );
+    Err(anyhow::anyhow!(
+        "Failed to extract frontmatter from {} of {} files",
+        report.errors.len(),
+        report.errors.len() + report.frontmatter.len()
+    ))
+}
+
 /// Process validate command
 ///
+/// Every problem is reported, not just the first: errors make the
+/// command fail, while warnings and info are printed to standard error
+/// and leave it successful.
+///
 /// # Arguments
 ///
 /// * `input` - Path to input file
 /// * `required` - Optional comma-separated list of required fields
+/// * `schema` - Optional path to a schema file
+/// * `json_schema` - Optional path to a JSON Schema file
+/// * `delimiters` - Fences to recognise besides `---` and `+++`
+/// * `policy` - Security policy to check, instead of the one from
+///   `SECURITY_POLICY`
+/// * `message_format` - How to report problems
 ///
 /// # Errors
 ///
 /// Returns an error if:
 /// - Input file cannot be read
 /// - Frontmatter parsing fails
-/// - Required fields are missing
+/// - The schema cannot be loaded
+/// - Any check reports an error, such as a missing required field or a
+///   schema violation
 async fn process_validate(
     input: &PathBuf,
     required: &Option<String>,
+    schema: &Option<PathBuf>,
+    json_schema: &Option<PathBuf>,
+    delimiters: &[Delimiter],
+    policy: Option<&SecurityPolicy>,
+    message_format: MessageFormat,
 ) -> Result<()> {
     // Read input file
     let content =
         tokio::fs::read_to_string(input).await.with_context(|| {
             format!("Failed to read input file: {}", input.display())
         })?;
+    let name = input.display().to_string();
 
-    // Extract frontmatter
-    let (frontmatter, _) = extract(&content)
-        .with_context(|| "Failed to extract frontmatter")?;
+    // Extract frontmatter, collecting problems instead of stopping
+    let mut options = ParseOptions::builder().env().build()?;
+    if let Some(policy) = policy {
+        options.security = *policy;
+    }
+    let (frontmatter, mut diagnostics) =
+        diagnostics::check_with_delimiters(
+            &content,
+            &options,
+            &registry(delimiters).for_path(input),
+        );
+    let Some(frontmatter) = frontmatter else {
+        if message_format == MessageFormat::Human {
+            return Err(anyhow::anyhow!(
+                "Failed to extract frontmatter: {}",
+                render_diagnostics(&diagnostics, &name)
+            ));
+        }
+        report(message_format, input, &diagnostics);
+        return Err(anyhow::anyhow!("Failed to extract frontmatter"));
+    };
 
     // Validate required fields
     if let Some(required_fields) = required {
-        let fields: Vec<&str> = required_fields.split(',').collect();
-        for field in fields {
-            if !frontmatter.contains_key(field) {
-                return Err(anyhow::anyhow!(
-                    "Missing required field: {}",
-                    field
-                ));
-            }
+        diagnostics
+            .check_required(&frontmatter, required_fields.split(','));
+    }
+
+    // Validate against the schemas
+    if let Some(schema_path) = schema {
+        let schema =
+            Schema::from_file_with_options(schema_path, &options)
+                .with_context(|| {
+                    format!(
+                        "Failed to load schema: {}",
+                        schema_path.display()
+                    )
+                })?;
+        diagnostics.check_schema(&schema, &frontmatter);
+    }
+    if let Some(schema_path) = json_schema {
+        let schema =
+            json_schema::from_file(schema_path).with_context(|| {
+                format!(
+                    "Failed to load JSON Schema: {}",
+                    schema_path.display()
+                )
+            })?;
+        diagnostics.check_schema(&schema, &frontmatter);
+    }
+
+    if diagnostics.has_errors() {
+        let summary = format!(
+            "Validation failed with {} violation(s)",
+            diagnostics.count(Severity::Error)
+        );
+        if message_format == MessageFormat::Human {
+            return Err(anyhow::anyhow!(
+                "{summary}:\n{}",
+                render_diagnostics(&diagnostics, &name)
+            ));
         }
+        report(message_format, input, &diagnostics);
+        return Err(anyhow::anyhow!(summary));
+    }
+
+    report(message_format, input, &diagnostics);
+    if message_format == MessageFormat::Human {
+        println!("Validation successful!");
+    }
+    Ok(())
+}
+
+/// Builds a registry of the default fences plus `delimiters`.
+fn registry(delimiters: &[Delimiter]) -> Delimiters {
+    let mut registry = Delimiters::default();
+    for delimiter in delimiters {
+        let _ = registry.register(delimiter.clone());
+    }
+    registry
+}
+
+/// Process get command
+///
+/// Strings are printed as they are; other values are printed as JSON.
+///
+/// # Arguments
+///
+/// * `input` - Path to input file
+/// * `path` - Dotted path to the value
+/// * `delimiters` - Fences to recognise besides `---` and `+++`
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - Input file cannot be read
+/// - Frontmatter parsing fails
+/// - The path is invalid or does not lead to a value
+async fn process_get(
+    input: &PathBuf,
+    path: &str,
+    delimiters: &[Delimiter],
+) -> Result<()> {
+    // Read input file
+    let content =
+        tokio::fs::read_to_string(input).await.with_context(|| {
+            format!("Failed to read input file: {}", input.display())
+        })?;
+
+    // Read the format from the fence, as `set` does
+    let options = ParseOptions::builder().env().build()?;
+    let frontmatter = Editor::with_delimiters_and_options(
+        &content,
+        &registry(delimiters).for_path(input),
+        &options,
+    )
+    .and_then(|editor| editor.frontmatter())
+    .map_err(|e| {
+        parse_failure(input, "Failed to extract frontmatter", e)
+    })?;
+
+    let _ = path::parse(path)?;
+    let value = frontmatter
+        .get_path(path)
+        .ok_or_else(|| crate::Error::KeyNotFound(path.to_string()))?;
+    match value {
+        Value::String(s) => println!("{s}"),
+        _ => println!("{}", serde_json::to_string(&Plain(value))?),
     }
+    Ok(())
+}
+
+/// Process set command
+///
+/// The file is edited in place with [`Editor::set_path`], so comments
+/// and formatting outside the changed value are kept.
+///
+/// # Arguments
+///
+/// * `input` - Path to input file
+/// * `path` - Dotted path to the value
+/// * `value` - New value, parsed as JSON if possible and as a plain
+///   scalar otherwise
+/// * `delimiters` - Fences to recognise besides `---` and `+++`
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - Input file cannot be read or written
+/// - Frontmatter parsing fails
+/// - The path is invalid or passes through a scalar
+/// - The entry cannot be rewritten in place
+async fn process_set(
+    input: &PathBuf,
+    path: &str,
+    value: &str,
+    delimiters: &[Delimiter],
+) -> Result<()> {
+    // Read input file
+    let content =
+        tokio::fs::read_to_string(input).await.with_context(|| {
+            format!("Failed to read input file: {}", input.display())
+        })?;
+
+    let options = ParseOptions::builder().env().build()?;
+    let mut editor = Editor::with_delimiters_and_options(
+        &content,
+        &registry(delimiters).for_path(input),
+        &options,
+    )
+    .map_err(|e| {
+        parse_failure(input, "Failed to extract frontmatter", e)
+    })?;
+    editor.set_path(path, &parse_value_arg(value))?;
+
+    tokio::fs::write(input, editor.as_str())
+        .await
+        .with_context(|| {
+            format!("Failed to write to file: {}", input.display())
+        })?;
+    log::info!("Set `{}` in `{}`", path, input.display());
+    Ok(())
+}
 
-    println!("Validation successful!");
+/// Process diff command
+///
+/// Each change is printed on its own line: `+` for additions, `-` for
+/// removals and `~` for changed values. Nothing is printed when the
+/// frontmatter is the same.
+///
+/// # Arguments
+///
+/// * `old` - Path to the original file
+/// * `new` - Path to the changed file
+/// * `json_patch` - Whether to print a JSON Patch instead
+/// * `delimiters` - Fences to recognise besides `---` and `+++`
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - Either file cannot be read
+/// - Frontmatter parsing fails
+async fn process_diff(
+    old: &PathBuf,
+    new: &PathBuf,
+    json_patch: bool,
+    delimiters: &[Delimiter],
+) -> Result<()> {
+    let options = ParseOptions::builder().env().build()?;
+    let registry = registry(delimiters);
+    let mut frontmatter = Vec::with_capacity(2);
+    for input in [old, new] {
+        let content = tokio::fs::read_to_string(input)
+            .await
+            .with_context(|| {
+                format!(
+                    "Failed to read input file: {}",
+                    input.display()
+                )
+            })?;
+        let (fm, _) = extract_with_delimiters_and_options(
+            &content,
+            &registry.for_path(input),
+            &options,
+        )
+        .map_err(|e| {
+            parse_failure(
+                input,
+                &format!(
+                    "Failed to extract frontmatter: {}",
+                    input.display()
+                ),
+                e,
+            )
+        })?;
+        frontmatter.push(fm);
+    }
+
+    let changes = diff::diff(&frontmatter[0], &frontmatter[1]);
+    if json_patch {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&changes.to_json_patch())?
+        );
+    } else {
+        print!("{changes}");
+    }
     Ok(())
 }
 
+/// Prints the diagnostics found in `input`.
+///
+/// Human-readable diagnostics go to standard error, and only if there
+/// are any. JSON and SARIF go to standard output, where a SARIF log is
+/// printed even when it has no results.
+fn report(
+    message_format: MessageFormat,
+    input: &Path,
+    diagnostics: &Diagnostics,
+) {
+    let name = input.display().to_string();
+    report_files(message_format, [(name.as_str(), diagnostics)]);
+}
+
+/// Prints the diagnostics found in several files, as [`report`] does,
+/// with a single SARIF log for all of them.
+fn report_files<'a>(
+    message_format: MessageFormat,
+    files: impl IntoIterator<Item = (&'a str, &'a Diagnostics)>,
+) {
+    match message_format {
+        MessageFormat::Human => {
+            for (name, diagnostics) in files {
+                if !diagnostics.is_empty() {
+                    eprintln!(
+                        "{}",
+                        render_diagnostics(diagnostics, name)
+                    );
+                }
+            }
+        }
+        MessageFormat::Json => {
+            for (name, diagnostics) in files {
+                for diagnostic in diagnostics {
+                    println!("{}", diagnostic.to_json(name));
+                }
+            }
+        }
+        MessageFormat::Sarif => {
+            println!("{:#}", diagnostics::to_sarif(files));
+        }
+    }
+}
+
+/// Renders every diagnostic, separated by blank lines.
+fn render_diagnostics(diagnostics: &Diagnostics, name: &str) -> String {
+    diagnostics
+        .iter()
+        .map(|diagnostic| diagnostic.render(name))
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+/// Adds `message` to an error from reading the frontmatter of `input`.
+///
+/// Errors with a position are shown with the offending line of the file
+/// and a caret under the position.
+fn parse_failure(
+    input: &Path,
+    message: &str,
+    err: crate::Error,
+) -> anyhow::Error {
+    if err.context().is_some() {
+        anyhow::anyhow!(
+            "{message}: {}",
+            err.render(&input.display().to_string())
+        )
+    } else {
+        anyhow::Error::new(err).context(message.to_string())
+    }
+}
+
+/// Parses a value given on the command line.
+///
+/// JSON is accepted for arrays, objects and quoted strings; anything
+/// that is not valid JSON is read as a plain scalar.
+fn parse_value_arg(raw: &str) -> Value {
+    serde_json::from_str(raw).unwrap_or_else(|_| {
+        raw.parse()
+            .unwrap_or_else(|_| Value::String(raw.to_string()))
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -217,6 +926,110 @@ mod tests {
     mod extract_tests {
         use super::*;
 
+        /// Writes a small tree of posts, one of them broken, to `dir`.
+        fn write_posts(dir: &Path) -> Result<()> {
+            std::fs::create_dir_all(dir.join("posts/drafts"))?;
+            std::fs::write(
+                dir.join("index.md"),
+                "---\ntitle: Home\n---\n",
+            )?;
+            std::fs::write(
+                dir.join("posts/a.md"),
+                "+++\ntitle = \"A\"\n+++\n",
+            )?;
+            std::fs::write(
+                dir.join("posts/drafts/b.md"),
+                "---\ntitle: [unclosed\n---\n",
+            )?;
+            Ok(())
+        }
+
+        #[tokio::test]
+        async fn test_extract_recursive_document() -> Result<()> {
+            let dir = tempdir()?;
+            let content = dir.path().join("content");
+            let output_path = dir.path().join("out.json");
+            write_posts(&content)?;
+
+            let globs = Globs {
+                include: &[],
+                exclude: &["**/drafts".to_string()],
+            };
+            process_extract_dir(
+                &content,
+                "json",
+                &Some(output_path.clone()),
+                globs,
+                &[],
+                MessageFormat::Human,
+            )
+            .await?;
+
+            let output: serde_json::Value = serde_json::from_str(
+                &std::fs::read_to_string(&output_path)?,
+            )?;
+            assert_eq!(
+                output,
+                serde_json::json!({
+                    "index.md": { "title": "Home" },
+                    "posts/a.md": { "title": "A" },
+                })
+            );
+            Ok(())
+        }
+
+        #[tokio::test]
+        async fn test_extract_recursive_ndjson_with_errors(
+        ) -> Result<()> {
+            let dir = tempdir()?;
+            let content = dir.path().join("content");
+            let output_path = dir.path().join("out.ndjson");
+            write_posts(&content)?;
+
+            let globs = Globs {
+                include: &["posts/**".to_string()],
+                exclude: &[],
+            };
+            let result = process_extract_dir(
+                &content,
+                "ndjson",
+                &Some(output_path.clone()),
+                globs,
+                &[],
+                MessageFormat::Json,
+            )
+            .await;
+
+            let error = result.unwrap_err().to_string();
+            assert!(error.contains("from 1 of 2 files"), "{error}");
+            assert_eq!(
+                std::fs::read_to_string(&output_path)?,
+                "{\"posts/a.md\":{\"title\":\"A\"}}\n"
+            );
+            Ok(())
+        }
+
+        #[tokio::test]
+        async fn test_extract_recursive_unsupported_format(
+        ) -> Result<()> {
+            let dir = tempdir()?;
+            let globs = Globs {
+                include: &[],
+                exclude: &[],
+            };
+            assert!(process_extract_dir(
+                dir.path(),
+                "xml",
+                &None,
+                globs,
+                &[],
+                MessageFormat::Human,
+            )
+            .await
+            .is_err());
+            Ok(())
+        }
+
         #[tokio::test]
         async fn test_extract_command_default_format() -> Result<()> {
             let dir = tempdir()?;
@@ -275,6 +1088,8 @@ async fn test_extract_command_uppercase_format() -> Result<()> {
                 &input_path,
                 "YAML",
                 &Some(output_path.clone()),
+                &[],
+                MessageFormat::Human,
             )
             .await;
             assert!(result.is_ok());
@@ -303,9 +1118,14 @@ async fn test_extract_command_invalid_format() -> Result<()> {
             writeln!(file, "{}", content)?;
 
             // Test extract command with an invalid format to ensure it returns an error
-            let result =
-                process_extract(&input_path, "invalid_format", &None)
-                    .await;
+            let result = process_extract(
+                &input_path,
+                "invalid_format",
+                &None,
+                &[],
+                MessageFormat::Human,
+            )
+            .await;
             assert!(result.is_err());
             if let Err(e) = result {
                 assert!(e.to_string().contains("Unsupported format"));
@@ -334,6 +1154,8 @@ async fn test_extract_command() -> Result<()> {
                 &input_path,
                 "yaml",
                 &Some(output_path.clone()),
+                &[],
+                MessageFormat::Human,
             )
             .await?;
 
@@ -368,9 +1190,14 @@ async fn test_extract_command_invalid_input_file() -> Result<()>
         {
             let input_path = PathBuf::from("nonexistent.md");
             let output_path = None;
-            let result =
-                process_extract(&input_path, "yaml", &output_path)
-                    .await;
+            let result = process_extract(
+                &input_path,
+                "yaml",
+                &output_path,
+                &[],
+                MessageFormat::Human,
+            )
+            .await;
             assert!(result.is_err());
             if let Err(e) = result {
                 assert!(e
@@ -395,8 +1222,14 @@ async fn test_extract_command_unsupported_format() -> Result<()>
             let mut file = File::create(&input_path)?;
             writeln!(file, "{}", content)?;
 
-            let result =
-                process_extract(&input_path, "xml", &None).await;
+            let result = process_extract(
+                &input_path,
+                "xml",
+                &None,
+                &[],
+                MessageFormat::Human,
+            )
+            .await;
             assert!(result.is_err());
             if let Err(e) = result {
                 assert!(e.to_string().contains("Unsupported format"));
@@ -414,8 +1247,14 @@ async fn test_extract_command_no_frontmatter() -> Result<()> {
             let mut file = File::create(&input_path)?;
             writeln!(file, "{}", content)?;
 
-            let result =
-                process_extract(&input_path, "yaml", &None).await;
+            let result = process_extract(
+                &input_path,
+                "yaml",
+                &None,
+                &[],
+                MessageFormat::Human,
+            )
+            .await;
             assert!(result.is_err());
             if let Err(e) = result {
                 assert!(e
@@ -441,8 +1280,14 @@ async fn test_extract_command_invalid_frontmatter() -> Result<()>
             let mut file = File::create(&input_path)?;
             writeln!(file, "{}", content)?;
 
-            let result =
-                process_extract(&input_path, "yaml", &None).await;
+            let result = process_extract(
+                &input_path,
+                "yaml",
+                &None,
+                &[],
+                MessageFormat::Human,
+            )
+            .await;
             assert!(result.is_err());
             if let Err(e) = result {
                 assert!(e
@@ -483,6 +1328,8 @@ async fn test_extract_command_output_write_error() -> Result<()>
                 &input_path,
                 "yaml",
                 &Some(output_path.clone()),
+                &[],
+                MessageFormat::Human,
             )
             .await;
             assert!(result.is_err());
@@ -515,6 +1362,8 @@ async fn test_extract_command_toml_format() -> Result<()> {
                 &input_path,
                 "toml",
                 &Some(output_path.clone()),
+                &[],
+                MessageFormat::Human,
             )
             .await;
             assert!(result.is_ok());
@@ -553,6 +1402,8 @@ async fn test_extract_command_json_format() -> Result<()> {
                 &input_path,
                 "json",
                 &Some(output_path.clone()),
+                &[],
+                MessageFormat::Human,
             )
             .await;
             assert!(result.is_ok());
@@ -571,6 +1422,71 @@ async fn test_extract_command_json_format() -> Result<()> {
             Ok(())
         }
 
+        #[tokio::test]
+        async fn test_extract_command_json5_and_ron_formats(
+        ) -> Result<()> {
+            let dir = tempdir()?;
+            let input_path = dir.path().join("test.md");
+            std::fs::write(
+                &input_path,
+                "---\ntitle: Test\ndate: 2024-01-01\n---\nContent here\n",
+            )?;
+
+            for (format, expected) in [
+                ("json5", "title: \"Test\""),
+                ("ron", "\"title\": \"Test\""),
+            ] {
+                let output_path =
+                    dir.path().join(format!("out.{format}"));
+                process_extract(
+                    &input_path,
+                    format,
+                    &Some(output_path.clone()),
+                    &[],
+                    MessageFormat::Human,
+                )
+                .await?;
+
+                let output = std::fs::read_to_string(&output_path)?;
+                assert!(output.contains(expected), "{output}");
+                assert!(output.contains("2024-01-01"), "{output}");
+            }
+
+            Ok(())
+        }
+
+        #[tokio::test]
+        async fn test_extract_command_org_and_asciidoc_formats(
+        ) -> Result<()> {
+            let dir = tempdir()?;
+            let input_path = dir.path().join("guide.org");
+            std::fs::write(
+                &input_path,
+                "#+TITLE: Guide\n#+AUTHOR: Jane\n\n* Heading\n",
+            )?;
+
+            for (format, expected) in [
+                ("asciidoc", "= Guide\n:author: Jane\n"),
+                ("org", "#+TITLE: Guide\n#+AUTHOR: Jane\n"),
+            ] {
+                let output_path =
+                    dir.path().join(format!("out.{format}"));
+                process_extract(
+                    &input_path,
+                    format,
+                    &Some(output_path.clone()),
+                    &[],
+                    MessageFormat::Human,
+                )
+                .await?;
+
+                let output = std::fs::read_to_string(&output_path)?;
+                assert!(output.starts_with(expected), "{output}");
+            }
+
+            Ok(())
+        }
+
         #[tokio::test]
         async fn test_extract_command_no_output_file() -> Result<()> {
             let dir = tempdir()?;
@@ -586,8 +1502,14 @@ async fn test_extract_command_no_output_file() -> Result<()> {
             let mut file = File::create(&input_path)?;
             writeln!(file, "{}", content)?;
 
-            let result =
-                process_extract(&input_path, "yaml", &None).await;
+            let result = process_extract(
+                &input_path,
+                "yaml",
+                &None,
+                &[],
+                MessageFormat::Human,
+            )
+            .await;
             assert!(result.is_ok());
 
             // Since output is to stdout, we can't easily capture it here
@@ -618,9 +1540,16 @@ async fn test_validate_command_required_fields_whitespace_only(
             writeln!(file, "{}", content)?;
 
             // Test validate command with required fields containing only whitespace
-            let result =
-                process_validate(&input_path, &Some("   ".to_string()))
-                    .await;
+            let result = process_validate(
+                &input_path,
+                &Some("   ".to_string()),
+                &None,
+                &None,
+                &[],
+                None,
+                MessageFormat::Human,
+            )
+            .await;
             assert!(result.is_err());
             if let Err(e) = result {
                 assert!(e
@@ -658,6 +1587,11 @@ async fn test_validate_command_missing_required_field(
             let result = process_validate(
                 &input_path,
                 &Some("author".to_string()),
+                &None,
+                &None,
+                &[],
+                None,
+                MessageFormat::Human,
             )
             .await;
             assert!(result.is_err());
@@ -691,6 +1625,8 @@ async fn test_extract_command_output_is_directory() -> Result<()>
                 &input_path,
                 "yaml",
                 &Some(output_path.to_path_buf()),
+                &[],
+                MessageFormat::Human,
             )
             .await;
             assert!(result.is_err());
@@ -722,46 +1658,211 @@ async fn test_extract_command_with_empty_frontmatter(
                 &input_path,
                 "yaml",
                 &Some(output_path.clone()),
+                &[],
+                MessageFormat::Human,
+            )
+            .await;
+            assert!(result.is_err());
+            if let Err(e) = result {
+                assert!(e
+                    .to_string()
+                    .contains("Failed to extract frontmatter"));
+            }
+
+            Ok(())
+        }
+
+        #[tokio::test]
+        async fn test_validate_command() -> Result<()> {
+            let dir = tempdir()?;
+            let input_path = dir.path().join("test.md");
+
+            // Create test input file
+            let content = r"---
+title: Test
+date: 2024-01-01
+---
+Content here";
+            let mut file = File::create(&input_path)?;
+            writeln!(file, "{}", content)?;
+
+            // Test validate command with valid fields
+            process_validate(
+                &input_path,
+                &Some("title,date".to_string()),
+                &None,
+                &None,
+                &[],
+                None,
+                MessageFormat::Human,
+            )
+            .await?;
+
+            // Test validate command with missing field
+            let result = process_validate(
+                &input_path,
+                &Some("title,author".to_string()),
+                &None,
+                &None,
+                &[],
+                None,
+                MessageFormat::Human,
+            )
+            .await;
+            assert!(result.is_err());
+
+            Ok(())
+        }
+
+        #[tokio::test]
+        async fn test_validate_command_with_schema() -> Result<()> {
+            let dir = tempdir()?;
+            let input_path = dir.path().join("test.md");
+            let schema_path = dir.path().join("schema.toml");
+
+            let content = r"---
+title: Test
+slug: test
+weight: 120
+tags: [rust, Web]
+---
+Content here";
+            let mut file = File::create(&input_path)?;
+            writeln!(file, "{}", content)?;
+
+            let schema = r#"
+[fields.title]
+type = "string"
+required = true
+
+[fields.date]
+required = true
+
+[fields.weight]
+type = "integer"
+maximum = 100
+
+[fields.tags]
+items = { type = "string", pattern = "^[a-z]+$" }
+"#;
+            std::fs::write(&schema_path, schema)?;
+
+            let result = process_validate(
+                &input_path,
+                &None,
+                &Some(schema_path.clone()),
+                &None,
+                &[],
+                None,
+                MessageFormat::Human,
+            )
+            .await;
+            let message = format!("{:?}", result.unwrap_err());
+            assert!(message.contains("3 violation(s)"));
+            assert!(message.contains("date: missing required field"));
+            assert!(message.contains("weight: 120 is greater than"));
+            assert!(message.contains("tags[1]: \"Web\" does not match"));
+            assert!(message.contains("warning[undeclared-field]"));
+
+            // The same file passes once the violations are fixed, even
+            // with the undeclared field still warned about
+            std::fs::write(
+                &input_path,
+                "---\ntitle: Test\nslug: test\ndate: 2024-01-01\nweight: 1\n---\n",
+            )?;
+            process_validate(
+                &input_path,
+                &None,
+                &Some(schema_path),
+                &None,
+                &[],
+                None,
+                MessageFormat::Human,
             )
-            .await;
-            assert!(result.is_err());
-            if let Err(e) = result {
-                assert!(e
-                    .to_string()
-                    .contains("Failed to extract frontmatter"));
-            }
+            .await?;
 
             Ok(())
         }
 
         #[tokio::test]
-        async fn test_validate_command() -> Result<()> {
+        async fn test_validate_command_with_json_schema() -> Result<()>
+        {
             let dir = tempdir()?;
             let input_path = dir.path().join("test.md");
+            let schema_path = dir.path().join("schema.json");
 
-            // Create test input file
-            let content = r"---
-title: Test
-date: 2024-01-01
----
-Content here";
-            let mut file = File::create(&input_path)?;
-            writeln!(file, "{}", content)?;
+            std::fs::write(
+                &input_path,
+                "---\ntitle: Test\ndraft: maybe\n---\nContent here",
+            )?;
+            std::fs::write(
+                &schema_path,
+                r#"{
+  "$schema": "https://json-schema.org/draft/2020-12/schema",
+  "type": "object",
+  "properties": {
+    "title": { "type": "string" },
+    "date": { "type": "string", "format": "date" },
+    "draft": { "type": "boolean" }
+  },
+  "required": ["title", "date"]
+}"#,
+            )?;
 
-            // Test validate command with valid fields
+            let result = process_validate(
+                &input_path,
+                &None,
+                &None,
+                &Some(schema_path.clone()),
+                &[],
+                None,
+                MessageFormat::Human,
+            )
+            .await;
+            let message = format!("{:?}", result.unwrap_err());
+            assert!(message.contains("2 violation(s)"));
+            assert!(message.contains("date: missing required field"));
+            assert!(message.contains("draft: expected boolean"));
+
+            std::fs::write(
+                &input_path,
+                "---\ntitle: Test\ndate: 2024-01-01\n---\n",
+            )?;
             process_validate(
                 &input_path,
-                &Some("title,date".to_string()),
+                &None,
+                &None,
+                &Some(schema_path),
+                &[],
+                None,
+                MessageFormat::Human,
             )
             .await?;
 
-            // Test validate command with missing field
+            Ok(())
+        }
+
+        #[tokio::test]
+        async fn test_validate_command_missing_schema_file(
+        ) -> Result<()> {
+            let dir = tempdir()?;
+            let input_path = dir.path().join("test.md");
+            std::fs::write(&input_path, "---\ntitle: Test\n---\n")?;
+
             let result = process_validate(
                 &input_path,
-                &Some("title,author".to_string()),
+                &None,
+                &Some(dir.path().join("missing.toml")),
+                &None,
+                &[],
+                None,
+                MessageFormat::Human,
             )
             .await;
-            assert!(result.is_err());
+            assert!(result
+                .unwrap_err()
+                .to_string()
+                .contains("Failed to load schema"));
 
             Ok(())
         }
@@ -774,6 +1875,11 @@ async fn test_validate_command_invalid_input_file() -> Result<()>
             let result = process_validate(
                 &input_path,
                 &Some("title".to_string()),
+                &None,
+                &None,
+                &[],
+                None,
+                MessageFormat::Human,
             )
             .await;
             assert!(result.is_err());
@@ -799,6 +1905,11 @@ async fn test_validate_command_no_frontmatter() -> Result<()> {
             let result = process_validate(
                 &input_path,
                 &Some("title".to_string()),
+                &None,
+                &None,
+                &[],
+                None,
+                MessageFormat::Human,
             )
             .await;
             assert!(result.is_err());
@@ -830,6 +1941,11 @@ async fn test_validate_command_invalid_frontmatter(
             let result = process_validate(
                 &input_path,
                 &Some("title".to_string()),
+                &None,
+                &None,
+                &[],
+                None,
+                MessageFormat::Human,
             )
             .await;
             assert!(result.is_err());
@@ -859,7 +1975,16 @@ async fn test_validate_command_no_required_fields() -> Result<()>
             writeln!(file, "{}", content)?;
 
             // Test validate command with no required fields
-            let result = process_validate(&input_path, &None).await;
+            let result = process_validate(
+                &input_path,
+                &None,
+                &None,
+                &None,
+                &[],
+                None,
+                MessageFormat::Human,
+            )
+            .await;
             assert!(result.is_ok());
 
             Ok(())
@@ -867,6 +1992,391 @@ async fn test_validate_command_no_required_fields() -> Result<()>
     }
 
     // Tests for CLI parsing
+    mod path_tests {
+        use super::*;
+
+        const CONTENT: &str = "---
+# Post metadata
+title: Test # shown in the header
+seo:
+  og:
+    image: old.png
+authors:
+  - name: Jane
+---
+Content here
+";
+
+        #[tokio::test]
+        async fn test_get_command() -> Result<()> {
+            let dir = tempdir()?;
+            let input_path = dir.path().join("test.md");
+            std::fs::write(&input_path, CONTENT)?;
+
+            process_get(&input_path, "seo.og.image", &[]).await?;
+            process_get(&input_path, "authors", &[]).await?;
+
+            let result =
+                process_get(&input_path, "seo.missing", &[]).await;
+            assert!(result.unwrap_err().to_string().contains(
+                "Key not found in front matter: seo.missing"
+            ));
+
+            let result = process_get(&input_path, "seo..og", &[]).await;
+            assert!(result
+                .unwrap_err()
+                .to_string()
+                .contains("Invalid front matter path"));
+
+            Ok(())
+        }
+
+        #[tokio::test]
+        async fn test_set_command_edits_in_place() -> Result<()> {
+            let dir = tempdir()?;
+            let input_path = dir.path().join("test.md");
+            std::fs::write(&input_path, CONTENT)?;
+
+            process_set(&input_path, "seo.og.image", "new.png", &[])
+                .await?;
+            process_set(
+                &input_path,
+                "authors[0].links",
+                r#"["a", 1]"#,
+                &[],
+            )
+            .await?;
+            process_set(&input_path, "weight", "10", &[]).await?;
+            process_get(&input_path, "authors[0].links", &[]).await?;
+
+            let updated = std::fs::read_to_string(&input_path)?;
+            assert!(updated.starts_with(
+                "---\n# Post metadata\ntitle: Test # shown in the header\n"
+            ));
+            assert!(updated.ends_with("---\nContent here\n"));
+
+            let fm = Editor::new(&updated)?.frontmatter()?;
+            assert_eq!(
+                fm.get_path("seo.og.image").and_then(Value::as_str),
+                Some("new.png")
+            );
+            assert_eq!(
+                fm.get_path("authors[0].links[1]"),
+                Some(&Value::Integer(1))
+            );
+            assert_eq!(fm.get("weight"), Some(&Value::Integer(10)));
+
+            let result =
+                process_set(&input_path, "title.first", "x", &[]).await;
+            assert!(result.is_err());
+            assert_eq!(std::fs::read_to_string(&input_path)?, updated);
+
+            Ok(())
+        }
+
+        #[tokio::test]
+        async fn test_get_and_set_custom_delimiters() -> Result<()> {
+            let dir = tempdir()?;
+            let input_path = dir.path().join("post.md");
+            std::fs::write(
+                &input_path,
+                ";;;\n{\"title\": \"Post\"}\n;;;\nBody\n",
+            )?;
+            let delimiters = ["json:;;;".parse::<Delimiter>()?];
+
+            assert!(process_get(&input_path, "title", &[])
+                .await
+                .is_err());
+            assert!(process_set(&input_path, "title", "New", &[])
+                .await
+                .is_err());
+
+            process_get(&input_path, "title", &delimiters).await?;
+            process_set(&input_path, "title", "New", &delimiters)
+                .await?;
+            assert_eq!(
+                std::fs::read_to_string(&input_path)?,
+                ";;;\n{\"title\": \"New\"}\n;;;\nBody\n"
+            );
+            Ok(())
+        }
+
+        #[test]
+        fn test_parse_value_arg() {
+            assert_eq!(parse_value_arg("true"), Value::Boolean(true));
+            assert_eq!(parse_value_arg("2.5"), Value::Float(2.5));
+            assert_eq!(
+                parse_value_arg(r#""10""#),
+                Value::String("10".to_string())
+            );
+            assert_eq!(
+                parse_value_arg("plain text"),
+                Value::String("plain text".to_string())
+            );
+            assert!(parse_value_arg(r#"{"a": [1]}"#).is_object());
+        }
+    }
+
+    mod diff_tests {
+        use super::*;
+
+        #[tokio::test]
+        async fn test_diff_command() -> Result<()> {
+            let dir = tempdir()?;
+            let old = dir.path().join("old.md");
+            let new = dir.path().join("new.md");
+            std::fs::write(
+                &old,
+                "---\ntitle: A\ntags: [x]\n---\nBody",
+            )?;
+            std::fs::write(
+                &new,
+                "+++\ntitle = \"B\"\ntags = [\"x\", \"y\"]\n+++\nBody",
+            )?;
+
+            process_diff(&old, &new, false, &[]).await?;
+            process_diff(&old, &new, true, &[]).await?;
+            process_diff(&old, &old, false, &[]).await?;
+
+            let custom = dir.path().join("custom.md");
+            std::fs::write(&custom, ";;;\n{\"title\": \"C\"}\n;;;\n")?;
+            let delimiters = ["json:;;;".parse::<Delimiter>()?];
+            assert!(process_diff(&old, &custom, false, &[])
+                .await
+                .is_err());
+            process_diff(&old, &custom, false, &delimiters).await?;
+
+            let missing = dir.path().join("missing.md");
+            let result = process_diff(&old, &missing, false, &[]).await;
+            assert!(result
+                .unwrap_err()
+                .to_string()
+                .contains("Failed to read input file"));
+            Ok(())
+        }
+
+        #[test]
+        fn test_cli_parsing_diff() {
+            let args = Cli::parse_from([
+                "program",
+                "diff",
+                "a.md",
+                "b.md",
+                "--json-patch",
+            ]);
+            match args.command {
+                Commands::Diff {
+                    old,
+                    new,
+                    json_patch,
+                    ..
+                } => {
+                    assert_eq!(old, PathBuf::from("a.md"));
+                    assert_eq!(new, PathBuf::from("b.md"));
+                    assert!(json_patch);
+                }
+                _ => panic!("Expected Diff command"),
+            }
+        }
+    }
+
+    mod diagnostic_tests {
+        use super::*;
+
+        #[tokio::test]
+        async fn test_parse_errors_show_file_position() -> Result<()> {
+            let dir = tempdir()?;
+            let input_path = dir.path().join("post.md");
+            std::fs::write(
+                &input_path,
+                "---\ntitle: Post\nbad: : x\n---\nBody\n",
+            )?;
+
+            let err = process_extract(
+                &input_path,
+                "yaml",
+                &None,
+                &[],
+                MessageFormat::Human,
+            )
+            .await
+            .unwrap_err();
+            assert_eq!(
+                err.to_string(),
+                format!(
+                    "Failed to extract frontmatter: Failed to parse YAML: mapping values are not allowed in this context\n --> {}:3:6\n  |\n3 | bad: : x\n  |      ^",
+                    input_path.display()
+                )
+            );
+
+            let err = process_get(&input_path, "title", &[])
+                .await
+                .unwrap_err();
+            assert!(err.to_string().contains(":3:6\n"));
+            Ok(())
+        }
+
+        #[tokio::test]
+        async fn test_validate_reports_every_error() -> Result<()> {
+            let dir = tempdir()?;
+            let input_path = dir.path().join("post.md");
+            std::fs::write(
+                &input_path,
+                "---\ntitle: Post\nimage: ../cover.png\n---\nBody\n",
+            )?;
+
+            let err = process_validate(
+                &input_path,
+                &Some("title,date,author".to_string()),
+                &None,
+                &None,
+                &[],
+                Some(&SecurityPolicy::strict()),
+                MessageFormat::Human,
+            )
+            .await
+            .unwrap_err();
+            let message = err.to_string();
+            assert!(message.starts_with(
+                "Validation failed with 3 violation(s):\nerror[path-traversal]"
+            ));
+            assert!(message.contains(":3:8\n"));
+            assert!(message.contains(
+                "error[missing-field]: Missing required field: date"
+            ));
+            assert!(message.contains(
+                "error[missing-field]: Missing required field: author"
+            ));
+            Ok(())
+        }
+
+        #[tokio::test]
+        async fn test_machine_readable_failures() -> Result<()> {
+            let dir = tempdir()?;
+            let input_path = dir.path().join("post.md");
+            std::fs::write(&input_path, "---\ntitle: [oops\n---\n")?;
+
+            for message_format in
+                [MessageFormat::Json, MessageFormat::Sarif]
+            {
+                let err = process_extract(
+                    &input_path,
+                    "yaml",
+                    &None,
+                    &[],
+                    message_format,
+                )
+                .await
+                .unwrap_err();
+                assert_eq!(
+                    err.to_string(),
+                    "Failed to extract frontmatter"
+                );
+
+                let err = process_validate(
+                    &input_path,
+                    &None,
+                    &None,
+                    &None,
+                    &[],
+                    None,
+                    message_format,
+                )
+                .await
+                .unwrap_err();
+                assert_eq!(
+                    err.to_string(),
+                    "Failed to extract frontmatter"
+                );
+            }
+
+            std::fs::write(&input_path, "---\ntitle: Post\n---\n")?;
+            let err = process_validate(
+                &input_path,
+                &Some("date".to_string()),
+                &None,
+                &None,
+                &[],
+                None,
+                MessageFormat::Json,
+            )
+            .await
+            .unwrap_err();
+            assert_eq!(
+                err.to_string(),
+                "Validation failed with 1 violation(s)"
+            );
+            Ok(())
+        }
+
+        #[tokio::test]
+        async fn test_custom_delimiters() -> Result<()> {
+            let dir = tempdir()?;
+            let input_path = dir.path().join("post.md");
+            let output_path = dir.path().join("out.yaml");
+            std::fs::write(
+                &input_path,
+                ";;;\n{\"title\": \"Post\"}\n;;;\nBody\n",
+            )?;
+            let delimiters = ["json:;;;".parse::<Delimiter>()?];
+
+            assert!(process_extract(
+                &input_path,
+                "yaml",
+                &Some(output_path.clone()),
+                &[],
+                MessageFormat::Human,
+            )
+            .await
+            .is_err());
+
+            process_extract(
+                &input_path,
+                "yaml",
+                &Some(output_path.clone()),
+                &delimiters,
+                MessageFormat::Human,
+            )
+            .await?;
+            let output = std::fs::read_to_string(&output_path)?;
+            assert!(output.contains("title: Post"));
+
+            process_validate(
+                &input_path,
+                &Some("title".to_string()),
+                &None,
+                &None,
+                &delimiters,
+                None,
+                MessageFormat::Human,
+            )
+            .await?;
+            Ok(())
+        }
+
+        #[tokio::test]
+        async fn test_validate_ignores_links_in_body() -> Result<()> {
+            let dir = tempdir()?;
+            let input_path = dir.path().join("post.md");
+            std::fs::write(
+                &input_path,
+                "---\ntitle: Post\n---\nSee [the index](../index.md).\n",
+            )?;
+
+            process_validate(
+                &input_path,
+                &Some("title".to_string()),
+                &None,
+                &None,
+                &[],
+                Some(&SecurityPolicy::strict()),
+                MessageFormat::Human,
+            )
+            .await?;
+            Ok(())
+        }
+    }
+
     mod cli_parsing_tests {
         use super::*;
         use clap::Parser;
@@ -885,6 +2395,101 @@ fn test_cli_parsing_extract_default_format() {
             }
         }
 
+        #[test]
+        fn test_cli_parsing_extract_recursive() {
+            let args = Cli::parse_from([
+                "program",
+                "extract",
+                "content",
+                "-r",
+                "--include",
+                "**/*.md",
+                "--exclude",
+                "drafts",
+                "--format",
+                "ndjson",
+            ]);
+            match args.command {
+                Commands::Extract {
+                    recursive,
+                    include,
+                    exclude,
+                    ..
+                } => {
+                    assert!(recursive);
+                    assert_eq!(include, ["**/*.md"]);
+                    assert_eq!(exclude, ["drafts"]);
+                }
+                _ => panic!("Expected Extract command"),
+            }
+
+            assert!(Cli::try_parse_from([
+                "program",
+                "extract",
+                "input.md",
+                "--include",
+                "*.md",
+            ])
+            .is_err());
+        }
+
+        #[test]
+        fn test_cli_parsing_validate_policy() {
+            let args = Cli::parse_from([
+                "program",
+                "validate",
+                "input.md",
+                "--policy",
+                "path-traversal,yaml-anchor",
+            ]);
+            match args.command {
+                Commands::Validate { policy, .. } => {
+                    let policy = policy.unwrap();
+                    assert!(policy.deny_path_traversal);
+                    assert!(policy.deny_yaml_anchors);
+                    assert!(!policy.deny_null_bytes);
+                }
+                _ => panic!("Expected Validate command"),
+            }
+
+            assert!(Cli::try_parse_from([
+                "program",
+                "validate",
+                "input.md",
+                "--policy",
+                "no-such-rule",
+            ])
+            .is_err());
+        }
+
+        #[test]
+        fn test_cli_parsing_get_and_set() {
+            let args =
+                Cli::parse_from(["program", "get", "in.md", "a.b[0]"]);
+            match args.command {
+                Commands::Get { input, path, .. } => {
+                    assert_eq!(input, PathBuf::from("in.md"));
+                    assert_eq!(path, "a.b[0]");
+                }
+                _ => panic!("Expected Get command"),
+            }
+
+            let args =
+                Cli::parse_from(["program", "set", "in.md", "a", "1"]);
+            match args.command {
+                Commands::Set { path, value, .. } => {
+                    assert_eq!(path, "a");
+                    assert_eq!(value, "1");
+                }
+                _ => panic!("Expected Set command"),
+            }
+
+            assert!(Cli::try_parse_from([
+                "program", "set", "in.md", "a"
+            ])
+            .is_err());
+        }
+
         #[test]
         fn test_cli_parsing_invalid_command() {
             // Test parsing an invalid command
@@ -916,7 +2521,20 @@ fn test_cli_parsing() {
                 "title,date",
             ]);
             match args.command {
-                Commands::Validate { input, required } => {
+                Commands::Validate {
+                    input,
+                    required,
+                    schema,
+                    json_schema,
+                    delimiters,
+                    policy,
+                    message_format,
+                } => {
+                    assert_eq!(schema, None);
+                    assert!(delimiters.is_empty());
+                    assert_eq!(policy, None);
+                    assert_eq!(json_schema, None);
+                    assert_eq!(message_format, MessageFormat::Human);
                     assert_eq!(input, PathBuf::from("input.md"));
                     assert_eq!(
                         required,
@@ -925,6 +2543,67 @@ fn test_cli_parsing() {
                 }
                 _ => panic!("Expected Validate command"),
             }
+
+            let args = Cli::parse_from([
+                "program",
+                "extract",
+                "input.md",
+                "--message-format",
+                "sarif",
+            ]);
+            assert!(matches!(
+                args.command,
+                Commands::Extract {
+                    message_format: MessageFormat::Sarif,
+                    ..
+                }
+            ));
+            assert!(Cli::try_parse_from([
+                "program",
+                "validate",
+                "input.md",
+                "--message-format",
+                "xml",
+            ])
+            .is_err());
+
+            let args = Cli::parse_from([
+                "program",
+                "extract",
+                "input.md",
+                "--delimiter",
+                "json:;;;",
+                "--delimiter",
+                "yaml:---:...",
+            ]);
+            match args.command {
+                Commands::Extract { delimiters, .. } => {
+                    assert_eq!(
+                        delimiters,
+                        [
+                            Delimiter::new(
+                                ";;;",
+                                [";;;"],
+                                Format::Json
+                            ),
+                            Delimiter::new(
+                                "---",
+                                ["..."],
+                                Format::Yaml
+                            ),
+                        ]
+                    );
+                }
+                _ => panic!("Expected Extract command"),
+            }
+            assert!(Cli::try_parse_from([
+                "program",
+                "validate",
+                "input.md",
+                "--delimiter",
+                "xml:<<<",
+            ])
+            .is_err());
         }
     }
 
@@ -953,6 +2632,11 @@ async fn test_cli_process_extract() -> Result<()> {
                     input: input_path.clone(),
                     format: "yaml".to_string(),
                     output: Some(output_path.clone()),
+                    recursive: false,
+                    include: Vec::new(),
+                    exclude: Vec::new(),
+                    delimiters: Vec::new(),
+                    message_format: MessageFormat::Human,
                 },
             };
 
@@ -986,6 +2670,11 @@ async fn test_cli_process_validate() -> Result<()> {
                 command: Commands::Validate {
                     input: input_path.clone(),
                     required: Some("title,date".to_string()),
+                    schema: None,
+                    json_schema: None,
+                    delimiters: Vec::new(),
+                    policy: None,
+                    message_format: MessageFormat::Human,
                 },
             };
 
@@ -1011,7 +2700,14 @@ async fn test_extract_command_empty_format() -> Result<()> {
         writeln!(file, "{}", content)?;
 
         // Test extract command with an empty format string
-        let result = process_extract(&input_path, "", &None).await;
+        let result = process_extract(
+            &input_path,
+            "",
+            &None,
+            &[],
+            MessageFormat::Human,
+        )
+        .await;
         assert!(result.is_err());
         if let Err(e) = result {
             assert!(e.to_string().contains("Unsupported format"));
@@ -1040,6 +2736,11 @@ async fn test_validate_command_required_fields_with_whitespace(
         let result = process_validate(
             &input_path,
             &Some(" title , date ".to_string()),
+            &None,
+            &None,
+            &[],
+            None,
+            MessageFormat::Human,
         )
         .await;
         assert!(result.is_err());
@@ -1072,6 +2773,11 @@ async fn test_validate_command_duplicate_required_fields(
         let result = process_validate(
             &input_path,
             &Some("title,date,title".to_string()),
+            &None,
+            &None,
+            &[],
+            None,
+            MessageFormat::Human,
         )
         .await;
         assert!(result.is_ok());
@@ -1105,6 +2811,8 @@ async fn test_extract_command_with_complex_data() -> Result<()> {
             &input_path,
             "json",
             &Some(output_path.clone()),
+            &[],
+            MessageFormat::Human,
         )
         .await;
         assert!(result.is_ok());
@@ -0,0 +1,413 @@
+// de.rs
+
+//! Deserialisation of typed values from front matter.
+//!
+//! `Value` implements [`serde::Deserializer`], so any
+//! `T: DeserializeOwned` can be read straight out of parsed front matter
+//! without a round trip through a text format. Type errors report the path
+//! of the offending key through `serde_path_to_error`.
+
+use serde::de::{
+    self,
+    value::{MapAccessDeserializer, MapDeserializer, SeqDeserializer},
+    DeserializeOwned, IntoDeserializer, Unexpected, Visitor,
+};
+use std::iter;
+
+use crate::{
+    error::Error,
+    types::{
+        Datetime, Frontmatter, Value, TOML_DATETIME_FIELD,
+        TOML_DATETIME_NAME,
+    },
+};
+
+impl de::Error for Error {
+    fn custom<T: std::fmt::Display>(msg: T) -> Self {
+        Self::DeserializeError {
+            path: String::new(),
+            message: msg.to_string(),
+        }
+    }
+}
+
+/// Deserialises `T` from a value, recording the key path of any error.
+pub(crate) fn from_value<T: DeserializeOwned>(
+    value: Value,
+) -> Result<T, Error> {
+    serde_path_to_error::deserialize(value).map_err(|err| {
+        let path = err.path().to_string();
+        match err.into_inner() {
+            Error::DeserializeError { message, .. } => {
+                Error::DeserializeError { path, message }
+            }
+            other => other,
+        }
+    })
+}
+
+impl Frontmatter {
+    /// Deserialises the front matter into a typed value.
+    ///
+    /// Integers widen to floats where a float is expected, YAML tags are
+    /// ignored unless the target is an enum (where the tag names the
+    /// variant), and datetimes deserialise into either `String` or
+    /// [`Datetime`].
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::DeserializeError` with the path of the offending
+    /// key, such as `author.name` or `tags[2]`, if the front matter does
+    /// not match `T`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use frontmatter_gen::{parser, Format};
+    /// use serde::Deserialize;
+    ///
+    /// #[derive(Deserialize)]
+    /// struct Post {
+    ///     title: String,
+    ///     tags: Vec<String>,
+    /// }
+    ///
+    /// let fm = parser::parse("title: Hello\ntags: [rust]", Format::Yaml)?;
+    /// let post: Post = fm.deserialize_into()?;
+    /// assert_eq!(post.title, "Hello");
+    /// assert_eq!(post.tags, ["rust"]);
+    /// # Ok::<(), frontmatter_gen::Error>(())
+    /// ```
+    pub fn deserialize_into<T: DeserializeOwned>(
+        &self,
+    ) -> Result<T, Error> {
+        from_value(Value::Object(Box::new(self.clone())))
+    }
+}
+
+impl Value {
+    /// Returns the `Unexpected` description of this value for errors.
+    fn unexpected(&self) -> Unexpected<'_> {
+        match self {
+            Value::Null => Unexpected::Unit,
+            Value::String(s) => Unexpected::Str(s),
+            Value::Integer(i) => Unexpected::Signed(*i),
+            Value::Float(f) => Unexpected::Float(*f),
+            Value::DateTime(_) => Unexpected::Other("datetime"),
+            Value::Boolean(b) => Unexpected::Bool(*b),
+            Value::Array(_) => Unexpected::Seq,
+            Value::Object(_) => Unexpected::Map,
+            Value::Tagged(..) => Unexpected::Other("tagged value"),
+        }
+    }
+}
+
+/// Visits the entries of a map, checking that all of them were consumed.
+fn visit_map<'de, I, K, V, Vis>(
+    entries: I,
+    visitor: Vis,
+) -> Result<Vis::Value, Error>
+where
+    I: Iterator<Item = (K, V)>,
+    K: IntoDeserializer<'de, Error>,
+    V: IntoDeserializer<'de, Error>,
+    Vis: Visitor<'de>,
+{
+    let mut map = MapDeserializer::new(entries);
+    let value = visitor.visit_map(&mut map)?;
+    map.end()?;
+    Ok(value)
+}
+
+impl<'de> de::Deserializer<'de> for Value {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(
+        self,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        match self {
+            Value::Null => visitor.visit_unit(),
+            Value::String(s) => visitor.visit_string(s),
+            Value::Integer(i) => visitor.visit_i64(i),
+            Value::Float(f) => visitor.visit_f64(f),
+            // The TOML struct form lets `Value` and `toml::Value` targets
+            // keep the datetime; string targets go through
+            // `deserialize_string` instead.
+            Value::DateTime(dt) => visit_map(
+                iter::once((TOML_DATETIME_FIELD, dt.to_string())),
+                visitor,
+            ),
+            Value::Boolean(b) => visitor.visit_bool(b),
+            Value::Array(arr) => {
+                let mut seq = SeqDeserializer::new(arr.into_iter());
+                let value = visitor.visit_seq(&mut seq)?;
+                seq.end()?;
+                Ok(value)
+            }
+            Value::Object(obj) => visit_map(obj.into_iter(), visitor),
+            Value::Tagged(_, value) => value.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_str<V: Visitor<'de>>(
+        self,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        self.deserialize_string(visitor)
+    }
+
+    fn deserialize_string<V: Visitor<'de>>(
+        self,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        match self {
+            Value::DateTime(dt) => visitor.visit_string(dt.to_string()),
+            Value::Tagged(_, value) => {
+                value.deserialize_string(visitor)
+            }
+            _ => self.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(
+        self,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        match self {
+            Value::Null => visitor.visit_none(),
+            _ => visitor.visit_some(self),
+        }
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        if name != TOML_DATETIME_NAME {
+            return match self {
+                Value::Tagged(_, value) => {
+                    value.deserialize_struct(name, fields, visitor)
+                }
+                _ => self.deserialize_any(visitor),
+            };
+        }
+
+        // `Datetime` reads itself from a one-field map, which is built
+        // from a native datetime or a string in RFC 3339 form.
+        let dt = match self {
+            Value::DateTime(dt) => dt,
+            Value::String(ref s) => {
+                s.parse::<Datetime>().map_err(|_| {
+                    <Error as de::Error>::invalid_value(
+                        self.unexpected(),
+                        &"a datetime",
+                    )
+                })?
+            }
+            Value::Tagged(_, value) => {
+                return value.deserialize_struct(name, fields, visitor)
+            }
+            _ => {
+                return Err(<Error as de::Error>::invalid_type(
+                    self.unexpected(),
+                    &"a datetime",
+                ))
+            }
+        };
+        visit_map(
+            iter::once((TOML_DATETIME_FIELD, dt.to_string())),
+            visitor,
+        )
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        match self {
+            Value::String(variant) => {
+                visitor.visit_enum(variant.into_deserializer())
+            }
+            // A YAML tag names the variant, as in `!Circle { r: 1 }`.
+            Value::Tagged(tag, value) => {
+                let variant = tag.strip_prefix('!').unwrap_or(&tag);
+                visitor.visit_enum(MapAccessDeserializer::new(
+                    MapDeserializer::new(iter::once((
+                        variant.to_string(),
+                        *value,
+                    ))),
+                ))
+            }
+            Value::Object(obj) if obj.len() == 1 => {
+                visitor.visit_enum(MapAccessDeserializer::new(
+                    MapDeserializer::new(obj.into_iter()),
+                ))
+            }
+            _ => Err(<Error as de::Error>::invalid_type(
+                self.unexpected(),
+                &"a string or a map with a single key",
+            )),
+        }
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'de>>(
+        self,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        visitor.visit_unit()
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char
+        bytes byte_buf unit unit_struct seq tuple tuple_struct
+        map identifier
+    }
+}
+
+impl IntoDeserializer<'_, Error> for Value {
+    type Deserializer = Self;
+
+    fn into_deserializer(self) -> Self {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parser::parse, types::Format};
+    use serde::Deserialize;
+    use std::collections::BTreeMap;
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Post {
+        title: String,
+        draft: bool,
+        weight: Option<u32>,
+        rating: f64,
+        date: Datetime,
+        tags: Vec<String>,
+        author: Author,
+    }
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Author {
+        name: String,
+        #[serde(default)]
+        links: BTreeMap<String, String>,
+    }
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    enum Shape {
+        Point,
+        Circle { r: i64 },
+        Label(String),
+    }
+
+    #[test]
+    fn test_deserialize_into_struct() {
+        let fm = parse(
+            r#"
+title = "Hello"
+draft = false
+rating = 4
+date = 2024-01-02T03:04:05Z
+tags = ["rust", "serde"]
+
+[author]
+name = "Jane"
+links = { site = "https://example.com" }
+"#,
+            Format::Toml,
+        )
+        .unwrap();
+
+        let post: Post = fm.deserialize_into().unwrap();
+        assert_eq!(post.title, "Hello");
+        assert!(!post.draft);
+        assert_eq!(post.weight, None);
+        assert!((post.rating - 4.0).abs() < f64::EPSILON);
+        assert_eq!(post.date.to_string(), "2024-01-02T03:04:05Z");
+        assert_eq!(post.tags, ["rust", "serde"]);
+        assert_eq!(post.author.links["site"], "https://example.com");
+    }
+
+    #[test]
+    fn test_datetimes_deserialize_from_strings_and_into_strings() {
+        #[derive(Deserialize)]
+        struct Dates {
+            parsed: Datetime,
+            text: String,
+        }
+
+        let fm = parse(
+            "parsed = \"2024-01-02\"\ntext = 2024-01-02",
+            Format::Toml,
+        )
+        .unwrap();
+        let dates: Dates = fm.deserialize_into().unwrap();
+        assert_eq!(dates.parsed.to_string(), "2024-01-02");
+        assert_eq!(dates.text, "2024-01-02");
+    }
+
+    #[test]
+    fn test_deserialize_enums() {
+        let fm = parse(
+            "a: Point\nb: { Circle: { r: 2 } }\nc: !Label hi\n",
+            Format::Yaml,
+        )
+        .unwrap();
+        let shapes: BTreeMap<String, Shape> =
+            fm.deserialize_into().unwrap();
+        assert_eq!(shapes["a"], Shape::Point);
+        assert_eq!(shapes["b"], Shape::Circle { r: 2 });
+        assert_eq!(shapes["c"], Shape::Label("hi".to_string()));
+    }
+
+    #[test]
+    fn test_errors_carry_the_key_path() {
+        #[derive(Debug, Deserialize)]
+        struct Doc {
+            #[allow(dead_code)]
+            author: Author,
+            #[allow(dead_code)]
+            tags: Vec<String>,
+        }
+
+        let fm =
+            parse("author: { name: 42 }\ntags: []\n", Format::Yaml)
+                .unwrap();
+        let err = fm.deserialize_into::<Doc>().unwrap_err();
+        assert!(matches!(
+            &err,
+            Error::DeserializeError { path, .. } if path == "author.name"
+        ));
+
+        let fm = parse(
+            "author: { name: A }\ntags: [a, [b]]\n",
+            Format::Yaml,
+        )
+        .unwrap();
+        let err = fm.deserialize_into::<Doc>().unwrap_err();
+        assert!(err.to_string().starts_with(
+            "Failed to deserialise front matter at `tags[1]`"
+        ));
+
+        let fm = parse("tags: []\n", Format::Yaml).unwrap();
+        let err = fm.deserialize_into::<Doc>().unwrap_err();
+        assert!(err.to_string().contains("missing field `author`"));
+    }
+}
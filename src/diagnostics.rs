@@ -0,0 +1,1491 @@
+// diagnostics.rs
+
+//! # Diagnostics Module
+//!
+//! [`validate_input`](crate::validate_input) and
+//! [`validate_frontmatter`](crate::parser::validate_frontmatter) stop at
+//! the first problem they find, which suits parsing but makes fixing a
+//! document a game of whack-a-mole. This module instead collects every
+//! problem in one pass: oversized content, values that break the
+//! [`SecurityPolicy`], excessive nesting, too many keys, missing fields
+//! and schema violations.
+//!
+//! Each [`Diagnostic`] carries a [`Severity`], a stable [`Code`] that
+//! tools can match on, and a location: the key path for problems in the
+//! front matter's data, or the line and column for problems in its text.
+//!
+//! ## Usage Example
+//!
+//! ```rust
+//! use frontmatter_gen::{
+//!     diagnostics::{self, Code, Severity},
+//!     ParseOptions, SecurityPolicy,
+//! };
+//!
+//! let content = "---\ntitle: Post\nimage: ../secret.png\n---\nSee [the guide](../guide.md).";
+//! let options = ParseOptions {
+//!     security: SecurityPolicy::strict(),
+//!     ..ParseOptions::default()
+//! };
+//! let (frontmatter, diagnostics) = diagnostics::check(content, &options);
+//!
+//! assert!(frontmatter.is_some());
+//! assert!(diagnostics.has_errors());
+//!
+//! // Relative links in the body are not front matter, so only the
+//! // image is reported.
+//! assert_eq!(diagnostics.len(), 1);
+//! let first = &diagnostics.as_slice()[0];
+//! assert_eq!(first.severity, Severity::Error);
+//! assert_eq!(first.code, Code::PathTraversal);
+//! assert_eq!(first.path.as_deref(), Some("image"));
+//! assert_eq!(first.context.as_ref().and_then(|c| c.line), Some(3));
+//! ```
+
+use indexmap::IndexMap;
+use serde_json::{json, Value as JsonValue};
+use std::fmt;
+
+use crate::{
+    error::{Category, Context, Error},
+    extractor::{find_block, Block, Delimiters},
+    parser,
+    schema::{FieldSchema, Rule, Schema},
+    security::{PolicyRule, SecurityPolicy},
+    types::{Format, Frontmatter, Value},
+    ParseOptions,
+};
+
+/// How serious a [`Diagnostic`] is.
+///
+/// Severities are ordered, so `Severity::Warning < Severity::Error`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Severity {
+    /// Worth knowing, but not a problem.
+    Info,
+    /// Probably a mistake, but the document is still usable.
+    Warning,
+    /// The document fails validation.
+    Error,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Severity::Info => "info",
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        })
+    }
+}
+
+/// What kind of problem a [`Diagnostic`] reports.
+///
+/// The string form from [`Code::as_str`] is stable across releases.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Code {
+    /// The front matter could not be found or parsed (`parse-error`).
+    ParseError,
+    /// The content is larger than the size limit (`content-too-large`).
+    ContentTooLarge,
+    /// A key or string value contains a null byte (`null-byte`).
+    NullByte,
+    /// A path-like key holds a path that climbs out with `..`
+    /// (`path-traversal`).
+    PathTraversal,
+    /// A string value is longer than the security policy allows
+    /// (`max-string-length`).
+    MaxStringLength,
+    /// A YAML value carries a tag (`yaml-tag`).
+    YamlTag,
+    /// The YAML defines an anchor or uses an alias (`yaml-anchor`).
+    YamlAnchor,
+    /// A value is nested deeper than the depth limit
+    /// (`nesting-too-deep`).
+    NestingTooDeep,
+    /// The front matter has more keys than the key limit
+    /// (`too-many-keys`).
+    TooManyKeys,
+    /// YAML aliases expand the front matter past a limit
+    /// (`expansion-limit`).
+    ExpansionLimit,
+    /// A field asked for on the command line is missing
+    /// (`missing-field`).
+    MissingField,
+    /// A schema rule is broken (`schema-required`, `schema-type`,
+    /// `schema-enum`, `schema-pattern`, `schema-length` or
+    /// `schema-range`).
+    Schema(Rule),
+    /// A field is not declared in the schema, so it was not checked
+    /// (`undeclared-field`).
+    UndeclaredField,
+    /// The document does not open with a fence or format that is
+    /// recognised (`unknown-format`).
+    UnknownFormat,
+    /// A value breaks a validation rule (`invalid-value`).
+    InvalidValue,
+    /// A key asked for does not exist (`key-not-found`).
+    KeyNotFound,
+    /// A key path is malformed or does not fit the front matter
+    /// (`invalid-path`).
+    InvalidPath,
+    /// Two values could not be merged (`merge-conflict`).
+    MergeConflict,
+    /// A value could not be converted to another format
+    /// (`conversion-error`).
+    ConversionError,
+    /// The front matter could not be edited in place (`edit-error`).
+    EditError,
+    /// A JSON Patch could not be applied (`patch-error`).
+    PatchError,
+    /// The front matter does not fit the type it was read into
+    /// (`deserialize-error`).
+    DeserializeError,
+    /// A parse option or its environment variable is invalid
+    /// (`invalid-option`).
+    InvalidOption,
+    /// A file or directory could not be read or written (`io-error`).
+    IoError,
+    /// An error with no more specific code; its [`Category`] says what
+    /// kind it is (`other`).
+    Other,
+}
+
+impl Code {
+    /// Returns the stable name of the code, such as `too-many-keys`.
+    #[must_use]
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            Code::ParseError => "parse-error",
+            Code::ContentTooLarge => "content-too-large",
+            Code::NullByte => "null-byte",
+            Code::PathTraversal => "path-traversal",
+            Code::MaxStringLength => "max-string-length",
+            Code::YamlTag => "yaml-tag",
+            Code::YamlAnchor => "yaml-anchor",
+            Code::NestingTooDeep => "nesting-too-deep",
+            Code::TooManyKeys => "too-many-keys",
+            Code::ExpansionLimit => "expansion-limit",
+            Code::MissingField => "missing-field",
+            Code::Schema(Rule::Required) => "schema-required",
+            Code::Schema(Rule::Type) => "schema-type",
+            Code::Schema(Rule::Enum) => "schema-enum",
+            Code::Schema(Rule::Pattern) => "schema-pattern",
+            Code::Schema(Rule::Length) => "schema-length",
+            Code::Schema(Rule::Range) => "schema-range",
+            Code::UndeclaredField => "undeclared-field",
+            Code::UnknownFormat => "unknown-format",
+            Code::InvalidValue => "invalid-value",
+            Code::KeyNotFound => "key-not-found",
+            Code::InvalidPath => "invalid-path",
+            Code::MergeConflict => "merge-conflict",
+            Code::ConversionError => "conversion-error",
+            Code::EditError => "edit-error",
+            Code::PatchError => "patch-error",
+            Code::DeserializeError => "deserialize-error",
+            Code::InvalidOption => "invalid-option",
+            Code::IoError => "io-error",
+            Code::Other => "other",
+        }
+    }
+
+    /// Returns the category of the problem, matching
+    /// [`Error::category`] for the errors that report the same problem.
+    #[must_use]
+    pub const fn category(&self) -> Category {
+        match self {
+            Code::ParseError | Code::UnknownFormat => Category::Parsing,
+            Code::ContentTooLarge
+            | Code::NestingTooDeep
+            | Code::TooManyKeys
+            | Code::ExpansionLimit
+            | Code::InvalidOption
+            | Code::IoError => Category::Configuration,
+            Code::ConversionError
+            | Code::EditError
+            | Code::PatchError
+            | Code::DeserializeError => Category::Conversion,
+            Code::NullByte
+            | Code::PathTraversal
+            | Code::MaxStringLength
+            | Code::YamlTag
+            | Code::YamlAnchor
+            | Code::MissingField
+            | Code::Schema(_)
+            | Code::UndeclaredField
+            | Code::InvalidValue
+            | Code::KeyNotFound
+            | Code::InvalidPath
+            | Code::MergeConflict
+            | Code::Other => Category::Validation,
+        }
+    }
+
+    /// Returns a suggestion for fixing the problem, if there is one
+    /// that does not depend on the document.
+    #[must_use]
+    pub const fn suggestion(&self) -> Option<&'static str> {
+        Some(match self {
+            Code::ContentTooLarge => {
+                "Shorten the document or raise `MAX_FRONTMATTER_SIZE`."
+            }
+            Code::NullByte => "Remove the null byte.",
+            Code::PathTraversal => {
+                "Use a path from the site root instead of one that climbs out with `../`."
+            }
+            Code::MaxStringLength => {
+                "Shorten the value, or move long text into the body."
+            }
+            Code::YamlTag => "Remove the tag and write the value plainly.",
+            Code::YamlAnchor => {
+                "Write the value out in full instead of using an anchor or alias."
+            }
+            Code::NestingTooDeep => {
+                "Flatten the value or raise `MAX_NESTING_DEPTH`."
+            }
+            Code::TooManyKeys => {
+                "Remove unused fields or group related ones into objects."
+            }
+            Code::ExpansionLimit => {
+                "Use fewer aliases, or raise the limits in `ParseOptions`."
+            }
+            Code::MissingField | Code::Schema(Rule::Required) => {
+                "Add the field to the front matter."
+            }
+            Code::Schema(Rule::Type) => {
+                "Change the value to the type the schema expects."
+            }
+            Code::Schema(Rule::Enum) => {
+                "Use one of the values the schema allows."
+            }
+            Code::UndeclaredField => {
+                "Declare the field in the schema, or remove it."
+            }
+            Code::ParseError
+            | Code::Schema(Rule::Pattern | Rule::Length | Rule::Range)
+            | Code::UnknownFormat
+            | Code::InvalidValue
+            | Code::KeyNotFound
+            | Code::InvalidPath
+            | Code::MergeConflict
+            | Code::ConversionError
+            | Code::EditError
+            | Code::PatchError
+            | Code::DeserializeError
+            | Code::InvalidOption
+            | Code::IoError
+            | Code::Other => return None,
+        })
+    }
+}
+
+impl From<PolicyRule> for Code {
+    fn from(rule: PolicyRule) -> Self {
+        match rule {
+            PolicyRule::PathTraversal => Code::PathTraversal,
+            PolicyRule::NullByte => Code::NullByte,
+            PolicyRule::MaxStringLength => Code::MaxStringLength,
+            PolicyRule::YamlTag => Code::YamlTag,
+            PolicyRule::YamlAnchor => Code::YamlAnchor,
+        }
+    }
+}
+
+impl fmt::Display for Code {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A single problem found while validating a document.
+#[derive(Debug, Clone)]
+pub struct Diagnostic {
+    /// How serious the problem is.
+    pub severity: Severity,
+    /// What kind of problem it is.
+    pub code: Code,
+    /// The category of the problem, as for an [`Error`].
+    pub category: Category,
+    /// A human-readable description of the problem.
+    pub message: String,
+    /// How the problem might be fixed.
+    pub suggestion: Option<String>,
+    /// The path of the offending key, such as `author.name` or
+    /// `tags[2]`, for problems in the front matter's data.
+    pub path: Option<String>,
+    /// The line and column of the problem, for problems in the text.
+    pub context: Option<Context>,
+}
+
+impl Diagnostic {
+    /// Creates a diagnostic without a location, taking its category
+    /// and suggestion from the code.
+    #[must_use]
+    pub fn new(
+        severity: Severity,
+        code: Code,
+        message: impl Into<String>,
+    ) -> Self {
+        Self {
+            severity,
+            code,
+            category: code.category(),
+            message: message.into(),
+            suggestion: code.suggestion().map(str::to_string),
+            path: None,
+            context: None,
+        }
+    }
+
+    /// Creates an error diagnostic from an error, keeping its category
+    /// and position.
+    ///
+    /// Each kind of error gets its own code, such as
+    /// [`Code::ContentTooLarge`] or [`Code::KeyNotFound`]; parse errors
+    /// without one get [`Code::ParseError`], and security policy
+    /// violations the code of their rule and the path of their key.
+    /// Only `Error::Other` gets [`Code::Other`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use frontmatter_gen::{diagnostics::{Code, Diagnostic}, error::Category, extract};
+    ///
+    /// let err = extract("---\ntitle: [oops\n---\n").unwrap_err();
+    /// let diagnostic = Diagnostic::from_error(&err);
+    /// assert_eq!(diagnostic.code, Code::ParseError);
+    /// assert_eq!(diagnostic.category, Category::Parsing);
+    /// assert!(diagnostic.context.is_some());
+    /// ```
+    #[must_use]
+    pub fn from_error(err: &Error) -> Self {
+        let (code, path) = match err.inner() {
+            Error::NestingTooDeep { .. } => {
+                (Code::NestingTooDeep, None)
+            }
+            Error::ExpansionLimitExceeded { .. } => {
+                (Code::ExpansionLimit, None)
+            }
+            Error::PolicyViolation { key, rule, .. } => {
+                ((*rule).into(), Some(key.clone()))
+            }
+            inner => (
+                match inner {
+                    Error::ContentTooLarge { .. } => {
+                        Code::ContentTooLarge
+                    }
+                    Error::InvalidFormat
+                    | Error::UnsupportedFormat { .. } => {
+                        Code::UnknownFormat
+                    }
+                    Error::ValidationError(_) => Code::InvalidValue,
+                    Error::KeyNotFound(_) => Code::KeyNotFound,
+                    Error::InvalidPath(_) => Code::InvalidPath,
+                    Error::MergeConflict(_) => Code::MergeConflict,
+                    Error::ConversionError(_) => Code::ConversionError,
+                    Error::EditError(_) => Code::EditError,
+                    Error::PatchError(_) => Code::PatchError,
+                    Error::DeserializeError { .. } => {
+                        Code::DeserializeError
+                    }
+                    Error::InvalidOption { .. } => Code::InvalidOption,
+                    Error::IoError { .. } => Code::IoError,
+                    Error::Other(_) => Code::Other,
+                    _ => Code::ParseError,
+                },
+                None,
+            ),
+        };
+        Self {
+            category: err.category(),
+            path,
+            context: err.context().cloned(),
+            ..Self::new(Severity::Error, code, err.summary())
+        }
+    }
+
+    /// Sets the key path the diagnostic refers to.
+    #[must_use]
+    pub fn at_path(mut self, path: impl Into<String>) -> Self {
+        self.path = Some(path.into());
+        self
+    }
+
+    /// Sets the position in the text the diagnostic refers to.
+    #[must_use]
+    pub fn at_position(mut self, context: Context) -> Self {
+        self.context = Some(context);
+        self
+    }
+
+    /// Renders the diagnostic in the style of compiler diagnostics.
+    ///
+    /// Diagnostics with a position show the offending line with a caret
+    /// under the column, as [`Error::render`] does, in place of the
+    /// position that ends the [`Display`](fmt::Display) form.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the source, usually its file path.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use frontmatter_gen::{diagnostics, ParseOptions, SecurityPolicy};
+    ///
+    /// let (_, diagnostics) = diagnostics::check(
+    ///     "---\nimage: ../a.png\n---\n",
+    ///     &ParseOptions {
+    ///         security: SecurityPolicy::strict(),
+    ///         ..ParseOptions::default()
+    ///     },
+    /// );
+    /// assert_eq!(
+    ///     diagnostics.as_slice()[0].render("post.md"),
+    ///     "error[path-traversal]: image: Path `../a.png` climbs out with `..`
+    ///  --> post.md:2:8
+    ///   |
+    /// 2 | image: ../a.png
+    ///   |        ^"
+    /// );
+    /// ```
+    #[must_use]
+    pub fn render(&self, name: &str) -> String {
+        match self.context.as_ref().and_then(|c| c.excerpt(name)) {
+            Some(excerpt) => format!("{}\n{excerpt}", self.headline()),
+            None => self.to_string(),
+        }
+    }
+
+    /// Returns the severity, code, path and message, without the
+    /// position.
+    fn headline(&self) -> String {
+        let mut headline =
+            format!("{}[{}]: ", self.severity, self.code);
+        if let Some(path) = &self.path {
+            headline.push_str(path);
+            headline.push_str(": ");
+        }
+        headline.push_str(&self.message);
+        headline
+    }
+
+    /// Returns the diagnostic as a JSON object for tools, with the file
+    /// it was found in.
+    ///
+    /// The object has the keys `file`, `line`, `column`, `severity`,
+    /// `code`, `category`, `message`, `path` and `suggestion`. Unknown
+    /// values are `null`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use frontmatter_gen::diagnostics::{Code, Diagnostic, Severity};
+    ///
+    /// let diagnostic =
+    ///     Diagnostic::new(Severity::Error, Code::MissingField, "Missing required field: date");
+    /// let json = diagnostic.to_json("post.md");
+    /// assert_eq!(json["file"], "post.md");
+    /// assert_eq!(json["code"], "missing-field");
+    /// assert_eq!(json["category"], "validation");
+    /// assert!(json["line"].is_null());
+    /// ```
+    #[must_use]
+    pub fn to_json(&self, file: &str) -> JsonValue {
+        let position = self.context.as_ref();
+        json!({
+            "file": file,
+            "line": position.and_then(|c| c.line),
+            "column": position.and_then(|c| c.column),
+            "severity": self.severity.to_string(),
+            "code": self.code.as_str(),
+            "category": self.category.to_string(),
+            "message": self.message,
+            "path": self.path,
+            "suggestion": self.suggestion,
+        })
+    }
+
+    /// Returns the diagnostic as a SARIF result.
+    fn to_sarif(&self, file: &str) -> JsonValue {
+        let mut location = json!({
+            "physicalLocation": {
+                "artifactLocation": { "uri": file.replace('\\', "/") },
+            },
+        });
+        if let Some(Context {
+            line: Some(line),
+            column: Some(column),
+            ..
+        }) = &self.context
+        {
+            location["physicalLocation"]["region"] = json!({
+                "startLine": line,
+                "startColumn": column,
+            });
+        }
+        if let Some(path) = &self.path {
+            location["logicalLocations"] = json!([{
+                "fullyQualifiedName": path,
+                "kind": "member",
+            }]);
+        }
+
+        let mut properties =
+            json!({ "category": self.category.to_string() });
+        if let Some(suggestion) = &self.suggestion {
+            properties["suggestion"] = json!(suggestion);
+        }
+
+        json!({
+            "ruleId": self.code.as_str(),
+            "level": match self.severity {
+                Severity::Error => "error",
+                Severity::Warning => "warning",
+                Severity::Info => "note",
+            },
+            "message": { "text": self.message },
+            "locations": [location],
+            "properties": properties,
+        })
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.headline())?;
+        if let Some(Context {
+            line: Some(line),
+            column: Some(column),
+            ..
+        }) = &self.context
+        {
+            write!(f, " (at {line}:{column})")?;
+        }
+        Ok(())
+    }
+}
+
+/// A collection of diagnostics, in the order they were found.
+///
+/// The `check_*` methods each add every problem of one kind, so a
+/// caller can run exactly the checks it needs.
+#[derive(Debug, Clone, Default)]
+pub struct Diagnostics {
+    items: Vec<Diagnostic>,
+}
+
+impl Diagnostics {
+    /// Creates an empty collection.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a diagnostic.
+    pub fn push(&mut self, diagnostic: Diagnostic) {
+        self.items.push(diagnostic);
+    }
+
+    /// Returns the diagnostics in the order they were found.
+    #[must_use]
+    pub fn as_slice(&self) -> &[Diagnostic] {
+        &self.items
+    }
+
+    /// Returns an iterator over the diagnostics.
+    pub fn iter(&self) -> std::slice::Iter<'_, Diagnostic> {
+        self.items.iter()
+    }
+
+    /// Returns the number of diagnostics.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    /// Returns `true` if nothing was found.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// Returns the number of diagnostics with the given severity.
+    #[must_use]
+    pub fn count(&self, severity: Severity) -> usize {
+        self.iter().filter(|d| d.severity == severity).count()
+    }
+
+    /// Returns `true` if any diagnostic is an error.
+    #[must_use]
+    pub fn has_errors(&self) -> bool {
+        self.count(Severity::Error) > 0
+    }
+
+    /// Checks the raw text of a document.
+    ///
+    /// Reports content larger than `max_size`. The text is not otherwise
+    /// inspected; see [`Diagnostics::check_security`] for rules about
+    /// front matter values.
+    pub fn check_input(&mut self, content: &str, max_size: usize) {
+        if content.len() > max_size {
+            self.push(Diagnostic::new(
+                Severity::Error,
+                Code::ContentTooLarge,
+                format!(
+                    "Content is {} bytes. The maximum allowed is {max_size}.",
+                    content.len()
+                ),
+            ));
+        }
+    }
+
+    /// Checks parsed front matter against a security policy.
+    ///
+    /// Reports every violation of `policy`, with the path of its key
+    /// and, when the offending text can be found in `raw`, its position.
+    ///
+    /// # Arguments
+    ///
+    /// * `raw` - The raw front matter `frontmatter` was parsed from.
+    /// * `format` - The format of `raw`.
+    /// * `frontmatter` - The parsed front matter.
+    /// * `policy` - The rules to check.
+    pub fn check_security(
+        &mut self,
+        raw: &str,
+        format: Format,
+        frontmatter: &Frontmatter,
+        policy: &SecurityPolicy,
+    ) {
+        self.push_violations(raw, 0, raw, format, frontmatter, policy);
+    }
+
+    /// Reports the violations of `policy` in front matter that starts
+    /// at byte `start` of `content`.
+    fn push_violations(
+        &mut self,
+        content: &str,
+        start: usize,
+        raw: &str,
+        format: Format,
+        frontmatter: &Frontmatter,
+        policy: &SecurityPolicy,
+    ) {
+        for violation in policy.violations(raw, format, frontmatter) {
+            let mut diagnostic = Diagnostic::new(
+                Severity::Error,
+                violation.rule.into(),
+                violation.message,
+            )
+            .at_path(violation.key);
+            if let Some(offset) = violation.offset {
+                diagnostic = diagnostic.at_position(
+                    Context::from_offset(content, start + offset),
+                );
+            }
+            self.push(diagnostic);
+        }
+    }
+
+    /// Checks the shape of parsed front matter.
+    ///
+    /// Reports more than `max_keys` top-level keys, and every value
+    /// nested deeper than `max_depth`, counting top-level values as depth
+    /// one. Values inside a value that is already too deep are not
+    /// reported again.
+    pub fn check_structure(
+        &mut self,
+        frontmatter: &Frontmatter,
+        max_depth: usize,
+        max_keys: usize,
+    ) {
+        if frontmatter.len() > max_keys {
+            self.push(Diagnostic::new(
+                Severity::Error,
+                Code::TooManyKeys,
+                Error::ContentTooLarge {
+                    size: frontmatter.len(),
+                    max: max_keys,
+                }
+                .to_string(),
+            ));
+        }
+
+        for (key, value) in frontmatter.iter() {
+            self.check_depth(value, key, 1, max_depth);
+        }
+    }
+
+    /// Reports a value, or the first values below it, that are nested
+    /// deeper than `max_depth`.
+    fn check_depth(
+        &mut self,
+        value: &Value,
+        path: &str,
+        depth: usize,
+        max_depth: usize,
+    ) {
+        if depth > max_depth {
+            self.push(
+                Diagnostic::new(
+                    Severity::Error,
+                    Code::NestingTooDeep,
+                    Error::NestingTooDeep {
+                        depth,
+                        max: max_depth,
+                    }
+                    .to_string(),
+                )
+                .at_path(path),
+            );
+            return;
+        }
+
+        match value {
+            Value::Array(items) => {
+                for (index, item) in items.iter().enumerate() {
+                    self.check_depth(
+                        item,
+                        &format!("{path}[{index}]"),
+                        depth + 1,
+                        max_depth,
+                    );
+                }
+            }
+            Value::Object(object) => {
+                for (key, item) in object.iter() {
+                    self.check_depth(
+                        item,
+                        &format!("{path}.{key}"),
+                        depth + 1,
+                        max_depth,
+                    );
+                }
+            }
+            Value::Tagged(_, inner) => {
+                self.check_depth(inner, path, depth, max_depth);
+            }
+            _ => {}
+        }
+    }
+
+    /// Reports every top-level field in `fields` that is missing.
+    pub fn check_required<S: AsRef<str>>(
+        &mut self,
+        frontmatter: &Frontmatter,
+        fields: impl IntoIterator<Item = S>,
+    ) {
+        for field in fields {
+            let field = field.as_ref();
+            if !frontmatter.contains_key(field) {
+                self.push(Diagnostic::new(
+                    Severity::Error,
+                    Code::MissingField,
+                    format!("Missing required field: {field}"),
+                ));
+            }
+        }
+    }
+
+    /// Checks front matter against a schema.
+    ///
+    /// Every [`Violation`](crate::schema::Violation) becomes an error.
+    /// Fields the schema does not declare are reported as warnings,
+    /// since they are not checked at all.
+    pub fn check_schema(
+        &mut self,
+        schema: &Schema,
+        frontmatter: &Frontmatter,
+    ) {
+        for violation in schema.validate(frontmatter) {
+            self.push(
+                Diagnostic::new(
+                    Severity::Error,
+                    Code::Schema(violation.rule),
+                    violation.message,
+                )
+                .at_path(violation.path),
+            );
+        }
+        self.check_undeclared(&schema.fields, frontmatter, "");
+    }
+
+    /// Reports the keys of `object` that `fields` does not declare, and
+    /// looks for more in the ones it does.
+    fn check_undeclared(
+        &mut self,
+        fields: &IndexMap<String, FieldSchema>,
+        object: &Frontmatter,
+        prefix: &str,
+    ) {
+        for (key, value) in object.iter() {
+            let path = if prefix.is_empty() {
+                key.clone()
+            } else {
+                format!("{prefix}.{key}")
+            };
+            match fields.get(key) {
+                Some(field) => {
+                    self.check_undeclared_in(field, value, &path);
+                }
+                None => self.push(
+                    Diagnostic::new(
+                        Severity::Warning,
+                        Code::UndeclaredField,
+                        "field is not declared in the schema",
+                    )
+                    .at_path(path),
+                ),
+            }
+        }
+    }
+
+    /// Looks for undeclared fields inside a declared value.
+    fn check_undeclared_in(
+        &mut self,
+        field: &FieldSchema,
+        value: &Value,
+        path: &str,
+    ) {
+        match value {
+            Value::Object(object) => {
+                if let Some(fields) = &field.fields {
+                    self.check_undeclared(fields, object, path);
+                }
+            }
+            Value::Array(items) => {
+                if let Some(item_field) = &field.items {
+                    for (index, item) in items.iter().enumerate() {
+                        self.check_undeclared_in(
+                            item_field,
+                            item,
+                            &format!("{path}[{index}]"),
+                        );
+                    }
+                }
+            }
+            Value::Tagged(_, inner) => {
+                self.check_undeclared_in(field, inner, path);
+            }
+            _ => {}
+        }
+    }
+}
+
+impl fmt::Display for Diagnostics {
+    /// Writes one diagnostic per line.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for diagnostic in self {
+            writeln!(f, "{diagnostic}")?;
+        }
+        Ok(())
+    }
+}
+
+impl IntoIterator for Diagnostics {
+    type Item = Diagnostic;
+    type IntoIter = std::vec::IntoIter<Diagnostic>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.items.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a Diagnostics {
+    type Item = &'a Diagnostic;
+    type IntoIter = std::slice::Iter<'a, Diagnostic>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.items.iter()
+    }
+}
+
+impl Extend<Diagnostic> for Diagnostics {
+    fn extend<I: IntoIterator<Item = Diagnostic>>(&mut self, iter: I) {
+        self.items.extend(iter);
+    }
+}
+
+/// Extracts and parses the front matter of a document, collecting every
+/// problem instead of stopping at the first.
+///
+/// This runs [`Diagnostics::check_input`] on the whole document and, if
+/// the front matter parses, [`Diagnostics::check_security`] with
+/// `options.security` and [`Diagnostics::check_structure`] on it, with
+/// the depth and key limits of `options`. A document whose front matter
+/// cannot be found or parsed gets a [`Code::ParseError`] diagnostic,
+/// with the position of the error when the parser reports one.
+///
+/// # Arguments
+///
+/// * `content` - The whole document.
+/// * `options` - The limits and security policy to check against. The
+///   depth and key limits are checked when `options.validate` is set.
+///
+/// # Returns
+///
+/// The parsed front matter, if there is any, and the diagnostics found.
+///
+/// # Examples
+///
+/// ```rust
+/// use frontmatter_gen::{diagnostics::{self, Code}, ParseOptions};
+///
+/// let (frontmatter, diagnostics) =
+///     diagnostics::check("---\ntitle: [oops\n---\n", &ParseOptions::default());
+/// assert!(frontmatter.is_none());
+/// assert_eq!(diagnostics.as_slice()[0].code, Code::ParseError);
+/// ```
+#[must_use]
+pub fn check(
+    content: &str,
+    options: &ParseOptions,
+) -> (Option<Frontmatter>, Diagnostics) {
+    check_block(content, options, find_block(content))
+}
+
+/// Extracts and parses the front matter of a document found with the
+/// given delimiters, collecting every problem as [`check`] does.
+///
+/// The front matter is parsed in the format its delimiter names.
+///
+/// # Examples
+///
+/// ```rust
+/// use frontmatter_gen::{
+///     diagnostics,
+///     extractor::{Delimiter, Delimiters},
+///     Format, ParseOptions,
+/// };
+///
+/// let mut delimiters = Delimiters::default();
+/// delimiters.register(Delimiter::new(";;;", [";;;"], Format::Json));
+///
+/// let (frontmatter, diagnostics) = diagnostics::check_with_delimiters(
+///     ";;;\n{\"title\": \"Post\"}\n;;;\nBody",
+///     &ParseOptions::default(),
+///     &delimiters,
+/// );
+/// assert!(frontmatter.is_some());
+/// assert!(diagnostics.is_empty());
+/// ```
+#[must_use]
+pub fn check_with_delimiters(
+    content: &str,
+    options: &ParseOptions,
+    delimiters: &Delimiters,
+) -> (Option<Frontmatter>, Diagnostics) {
+    check_block(content, options, delimiters.find_block(content))
+}
+
+/// Checks a document whose front matter block has been looked for.
+fn check_block(
+    content: &str,
+    options: &ParseOptions,
+    block: Result<Block, Error>,
+) -> (Option<Frontmatter>, Diagnostics) {
+    let mut diagnostics = Diagnostics::new();
+    diagnostics.check_input(content, options.max_size.get());
+
+    // The structure is checked below, to report every problem
+    let parse_options = ParseOptions {
+        validate: false,
+        ..*options
+    };
+    let parsed = block.and_then(|block| {
+        let raw = &content[block.frontmatter.clone()];
+        parser::parse_with_options(
+            raw,
+            block.format,
+            Some(parse_options),
+        )
+        .map(|frontmatter| (frontmatter, block))
+        .map_err(|e| e.relocate(raw, content))
+    });
+
+    match parsed {
+        Ok((frontmatter, block)) => {
+            diagnostics.push_violations(
+                content,
+                block.frontmatter.start,
+                &content[block.frontmatter],
+                block.format,
+                &frontmatter,
+                &options.security,
+            );
+            if options.validate {
+                diagnostics.check_structure(
+                    &frontmatter,
+                    options.max_depth.get(),
+                    options.max_keys,
+                );
+            }
+            (Some(frontmatter), diagnostics)
+        }
+        Err(err) => {
+            diagnostics.push(Diagnostic::from_error(&err));
+            (None, diagnostics)
+        }
+    }
+}
+
+/// Builds a SARIF 2.1.0 log of the diagnostics found in each file, for
+/// code scanning services such as GitHub's.
+///
+/// Each [`Code`] that occurs becomes a rule, with its suggestion as the
+/// rule's help. Info diagnostics become SARIF notes, key paths become
+/// logical locations, and columns count Unicode code points.
+///
+/// # Examples
+///
+/// ```rust
+/// use frontmatter_gen::{diagnostics, ParseOptions, SecurityPolicy};
+///
+/// let options = ParseOptions { security: SecurityPolicy::strict(), ..ParseOptions::default() };
+/// let (_, found) = diagnostics::check("---\nimage: ../a.png\n---\n", &options);
+/// let log = diagnostics::to_sarif([("post.md", &found)]);
+/// let result = &log["runs"][0]["results"][0];
+/// assert_eq!(result["ruleId"], "path-traversal");
+/// assert_eq!(result["locations"][0]["physicalLocation"]["region"]["startLine"], 2);
+/// ```
+#[must_use]
+pub fn to_sarif<'a>(
+    files: impl IntoIterator<Item = (&'a str, &'a Diagnostics)>,
+) -> JsonValue {
+    let mut rules: IndexMap<&str, JsonValue> = IndexMap::new();
+    let mut results = Vec::new();
+
+    for (file, diagnostics) in files {
+        for diagnostic in diagnostics {
+            let code = diagnostic.code;
+            let _ = rules.entry(code.as_str()).or_insert_with(|| {
+                let mut rule = json!({
+                    "id": code.as_str(),
+                    "properties": { "category": code.category().to_string() },
+                });
+                if let Some(help) = code.suggestion() {
+                    rule["help"] = json!({ "text": help });
+                }
+                rule
+            });
+            results.push(diagnostic.to_sarif(file));
+        }
+    }
+
+    json!({
+        "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": env!("CARGO_PKG_NAME"),
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "informationUri": env!("CARGO_PKG_REPOSITORY"),
+                    "rules": rules.into_values().collect::<Vec<_>>(),
+                },
+            },
+            "columnKind": "unicodeCodePoints",
+            "results": results,
+        }],
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        parser::{parse, ExpansionLimit},
+        types::Format,
+    };
+
+    fn yaml(content: &str) -> Frontmatter {
+        parse(content, Format::Yaml).unwrap()
+    }
+
+    fn codes(diagnostics: &Diagnostics) -> Vec<&'static str> {
+        diagnostics.iter().map(|d| d.code.as_str()).collect()
+    }
+
+    /// Options with every security rule turned on.
+    fn strict() -> ParseOptions {
+        ParseOptions {
+            security: SecurityPolicy::strict(),
+            ..ParseOptions::default()
+        }
+    }
+
+    mod input_tests {
+        use super::*;
+
+        #[test]
+        fn test_reports_only_the_size_of_the_text() {
+            let content =
+                "---\nimage: ../a.png\n---\nx\0y\n[up](../index.md)\n";
+            let mut diagnostics = Diagnostics::new();
+            diagnostics.check_input(content, 16);
+
+            assert_eq!(codes(&diagnostics), ["content-too-large"]);
+
+            let mut diagnostics = Diagnostics::new();
+            diagnostics.check_input(content, 1024);
+            assert!(diagnostics.is_empty());
+        }
+    }
+
+    mod security_tests {
+        use super::*;
+
+        #[test]
+        fn test_reports_every_violation_with_key_and_position() {
+            let content = "---\nimage: ../a.png\nicon: ..\\b.png\na: &x 1\nb: *x\n---\n[up](../index.md)\n";
+            let (_, diagnostics) = check(content, &strict());
+
+            assert_eq!(
+                codes(&diagnostics),
+                [
+                    "path-traversal",
+                    "path-traversal",
+                    "yaml-anchor",
+                    "yaml-anchor",
+                ]
+            );
+            let paths: Vec<_> = diagnostics
+                .iter()
+                .map(|d| d.path.as_deref().unwrap())
+                .collect();
+            assert_eq!(paths, ["image", "icon", "a", "b"]);
+            let positions: Vec<_> = diagnostics
+                .iter()
+                .map(|d| {
+                    let context = d.context.as_ref().unwrap();
+                    (context.line.unwrap(), context.column.unwrap())
+                })
+                .collect();
+            assert_eq!(positions, [(2, 8), (3, 7), (4, 4), (5, 4)]);
+        }
+
+        #[test]
+        fn test_default_policy_reports_nothing() {
+            let (_, diagnostics) = check(
+                "---\nimage: ../a.png\n---\n```sh\ncd ../src\n```\n",
+                &ParseOptions::default(),
+            );
+            assert!(diagnostics.is_empty());
+        }
+
+        #[test]
+        fn test_check_security_on_raw_front_matter() {
+            let raw = "title = \"Post\"\nlayout = \"../../x\"\n";
+            let frontmatter = parse(raw, Format::Toml).unwrap();
+            let mut diagnostics = Diagnostics::new();
+            diagnostics.check_security(
+                raw,
+                Format::Toml,
+                &frontmatter,
+                &SecurityPolicy::strict(),
+            );
+
+            let diagnostic = &diagnostics.as_slice()[0];
+            assert_eq!(diagnostic.code, Code::PathTraversal);
+            assert_eq!(diagnostic.path.as_deref(), Some("layout"));
+            assert_eq!(
+                diagnostic.context.as_ref().and_then(|c| c.line),
+                Some(2)
+            );
+        }
+
+        #[test]
+        fn test_policy_errors_keep_their_rule_and_key() {
+            let diagnostic =
+                Diagnostic::from_error(&Error::PolicyViolation {
+                    key: "tags[1]".to_string(),
+                    rule: PolicyRule::MaxStringLength,
+                    message: "too long".to_string(),
+                });
+
+            assert_eq!(diagnostic.code, Code::MaxStringLength);
+            assert_eq!(diagnostic.category, Category::Validation);
+            assert_eq!(diagnostic.path.as_deref(), Some("tags[1]"));
+        }
+    }
+
+    mod structure_tests {
+        use super::*;
+
+        #[test]
+        fn test_reports_each_value_that_is_too_deep() {
+            let fm = yaml("a: [[1], 2]\nb: { c: { d: 1 } }\ne: 1\n");
+            let mut diagnostics = Diagnostics::new();
+            diagnostics.check_structure(&fm, 2, 2);
+
+            assert_eq!(
+                codes(&diagnostics),
+                [
+                    "too-many-keys",
+                    "nesting-too-deep",
+                    "nesting-too-deep"
+                ]
+            );
+            let paths: Vec<_> = diagnostics
+                .iter()
+                .filter_map(|d| d.path.as_deref())
+                .collect();
+            assert_eq!(paths, ["a[0][0]", "b.c.d"]);
+        }
+
+        #[test]
+        fn test_reports_every_missing_field() {
+            let fm = yaml("title: A\n");
+            let mut diagnostics = Diagnostics::new();
+            diagnostics
+                .check_required(&fm, ["title", "date", "author"]);
+
+            assert_eq!(diagnostics.len(), 2);
+            assert_eq!(
+                diagnostics.as_slice()[1].message,
+                "Missing required field: author"
+            );
+        }
+    }
+
+    mod schema_tests {
+        use super::*;
+
+        #[test]
+        fn test_violations_are_errors_and_extra_fields_warnings() {
+            let schema = Schema::parse(
+                r#"{"fields": {
+                    "title": {"type": "string", "required": true},
+                    "author": {"type": "object", "fields": {
+                        "name": {"type": "string"}
+                    }},
+                    "draft": {"type": "boolean"}
+                }}"#,
+                Format::Json,
+            )
+            .unwrap();
+            let fm = yaml("author: { name: A, email: a@b.c }\ndraft: no\nslug: x\n");
+
+            let mut diagnostics = Diagnostics::new();
+            diagnostics.check_schema(&schema, &fm);
+
+            assert_eq!(
+                codes(&diagnostics),
+                [
+                    "schema-required",
+                    "schema-type",
+                    "undeclared-field",
+                    "undeclared-field",
+                ]
+            );
+            assert_eq!(diagnostics.count(Severity::Error), 2);
+            assert_eq!(diagnostics.count(Severity::Warning), 2);
+            assert_eq!(
+                diagnostics.as_slice()[2].path.as_deref(),
+                Some("author.email")
+            );
+        }
+    }
+
+    mod report_tests {
+        use super::*;
+
+        #[test]
+        fn test_json_has_every_field() {
+            let (_, diagnostics) =
+                check("---\nimage: ../a.png\n---\n", &strict());
+            let json = diagnostics.as_slice()[0].to_json("post.md");
+
+            assert_eq!(json["file"], "post.md");
+            assert_eq!(json["line"], 2);
+            assert_eq!(json["column"], 8);
+            assert_eq!(json["severity"], "error");
+            assert_eq!(json["code"], "path-traversal");
+            assert_eq!(json["category"], "validation");
+            assert_eq!(json["path"], "image");
+            assert!(json["suggestion"]
+                .as_str()
+                .unwrap()
+                .contains("../"));
+        }
+
+        #[test]
+        fn test_errors_keep_their_category() {
+            let diagnostic = Diagnostic::from_error(
+                &Error::ConversionError("bad".to_string()),
+            );
+            assert_eq!(diagnostic.code, Code::ConversionError);
+            assert_eq!(diagnostic.category, Category::Conversion);
+            assert_eq!(diagnostic.suggestion, None);
+
+            let diagnostic =
+                Diagnostic::from_error(&Error::ContentTooLarge {
+                    size: 10,
+                    max: 5,
+                });
+            assert_eq!(diagnostic.code, Code::ContentTooLarge);
+            assert_eq!(diagnostic.category, Category::Configuration);
+            assert!(diagnostic.suggestion.is_some());
+
+            for (err, code) in [
+                (Error::InvalidFormat, Code::UnknownFormat),
+                (Error::KeyNotFound("a".into()), Code::KeyNotFound),
+                (Error::PatchError("bad".into()), Code::PatchError),
+            ] {
+                let diagnostic = Diagnostic::from_error(&err);
+                assert_eq!(diagnostic.code, code);
+                assert_eq!(diagnostic.category, code.category());
+            }
+            assert_eq!(
+                Diagnostic::from_error(&Error::Other("bad".into()))
+                    .code,
+                Code::Other
+            );
+
+            let diagnostic =
+                Diagnostic::from_error(&Error::NestingTooDeep {
+                    depth: 3,
+                    max: 2,
+                });
+            assert_eq!(diagnostic.code, Code::NestingTooDeep);
+            assert_eq!(diagnostic.category, Category::Configuration);
+
+            let diagnostic = Diagnostic::from_error(
+                &Error::ExpansionLimitExceeded {
+                    limit: ExpansionLimit::Aliases,
+                    max: 100,
+                },
+            );
+            assert_eq!(diagnostic.code, Code::ExpansionLimit);
+            assert_eq!(diagnostic.category, Category::Configuration);
+        }
+
+        #[test]
+        fn test_sarif_log() {
+            let mut first = Diagnostics::new();
+            first.push(
+                Diagnostic::new(
+                    Severity::Warning,
+                    Code::UndeclaredField,
+                    "field is not declared in the schema",
+                )
+                .at_path("slug"),
+            );
+            let (_, second) =
+                check("---\na: 1\nimage: ../y\n---\n", &strict());
+
+            let log = to_sarif([
+                ("docs\\a.md", &first),
+                ("b.md", &second),
+                ("c.md", &first),
+            ]);
+            assert_eq!(log["version"], "2.1.0");
+
+            let run = &log["runs"][0];
+            let rules =
+                run["tool"]["driver"]["rules"].as_array().unwrap();
+            let ids: Vec<_> = rules.iter().map(|r| &r["id"]).collect();
+            assert_eq!(ids, ["undeclared-field", "path-traversal"]);
+
+            let results = run["results"].as_array().unwrap();
+            assert_eq!(results.len(), 3);
+            assert_eq!(results[0]["level"], "warning");
+            assert_eq!(
+                results[0]["locations"][0]["physicalLocation"]
+                    ["artifactLocation"]["uri"],
+                "docs/a.md"
+            );
+            assert_eq!(
+                results[0]["locations"][0]["logicalLocations"][0]
+                    ["fullyQualifiedName"],
+                "slug"
+            );
+            assert_eq!(results[1]["level"], "error");
+            assert_eq!(
+                results[1]["locations"][0]["physicalLocation"]
+                    ["region"],
+                json!({ "startLine": 3, "startColumn": 8 })
+            );
+        }
+    }
+
+    mod check_tests {
+        use super::*;
+
+        #[test]
+        fn test_check_parses_without_stopping() {
+            let content =
+                "---\nlink: ../up\nnested: [[[1]]]\n---\nBody\n";
+            let options = ParseOptions {
+                max_depth: std::num::NonZeroUsize::new(2).unwrap(),
+                ..strict()
+            };
+            let (frontmatter, diagnostics) = check(content, &options);
+
+            assert!(frontmatter.is_some());
+            assert_eq!(
+                codes(&diagnostics),
+                ["path-traversal", "nesting-too-deep"]
+            );
+            assert!(diagnostics.has_errors());
+        }
+
+        #[test]
+        fn test_check_reports_parse_errors_with_position() {
+            let (frontmatter, diagnostics) = check(
+                "---\ntitle: A\nbad: : x\n---\n",
+                &ParseOptions::default(),
+            );
+
+            assert!(frontmatter.is_none());
+            let diagnostic = &diagnostics.as_slice()[0];
+            assert_eq!(diagnostic.code, Code::ParseError);
+            assert!(diagnostic.to_string().starts_with(
+                "error[parse-error]: Failed to parse YAML:"
+            ));
+            assert!(diagnostic.to_string().ends_with("(at 3:6)"));
+        }
+
+        #[test]
+        fn test_check_with_delimiters() {
+            let delimiters = Delimiters::extended();
+            let content = ";;;\n{\"link\": \"../up\"}\n;;;\n../body\n";
+            let (frontmatter, diagnostics) =
+                check_with_delimiters(content, &strict(), &delimiters);
+
+            assert!(frontmatter.is_some());
+            assert_eq!(codes(&diagnostics), ["path-traversal"]);
+
+            let (frontmatter, diagnostics) =
+                check(content, &ParseOptions::default());
+            assert!(frontmatter.is_none());
+            assert_eq!(
+                codes(&diagnostics).last(),
+                Some(&"unknown-format")
+            );
+        }
+
+        #[test]
+        fn test_display_and_ordering() {
+            assert!(Severity::Info < Severity::Warning);
+            assert!(Severity::Warning < Severity::Error);
+
+            let diagnostic = Diagnostic::new(
+                Severity::Warning,
+                Code::Schema(Rule::Range),
+                "too big",
+            )
+            .at_path("weight");
+            assert_eq!(
+                diagnostic.to_string(),
+                "warning[schema-range]: weight: too big"
+            );
+            assert_eq!(
+                diagnostic.render("post.md"),
+                diagnostic.to_string()
+            );
+        }
+    }
+}
@@ -0,0 +1,776 @@
+// diff.rs
+
+//! # Diff Module
+//!
+//! This module compares two front matter values and reports what changed
+//! between them, for example between two revisions of a page. Changes are
+//! reported per path, such as `seo.og.image` or `tags[2]`, as additions,
+//! removals or changed values.
+//!
+//! A [`Diff`] renders as a readable list of changes, converts to an
+//! [RFC 6902](https://www.rfc-editor.org/rfc/rfc6902) JSON Patch, and
+//! [`Frontmatter::apply_patch`] applies such a patch back.
+//!
+//! ## Usage Example
+//!
+//! ```rust
+//! use frontmatter_gen::{diff, parser, Format};
+//!
+//! let old = parser::parse("title: Draft\ntags: [rust]\ndraft: true\n", Format::Yaml)?;
+//! let new = parser::parse("title: Final\ntags: [rust, web]\n", Format::Yaml)?;
+//!
+//! let changes = diff::diff(&old, &new);
+//! assert_eq!(
+//!     changes.to_string(),
+//!     "~ title: \"Draft\" -> \"Final\"\n+ tags[1]: \"web\"\n- draft: true\n"
+//! );
+//!
+//! let mut patched = old.clone();
+//! patched.apply_patch(&changes.to_json_patch())?;
+//! assert_eq!(patched, new);
+//! # Ok::<(), frontmatter_gen::Error>(())
+//! ```
+
+use std::fmt;
+
+use serde_json::{json, Value as JsonValue};
+
+use crate::{
+    error::Error,
+    parser::json_to_value,
+    path::{self, Segment},
+    types::{Frontmatter, Plain, Value},
+};
+
+/// A single difference between two front matter values.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Change {
+    /// A key or array item present only in the new value.
+    Added {
+        /// Where the value was added.
+        path: Vec<Segment>,
+        /// The added value.
+        value: Value,
+    },
+    /// A key or array item present only in the old value.
+    Removed {
+        /// Where the value was removed.
+        path: Vec<Segment>,
+        /// The removed value.
+        value: Value,
+    },
+    /// A value that differs between the old and the new front matter.
+    Changed {
+        /// Where the value changed.
+        path: Vec<Segment>,
+        /// The old value.
+        old: Value,
+        /// The new value.
+        new: Value,
+    },
+}
+
+impl Change {
+    /// Returns the path of the changed value.
+    #[must_use]
+    pub fn path(&self) -> &[Segment] {
+        match self {
+            Change::Added { path, .. }
+            | Change::Removed { path, .. }
+            | Change::Changed { path, .. } => path,
+        }
+    }
+
+    /// Returns the change as a JSON Patch operation.
+    #[must_use]
+    pub fn to_json_patch(&self) -> JsonValue {
+        let pointer = pointer(self.path());
+        match self {
+            Change::Added { value, .. } => {
+                json!({ "op": "add", "path": pointer, "value": to_json(value) })
+            }
+            Change::Removed { .. } => {
+                json!({ "op": "remove", "path": pointer })
+            }
+            Change::Changed { new, .. } => {
+                json!({ "op": "replace", "path": pointer, "value": to_json(new) })
+            }
+        }
+    }
+}
+
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let path = path::join(self.path());
+        match self {
+            Change::Added { value, .. } => {
+                write!(f, "+ {path}: {}", to_json(value))
+            }
+            Change::Removed { value, .. } => {
+                write!(f, "- {path}: {}", to_json(value))
+            }
+            Change::Changed { old, new, .. } => {
+                write!(
+                    f,
+                    "~ {path}: {} -> {}",
+                    to_json(old),
+                    to_json(new)
+                )
+            }
+        }
+    }
+}
+
+/// The differences between two front matter values.
+///
+/// Changes are ordered so that applying them one after another turns
+/// the old front matter into the new one: within an array, items are
+/// added in ascending and removed in descending index order.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Diff {
+    changes: Vec<Change>,
+}
+
+impl Diff {
+    /// Returns the individual changes.
+    #[must_use]
+    pub fn changes(&self) -> &[Change] {
+        &self.changes
+    }
+
+    /// Returns `true` if the two values are equal.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// Returns the number of changes.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.changes.len()
+    }
+
+    /// Returns the diff as an RFC 6902 JSON Patch document.
+    ///
+    /// Datetimes are written as RFC 3339 strings and tagged values as
+    /// single-key objects such as `{"!custom": "y"}`, as in JSON front
+    /// matter. Applying the patch to the old front matter reproduces
+    /// the new one only when neither holds a datetime or a tagged
+    /// value; those come back as strings and objects.
+    #[must_use]
+    pub fn to_json_patch(&self) -> JsonValue {
+        JsonValue::Array(
+            self.changes.iter().map(Change::to_json_patch).collect(),
+        )
+    }
+}
+
+impl fmt::Display for Diff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for change in &self.changes {
+            writeln!(f, "{change}")?;
+        }
+        Ok(())
+    }
+}
+
+impl IntoIterator for Diff {
+    type Item = Change;
+    type IntoIter = std::vec::IntoIter<Change>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.changes.into_iter()
+    }
+}
+
+/// Compares two front matter values.
+///
+/// Objects are compared key by key and arrays item by item, so a change
+/// deep inside a value is reported at its own path. A value whose type
+/// changed, or a tagged value, is reported as a whole.
+///
+/// # Examples
+///
+/// ```rust
+/// use frontmatter_gen::{diff::{diff, Change}, Frontmatter, Value};
+///
+/// let old = Frontmatter::new();
+/// let mut new = Frontmatter::new();
+/// new.insert("draft".to_string(), Value::Boolean(false));
+///
+/// let changes = diff(&old, &new);
+/// assert!(matches!(&changes.changes()[0], Change::Added { .. }));
+/// ```
+#[must_use]
+pub fn diff(old: &Frontmatter, new: &Frontmatter) -> Diff {
+    let mut changes = Vec::new();
+    diff_objects(&mut Vec::new(), old, new, &mut changes);
+    Diff { changes }
+}
+
+fn diff_objects(
+    path: &mut Vec<Segment>,
+    old: &Frontmatter,
+    new: &Frontmatter,
+    changes: &mut Vec<Change>,
+) {
+    for (key, old_value) in old.iter() {
+        path.push(Segment::Key(key.clone()));
+        match new.get(key) {
+            Some(new_value) => {
+                diff_values(path, old_value, new_value, changes);
+            }
+            None => changes.push(Change::Removed {
+                path: path.clone(),
+                value: old_value.clone(),
+            }),
+        }
+        let _ = path.pop();
+    }
+    for (key, new_value) in new.iter() {
+        if !old.contains_key(key) {
+            let mut path = path.clone();
+            path.push(Segment::Key(key.clone()));
+            changes.push(Change::Added {
+                path,
+                value: new_value.clone(),
+            });
+        }
+    }
+}
+
+fn diff_values(
+    path: &mut Vec<Segment>,
+    old: &Value,
+    new: &Value,
+    changes: &mut Vec<Change>,
+) {
+    match (old, new) {
+        (Value::Object(old), Value::Object(new)) => {
+            diff_objects(path, old, new, changes);
+        }
+        (Value::Array(old), Value::Array(new)) => {
+            for (index, (old, new)) in old.iter().zip(new).enumerate() {
+                path.push(Segment::Index(index));
+                diff_values(path, old, new, changes);
+                let _ = path.pop();
+            }
+            let item = |index| {
+                let mut path = path.clone();
+                path.push(Segment::Index(index));
+                path
+            };
+            for (index, value) in new.iter().enumerate().skip(old.len())
+            {
+                changes.push(Change::Added {
+                    path: item(index),
+                    value: value.clone(),
+                });
+            }
+            for (index, value) in
+                old.iter().enumerate().skip(new.len()).rev()
+            {
+                changes.push(Change::Removed {
+                    path: item(index),
+                    value: value.clone(),
+                });
+            }
+        }
+        (old, new) if old != new => changes.push(Change::Changed {
+            path: path.clone(),
+            old: old.clone(),
+            new: new.clone(),
+        }),
+        _ => {}
+    }
+}
+
+impl Frontmatter {
+    /// Applies an RFC 6902 JSON Patch.
+    ///
+    /// All six operations are supported: `add`, `remove`, `replace`,
+    /// `move`, `copy` and `test`. Values in the patch are read as JSON
+    /// front matter is, so datetimes arrive as strings. A `test`
+    /// compares numbers by value, so `1` matches `1.0`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::PatchError` if the patch is malformed, an
+    /// operation refers to a missing location, a `test` fails, or the
+    /// result would not be an object. The front matter is left unchanged
+    /// in that case.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use frontmatter_gen::{Frontmatter, Value};
+    /// use serde_json::json;
+    ///
+    /// let mut fm = Frontmatter::new();
+    /// fm.apply_patch(&json!([
+    ///     { "op": "add", "path": "/tags", "value": ["rust"] },
+    ///     { "op": "add", "path": "/tags/-", "value": "web" },
+    ///     { "op": "test", "path": "/tags/1", "value": "web" },
+    /// ]))?;
+    /// assert_eq!(fm.get_path("tags[1]"), Some(&Value::String("web".to_string())));
+    /// # Ok::<(), frontmatter_gen::Error>(())
+    /// ```
+    pub fn apply_patch(
+        &mut self,
+        patch: &JsonValue,
+    ) -> Result<(), Error> {
+        let operations = patch.as_array().ok_or_else(|| {
+            patch_error("a patch must be an array of operations")
+        })?;
+
+        let mut root = Value::Object(Box::new(self.clone()));
+        for (index, operation) in operations.iter().enumerate() {
+            apply_operation(&mut root, operation).map_err(
+                |e| match e {
+                    Error::PatchError(msg) => patch_error(&format!(
+                        "operation {index}: {msg}"
+                    )),
+                    other => other,
+                },
+            )?;
+        }
+
+        match root {
+            Value::Object(fm) => {
+                *self = *fm;
+                Ok(())
+            }
+            _ => Err(patch_error("the result is not an object")),
+        }
+    }
+}
+
+fn apply_operation(
+    root: &mut Value,
+    operation: &JsonValue,
+) -> Result<(), Error> {
+    let member = |name: &str| {
+        operation.get(name).ok_or_else(|| {
+            patch_error(&format!("missing `{name}` member"))
+        })
+    };
+    let pointer_at = |name: &str| {
+        member(name)?
+            .as_str()
+            .ok_or_else(|| {
+                patch_error(&format!("`{name}` must be a string"))
+            })
+            .and_then(parse_pointer)
+    };
+
+    let op = member("op")?
+        .as_str()
+        .ok_or_else(|| patch_error("`op` must be a string"))?;
+    let path = pointer_at("path")?;
+    match op {
+        "add" => add(root, &path, json_to_value(member("value")?)),
+        "remove" => remove(root, &path).map(drop),
+        "replace" => {
+            let target = resolve_mut(root, &path)?;
+            *target = json_to_value(member("value")?);
+            Ok(())
+        }
+        "move" => {
+            let from = pointer_at("from")?;
+            if path.len() > from.len() && path.starts_with(&from) {
+                return Err(patch_error(
+                    "cannot move a value into one of its children",
+                ));
+            }
+            let value = remove(root, &from)?;
+            add(root, &path, value)
+        }
+        "copy" => {
+            let value =
+                resolve_mut(root, &pointer_at("from")?)?.clone();
+            add(root, &path, value)
+        }
+        "test" => {
+            let actual = to_json(resolve_mut(root, &path)?);
+            if same_json(&actual, member("value")?) {
+                Ok(())
+            } else {
+                Err(patch_error(&format!(
+                    "test failed at `{}`",
+                    pointer_str(&path)
+                )))
+            }
+        }
+        _ => Err(patch_error(&format!("unknown operation `{op}`"))),
+    }
+}
+
+/// Returns the value a pointer refers to.
+fn resolve_mut<'a>(
+    root: &'a mut Value,
+    tokens: &[String],
+) -> Result<&'a mut Value, Error> {
+    let mut current = root;
+    for (depth, token) in tokens.iter().enumerate() {
+        let missing = || {
+            patch_error(&format!(
+                "no value at `{}`",
+                pointer_str(&tokens[..=depth])
+            ))
+        };
+        current = match current {
+            Value::Object(obj) => {
+                obj.get_mut(token).ok_or_else(missing)?
+            }
+            Value::Array(arr) => parse_index(token)
+                .and_then(|index| arr.get_mut(index))
+                .ok_or_else(missing)?,
+            _ => return Err(missing()),
+        };
+    }
+    Ok(current)
+}
+
+/// Adds a value, replacing an existing object member or inserting
+/// into an array.
+fn add(
+    root: &mut Value,
+    tokens: &[String],
+    value: Value,
+) -> Result<(), Error> {
+    let Some((last, parent)) = tokens.split_last() else {
+        *root = value;
+        return Ok(());
+    };
+    match resolve_mut(root, parent)? {
+        Value::Object(obj) => {
+            let _ = obj.insert(last.clone(), value);
+            Ok(())
+        }
+        Value::Array(arr) => {
+            let index = if last == "-" {
+                arr.len()
+            } else {
+                parse_index(last)
+                    .filter(|&index| index <= arr.len())
+                    .ok_or_else(|| {
+                        patch_error(&format!(
+                            "index out of range at `{}`",
+                            pointer_str(tokens)
+                        ))
+                    })?
+            };
+            arr.insert(index, value);
+            Ok(())
+        }
+        _ => Err(patch_error(&format!(
+            "cannot add to a scalar at `{}`",
+            pointer_str(parent)
+        ))),
+    }
+}
+
+/// Removes and returns a value.
+fn remove(root: &mut Value, tokens: &[String]) -> Result<Value, Error> {
+    let (last, parent) = tokens.split_last().ok_or_else(|| {
+        patch_error("cannot remove the whole document")
+    })?;
+    let removed = match resolve_mut(root, parent)? {
+        Value::Object(obj) => obj.remove(last),
+        Value::Array(arr) => parse_index(last)
+            .filter(|&index| index < arr.len())
+            .map(|index| arr.remove(index)),
+        _ => None,
+    };
+    removed.ok_or_else(|| {
+        patch_error(&format!("no value at `{}`", pointer_str(tokens)))
+    })
+}
+
+/// Formats a path as a JSON Pointer.
+fn pointer(path: &[Segment]) -> String {
+    let tokens: Vec<String> = path
+        .iter()
+        .map(|segment| match segment {
+            Segment::Key(key) => key.clone(),
+            Segment::Index(index) => index.to_string(),
+        })
+        .collect();
+    pointer_str(&tokens)
+}
+
+/// Formats reference tokens as a JSON Pointer.
+fn pointer_str(tokens: &[String]) -> String {
+    tokens
+        .iter()
+        .map(|token| {
+            format!("/{}", token.replace('~', "~0").replace('/', "~1"))
+        })
+        .collect()
+}
+
+/// Splits a JSON Pointer into its unescaped reference tokens.
+fn parse_pointer(pointer: &str) -> Result<Vec<String>, Error> {
+    if pointer.is_empty() {
+        return Ok(Vec::new());
+    }
+    let rest = pointer.strip_prefix('/').ok_or_else(|| {
+        patch_error(&format!("`{pointer}` is not a JSON Pointer"))
+    })?;
+    Ok(rest
+        .split('/')
+        .map(|token| token.replace("~1", "/").replace("~0", "~"))
+        .collect())
+}
+
+/// Parses an array index, which has no sign or leading zeros.
+fn parse_index(token: &str) -> Option<usize> {
+    if token.len() > 1 && token.starts_with('0') {
+        return None;
+    }
+    if !token.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    token.parse().ok()
+}
+
+/// Converts a value to JSON, writing datetimes as strings.
+fn to_json(value: &Value) -> JsonValue {
+    serde_json::to_value(Plain(value)).unwrap_or(JsonValue::Null)
+}
+
+/// Compares two JSON values, treating numbers as equal when their
+/// values are, as RFC 6902 requires of `test`.
+fn same_json(a: &JsonValue, b: &JsonValue) -> bool {
+    match (a, b) {
+        (JsonValue::Number(x), JsonValue::Number(y)) => {
+            if x.is_f64() || y.is_f64() {
+                x.as_f64() == y.as_f64()
+            } else {
+                x == y
+            }
+        }
+        (JsonValue::Array(x), JsonValue::Array(y)) => {
+            x.len() == y.len()
+                && x.iter().zip(y).all(|(x, y)| same_json(x, y))
+        }
+        (JsonValue::Object(x), JsonValue::Object(y)) => {
+            x.len() == y.len()
+                && x.iter().all(|(key, x)| {
+                    y.get(key).is_some_and(|y| same_json(x, y))
+                })
+        }
+        _ => a == b,
+    }
+}
+
+fn patch_error(msg: &str) -> Error {
+    Error::PatchError(msg.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parser::parse, types::Format};
+
+    fn yaml(content: &str) -> Frontmatter {
+        parse(content, Format::Yaml).unwrap()
+    }
+
+    mod diff_tests {
+        use super::*;
+
+        #[test]
+        fn test_no_changes() {
+            let fm = yaml("title: A\ntags: [a]\n");
+            let changes = diff(&fm, &fm.clone());
+            assert!(changes.is_empty());
+            assert_eq!(changes.to_string(), "");
+            assert_eq!(changes.to_json_patch(), json!([]));
+        }
+
+        #[test]
+        fn test_nested_changes_are_reported_at_their_path() {
+            let old = yaml(
+                "seo:\n  og:\n    image: a.png\n    title: T\nauthors:\n  - name: A\n  - name: B\n  - name: C\nweight: 1\n",
+            );
+            let new = yaml(
+                "seo:\n  og:\n    image: b.png\n    alt: Cover\nauthors:\n  - name: A\nweight: one\n",
+            );
+            let changes = diff(&old, &new);
+            assert_eq!(
+                changes.to_string(),
+                "~ seo.og.image: \"a.png\" -> \"b.png\"\n\
+                 - seo.og.title: \"T\"\n\
+                 + seo.og.alt: \"Cover\"\n\
+                 - authors[2]: {\"name\":\"C\"}\n\
+                 - authors[1]: {\"name\":\"B\"}\n\
+                 ~ weight: 1 -> \"one\"\n"
+            );
+            assert_eq!(
+                changes.changes()[0].path(),
+                path::parse("seo.og.image").unwrap()
+            );
+            assert_eq!(changes.len(), 6);
+        }
+
+        #[test]
+        fn test_json_patch_output() {
+            let old = yaml("a/b: 1\ntags: [x]\nold: true\n");
+            let new = yaml("a/b: 2\ntags: [x, y]\n\"~\": null\n");
+            assert_eq!(
+                diff(&old, &new).to_json_patch(),
+                json!([
+                    { "op": "replace", "path": "/a~1b", "value": 2 },
+                    { "op": "add", "path": "/tags/1", "value": "y" },
+                    { "op": "remove", "path": "/old" },
+                    { "op": "add", "path": "/~0", "value": null },
+                ])
+            );
+        }
+
+        #[test]
+        fn test_patch_round_trips() {
+            let old = yaml(
+                "title: A\nlist: [1, 2, 3, 4]\nnested: { a: [{ b: 1 }] }\n",
+            );
+            let new = yaml(
+                "title: B\nlist: [1, 5]\nnested: { a: [{ b: 2, c: 3 }, 4] }\nextra: x\n",
+            );
+            let mut patched = old.clone();
+            patched
+                .apply_patch(&diff(&old, &new).to_json_patch())
+                .unwrap();
+            assert_eq!(patched, new);
+        }
+
+        #[test]
+        fn test_patch_turns_tags_and_datetimes_into_json() {
+            let old = yaml(
+                "t: x
+d: x
+",
+            );
+            let new = yaml(
+                "t: !custom y
+d: 2024-01-01T00:00:00Z
+",
+            );
+            let mut patched = old.clone();
+            patched
+                .apply_patch(&diff(&old, &new).to_json_patch())
+                .unwrap();
+            assert_eq!(
+                patched.get("d"),
+                Some(&Value::String(
+                    "2024-01-01T00:00:00Z".to_string()
+                ))
+            );
+            assert_eq!(
+                patched.get_path("t.!custom"),
+                Some(&Value::String("y".to_string()))
+            );
+        }
+    }
+
+    mod patch_tests {
+        use super::*;
+
+        #[test]
+        fn test_move_copy_and_test() {
+            let mut fm = yaml("a: { b: 1 }\nlist: [x, y]\n");
+            fm.apply_patch(&json!([
+                { "op": "move", "from": "/a/b", "path": "/c" },
+                { "op": "copy", "from": "/list/1", "path": "/list/0" },
+                { "op": "test", "path": "/list", "value": ["y", "x", "y"] },
+                { "op": "replace", "path": "/a", "value": [] },
+            ]))
+            .unwrap();
+            assert_eq!(fm, yaml("a: []\nlist: [y, x, y]\nc: 1\n"));
+        }
+
+        #[test]
+        fn test_compares_numbers_by_value() {
+            let mut fm = yaml(
+                "a: 1
+b: [2.0, { c: 3 }]
+",
+            );
+            fm.apply_patch(&json!([
+                { "op": "test", "path": "/a", "value": 1.0 },
+                { "op": "test", "path": "/b", "value": [2, { "c": 3.0 }] },
+            ]))
+            .unwrap();
+            let err = fm.apply_patch(&json!([
+                { "op": "test", "path": "/a", "value": 1.5 },
+            ]));
+            assert!(matches!(err, Err(Error::PatchError(_))));
+        }
+
+        #[test]
+        fn test_failed_patch_leaves_front_matter_unchanged() {
+            let mut fm = yaml("title: A\n");
+            let original = fm.clone();
+
+            let cases = [
+                (json!({}), "a patch must be an array of operations"),
+                (
+                    json!([
+                        { "op": "remove", "path": "/title" },
+                        { "op": "test", "path": "/title", "value": "A" },
+                    ]),
+                    "operation 1: no value at `/title`",
+                ),
+                (
+                    json!([{ "op": "test", "path": "/title", "value": "B" }]),
+                    "operation 0: test failed at `/title`",
+                ),
+                (
+                    json!([{ "op": "add", "path": "/title/x", "value": 1 }]),
+                    "operation 0: cannot add to a scalar at `/title`",
+                ),
+                (
+                    json!([{ "op": "add", "path": "", "value": 1 }]),
+                    "the result is not an object",
+                ),
+                (
+                    json!([{ "op": "swap", "path": "/title" }]),
+                    "operation 0: unknown operation `swap`",
+                ),
+                (
+                    json!([{ "op": "add", "path": "title", "value": 1 }]),
+                    "operation 0: `title` is not a JSON Pointer",
+                ),
+            ];
+            for (patch, message) in cases {
+                let err = fm.apply_patch(&patch).unwrap_err();
+                assert_eq!(
+                    err.to_string(),
+                    format!("Failed to apply JSON Patch: {message}")
+                );
+                assert_eq!(fm, original);
+            }
+        }
+
+        #[test]
+        fn test_array_indices() {
+            let mut fm = yaml("list: [a]\n");
+            assert!(fm
+                .apply_patch(&json!([
+                    { "op": "add", "path": "/list/01", "value": "b" }
+                ]))
+                .is_err());
+            assert!(fm
+                .apply_patch(&json!([
+                    { "op": "add", "path": "/list/2", "value": "b" }
+                ]))
+                .is_err());
+            fm.apply_patch(&json!([
+                { "op": "add", "path": "/list/1", "value": "b" },
+                { "op": "add", "path": "/list/0", "value": "c" },
+            ]))
+            .unwrap();
+            assert_eq!(fm, yaml("list: [c, a, b]\n"));
+        }
+    }
+}
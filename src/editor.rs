@@ -0,0 +1,1519 @@
+//! # Front Matter Editor Module
+//!
+//! This module provides in-place editing of the front matter block of a
+//! document. Where `extract` followed by `to_format` re-serialises the
+//! whole block, an [`Editor`] rewrites only the bytes that belong to the
+//! key being changed: comments, quoting style, blank lines and the body of
+//! the document are left exactly as they were.
+//!
+//! Edits address top-level keys, or nested values through
+//! [`Editor::set_path`]. YAML edits go through `noyalib`'s concrete
+//! syntax tree, while TOML and JSON edits are spliced into the source
+//! using the byte spans of the existing entries. JSON5 and RON blocks,
+//! and Org and AsciiDoc headers, cannot be edited in place.
+//!
+//! The block is parsed under the [`ParseOptions`] the editor was opened
+//! with, by default those of [`ParseOptions::default`]. After every edit
+//! the block is parsed again and compared with its previous contents. An edit that would disturb any other key is
+//! rejected with `Error::EditError`, and the document is left untouched.
+//!
+//! ## Usage Example
+//!
+//! ```rust
+//! use frontmatter_gen::{editor::Editor, Value};
+//!
+//! let content = "---\n# Post metadata\ntitle: My Post # shown in the header\nupdated: 2025-01-01\n---\nBody";
+//!
+//! let mut editor = Editor::new(content)?;
+//! editor.set("updated", &Value::String("2025-09-09".to_string()))?;
+//!
+//! assert_eq!(
+//!     editor.as_str(),
+//!     "---\n# Post metadata\ntitle: My Post # shown in the header\nupdated: 2025-09-09\n---\nBody"
+//! );
+//! # Ok::<(), frontmatter_gen::Error>(())
+//! ```
+
+use std::{fmt, ops::Range, sync::Arc};
+
+use noyalib::cst::{parse_document, Document as YamlDocument};
+use toml::Value as TomlValue;
+use toml_edit::{Document as TomlDocument, Item, Key};
+
+use crate::{
+    error::Error,
+    extractor::{find_block, Block, Delimiters},
+    parser::{self, value_to_toml, value_to_yaml},
+    path::{self, Segment},
+    types::{Format, Frontmatter, Plain, Value},
+    validate_input, ParseOptions,
+};
+
+/// Characters that `noyalib` reads as path syntax rather than as part of
+/// a key, so YAML keys containing them cannot be addressed in place.
+const YAML_PATH_CHARS: [char; 4] = ['.', '[', ']', '*'];
+
+/// An editor that changes front matter keys in place.
+///
+/// The editor owns a copy of the whole document. Only the front matter
+/// block located by [`find_block`] is ever rewritten, and within it
+/// only the entry being edited.
+///
+/// # Examples
+///
+/// ```rust
+/// use frontmatter_gen::{editor::Editor, Value};
+///
+/// let content = "+++\ntitle = \"My Post\"\ndraft = true # publish soon\n+++\nBody";
+///
+/// let mut editor = Editor::new(content)?;
+/// editor.rename("title", "name")?;
+/// editor.remove("draft")?;
+/// editor.set("weight", &Value::Integer(10))?;
+///
+/// assert_eq!(editor.as_str(), "+++\nname = \"My Post\"\nweight = 10\n+++\nBody");
+/// # Ok::<(), frontmatter_gen::Error>(())
+/// ```
+#[derive(Debug, Clone)]
+pub struct Editor {
+    content: String,
+    block: Range<usize>,
+    format: Format,
+    options: ParseOptions,
+}
+
+/// A single edit applied to the front matter block.
+#[derive(Debug, Clone, Copy)]
+enum Edit<'a> {
+    Set(&'a str, &'a Value),
+    /// Sets the value at a path under a top-level key, whose parent
+    /// already exists.
+    SetIn(&'a str, &'a str, &'a Value),
+    Rename(&'a str, &'a str),
+    Remove(&'a str),
+}
+
+impl Editor {
+    /// Creates an editor over the front matter block of `content`.
+    ///
+    /// The block and its format are found by [`find_block`], with the
+    /// default delimiters.
+    ///
+    /// # Arguments
+    ///
+    /// * `content` - The full document, including its front matter.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no front matter block is found or if the
+    /// block does not parse in its format.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use frontmatter_gen::{editor::Editor, Format};
+    ///
+    /// let editor = Editor::new("{\"title\": \"My Post\"}\nBody")?;
+    /// assert_eq!(editor.format(), Format::Json);
+    /// # Ok::<(), frontmatter_gen::Error>(())
+    /// ```
+    pub fn new(content: &str) -> Result<Self, Error> {
+        Self::with_options(content, &ParseOptions::default())
+    }
+
+    /// Creates an editor over the front matter block of `content`,
+    /// parsed under `options`.
+    ///
+    /// The size limit of `options` applies to `content`, its other
+    /// limits to every parse of the block, and its security policy to
+    /// the block as opened and after each edit.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `content` is too large, no front matter
+    /// block is found, the block does not parse within the limits of
+    /// `options`, or it breaks the security policy.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use frontmatter_gen::{editor::Editor, Error, ParseOptions};
+    ///
+    /// let options = ParseOptions::builder().max_size(8).build()?;
+    /// let result = Editor::with_options("{\"title\": \"My Post\"}", &options);
+    /// assert!(matches!(result, Err(Error::ContentTooLarge { .. })));
+    /// # Ok::<(), frontmatter_gen::Error>(())
+    /// ```
+    pub fn with_options(
+        content: &str,
+        options: &ParseOptions,
+    ) -> Result<Self, Error> {
+        validate_input(content, options)?;
+        Self::with_block(content, find_block(content)?, options)
+    }
+
+    /// Creates an editor over the front matter block of `content`,
+    /// found with `delimiters` instead of the default ones.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no front matter block is found or if the
+    /// block does not parse in its format.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use frontmatter_gen::{editor::Editor, extractor::Delimiters, Format};
+    ///
+    /// let editor = Editor::with_delimiters(
+    ///     ";;;\n{\"title\": \"My Post\"}\n;;;\nBody",
+    ///     &Delimiters::extended(),
+    /// )?;
+    /// assert_eq!(editor.format(), Format::Json);
+    /// # Ok::<(), frontmatter_gen::Error>(())
+    /// ```
+    pub fn with_delimiters(
+        content: &str,
+        delimiters: &Delimiters,
+    ) -> Result<Self, Error> {
+        Self::with_delimiters_and_options(
+            content,
+            delimiters,
+            &ParseOptions::default(),
+        )
+    }
+
+    /// Creates an editor over the front matter block of `content`,
+    /// found with `delimiters` and parsed under `options`, as
+    /// [`Editor::with_delimiters`] and [`Editor::with_options`] do.
+    ///
+    /// # Errors
+    ///
+    /// Returns the errors of [`Editor::with_options`].
+    pub fn with_delimiters_and_options(
+        content: &str,
+        delimiters: &Delimiters,
+        options: &ParseOptions,
+    ) -> Result<Self, Error> {
+        validate_input(content, options)?;
+        Self::with_block(
+            content,
+            delimiters.find_block(content)?,
+            options,
+        )
+    }
+
+    /// Creates an editor over `block` of `content`.
+    fn with_block(
+        content: &str,
+        block: Block,
+        options: &ParseOptions,
+    ) -> Result<Self, Error> {
+        let Block {
+            frontmatter: block,
+            format,
+            ..
+        } = block;
+        let raw = &content[block.clone()];
+        let frontmatter =
+            parser::parse_with_options(raw, format, Some(*options))
+                .map_err(|e| e.relocate(raw, content))?;
+        options.security.check(raw, format, &frontmatter)?;
+
+        Ok(Self {
+            content: content.to_string(),
+            block,
+            format,
+            options: *options,
+        })
+    }
+
+    /// Returns the format of the front matter block.
+    #[must_use]
+    pub const fn format(&self) -> Format {
+        self.format
+    }
+
+    /// Returns the raw front matter block, without its delimiters.
+    #[must_use]
+    pub fn raw_frontmatter(&self) -> &str {
+        &self.content[self.block.clone()]
+    }
+
+    /// Parses the current front matter block.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the block cannot be parsed.
+    pub fn frontmatter(&self) -> Result<Frontmatter, Error> {
+        self.parse(self.raw_frontmatter())
+    }
+
+    /// Parses `raw` in the block's format under the editor's options.
+    fn parse(&self, raw: &str) -> Result<Frontmatter, Error> {
+        parser::parse_with_options(raw, self.format, Some(self.options))
+    }
+
+    /// Returns the whole document, including any edits made so far.
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        &self.content
+    }
+
+    /// Consumes the editor and returns the edited document.
+    #[must_use]
+    pub fn into_string(self) -> String {
+        self.content
+    }
+
+    /// Sets a top-level key, replacing its value if it exists and
+    /// appending a new entry after the last one if it does not.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The top-level key to set.
+    /// * `value` - The new value.
+    ///
+    /// # Errors
+    ///
+    /// - `Error::EditError`: If the value cannot be written in place.
+    /// - `Error::ConversionError`: If the format cannot represent the
+    ///   value, such as `null` in TOML.
+    pub fn set(
+        &mut self,
+        key: &str,
+        value: &Value,
+    ) -> Result<(), Error> {
+        self.apply(Edit::Set(key, value))
+    }
+
+    /// Sets the value at a path such as `seo.og.image`.
+    ///
+    /// Intermediate objects and arrays are created as
+    /// [`Frontmatter::set_path`] would. In YAML, an existing value is
+    /// rewritten in place and a new key is added to the deepest mapping
+    /// on the path that exists, keeping the entries around it; when
+    /// that is not possible, the top-level entry containing the path is
+    /// rewritten as with [`Editor::set`].
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path to set, in the syntax of [`crate::path`].
+    /// * `value` - The new value.
+    ///
+    /// # Errors
+    ///
+    /// - `Error::InvalidPath`: If the path is malformed or passes
+    ///   through a scalar.
+    /// - `Error::EditError`: If the value cannot be written in place.
+    /// - `Error::ConversionError`: If the format cannot represent the
+    ///   value.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use frontmatter_gen::{editor::Editor, Value};
+    ///
+    /// let content = "---\nseo:\n  og:\n    image: a.png # 1200x630\n---\nBody";
+    ///
+    /// let mut editor = Editor::new(content)?;
+    /// editor.set_path("seo.og.image", &Value::String("b.png".to_string()))?;
+    ///
+    /// assert_eq!(
+    ///     editor.as_str(),
+    ///     "---\nseo:\n  og:\n    image: b.png # 1200x630\n---\nBody"
+    /// );
+    /// # Ok::<(), frontmatter_gen::Error>(())
+    /// ```
+    pub fn set_path(
+        &mut self,
+        path: &str,
+        value: &Value,
+    ) -> Result<(), Error> {
+        let segments = path::parse(path)?;
+        let Some(Segment::Key(key)) = segments.first() else {
+            return Err(Error::InvalidPath(path.to_string()));
+        };
+
+        let before = self.frontmatter()?;
+        let mut expected = before.clone();
+        let _ = expected.set_path(path, value.clone())?;
+
+        if self.format == Format::Yaml
+            && segments.len() > 1
+            && !path.contains('\\')
+            && segments.iter().all(|segment| match segment {
+                Segment::Key(key) => !key.contains(YAML_PATH_CHARS),
+                Segment::Index(_) => true,
+            })
+        {
+            // Write the first part of the path that does not exist yet,
+            // or the value itself, inside the deepest part that does.
+            let depth = (1..segments.len())
+                .rev()
+                .find(|&n| {
+                    before
+                        .get_path(&path::join(&segments[..n]))
+                        .is_some()
+                })
+                .unwrap_or(0);
+            let target = path::join(&segments[..=depth]);
+            if let Some(target_value) = expected.get_path(&target) {
+                if depth > 0
+                    && self
+                        .apply(Edit::SetIn(&target, key, target_value))
+                        .is_ok()
+                {
+                    return Ok(());
+                }
+            }
+        }
+
+        match expected.get(key) {
+            Some(entry) => self.set(key, entry),
+            None => Ok(()),
+        }
+    }
+
+    /// Renames a top-level key, keeping its value and position.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The existing key.
+    /// * `new_key` - The name it should have.
+    ///
+    /// # Errors
+    ///
+    /// - `Error::KeyNotFound`: If `key` does not exist.
+    /// - `Error::EditError`: If `new_key` already exists or the key
+    ///   cannot be renamed in place.
+    pub fn rename(
+        &mut self,
+        key: &str,
+        new_key: &str,
+    ) -> Result<(), Error> {
+        self.apply(Edit::Rename(key, new_key))
+    }
+
+    /// Removes a top-level key together with its value.
+    ///
+    /// Comments on the lines around the entry are kept.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key to remove.
+    ///
+    /// # Errors
+    ///
+    /// - `Error::KeyNotFound`: If `key` does not exist.
+    /// - `Error::EditError`: If the entry cannot be removed in place.
+    pub fn remove(&mut self, key: &str) -> Result<(), Error> {
+        self.apply(Edit::Remove(key))
+    }
+
+    /// Applies an edit to the block and splices the result back into the
+    /// document once it has been verified.
+    fn apply(&mut self, edit: Edit<'_>) -> Result<(), Error> {
+        let raw = self.raw_frontmatter();
+        let before = self.parse(raw)?;
+        if !edit.check(&before)? {
+            return Ok(());
+        }
+
+        let edited = match self.format {
+            Format::Yaml => edit_yaml(raw, edit, &before)?,
+            Format::Toml => edit_toml(raw, edit)?,
+            Format::Json => edit_json(raw, edit)?,
+            Format::Json5
+            | Format::Ron
+            | Format::Org
+            | Format::AsciiDoc => {
+                return Err(Error::EditError(format!(
+                    "{} front matter cannot be edited in place",
+                    self.format
+                )))
+            }
+            Format::Unsupported => return Err(Error::InvalidFormat),
+        };
+
+        // The block never ends with a line break, but re-rendered
+        // blocks do; keep the closing fence where it was.
+        let edited = if raw.ends_with('\n') {
+            edited.as_str()
+        } else {
+            edited.trim_end_matches(['\r', '\n'])
+        };
+
+        let after = self.parse(edited).map_err(|e| {
+            Error::EditError(format!(
+                "the edited block no longer parses: {e}"
+            ))
+        })?;
+        edit.verify(&before, &after)?;
+        self.options.security.check(edited, self.format, &after)?;
+
+        self.content.replace_range(self.block.clone(), edited);
+        self.block.end = self.block.start + edited.len();
+        Ok(())
+    }
+}
+
+impl fmt::Display for Editor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.content)
+    }
+}
+
+impl Edit<'_> {
+    /// Returns the existing key the edit addresses.
+    const fn key(&self) -> &str {
+        match *self {
+            Self::Set(key, _)
+            | Self::SetIn(_, key, _)
+            | Self::Rename(key, _)
+            | Self::Remove(key) => key,
+        }
+    }
+
+    /// Checks the edit against the current front matter, returning
+    /// `false` when there is nothing to do.
+    fn check(&self, before: &Frontmatter) -> Result<bool, Error> {
+        match *self {
+            Self::Set(..) | Self::SetIn(..) => Ok(true),
+            Self::Rename(key, _) | Self::Remove(key)
+                if !before.contains_key(key) =>
+            {
+                Err(Error::KeyNotFound(key.to_string()))
+            }
+            Self::Rename(key, new_key) if key == new_key => Ok(false),
+            Self::Rename(_, new_key)
+                if before.contains_key(new_key) =>
+            {
+                Err(Error::EditError(format!(
+                    "key `{new_key}` already exists"
+                )))
+            }
+            Self::Rename(..) | Self::Remove(_) => Ok(true),
+        }
+    }
+
+    /// Verifies that the edit changed nothing but its own entry.
+    fn verify(
+        &self,
+        before: &Frontmatter,
+        after: &Frontmatter,
+    ) -> Result<(), Error> {
+        let mut expected = before.clone();
+        let mut actual = after.clone();
+        let applied = match *self {
+            Self::Set(key, value) => {
+                let _ = expected.remove(key);
+                actual
+                    .remove(key)
+                    .is_some_and(|v| same_value(&v, value))
+            }
+            Self::SetIn(path, _, value) => {
+                expected.set_path(path, value.clone()).is_ok()
+                    && actual
+                        .get_path(path)
+                        .is_some_and(|v| same_value(v, value))
+            }
+            Self::Rename(key, new_key) => {
+                let value = expected.remove(key);
+                value.is_some() && actual.remove(new_key) == value
+            }
+            Self::Remove(key) => {
+                let _ = expected.remove(key);
+                !actual.contains_key(key)
+            }
+        };
+
+        if !applied {
+            Err(Error::EditError(format!(
+                "editing `{}` in place would not give the requested value",
+                self.key()
+            )))
+        } else if same_entries(&expected, &actual) {
+            Ok(())
+        } else {
+            Err(Error::EditError(format!(
+                "editing `{}` in place would change other entries",
+                self.key()
+            )))
+        }
+    }
+}
+
+/// Returns `true` if two values are equal, counting a NaN as equal to
+/// another NaN so that NaN entries can be verified.
+fn same_value(a: &Value, b: &Value) -> bool {
+    match (a, b) {
+        (Value::Float(a), Value::Float(b)) => {
+            a == b || (a.is_nan() && b.is_nan())
+        }
+        (Value::Array(a), Value::Array(b)) => {
+            a.len() == b.len()
+                && a.iter().zip(b).all(|(a, b)| same_value(a, b))
+        }
+        (Value::Object(a), Value::Object(b)) => same_entries(a, b),
+        (Value::Tagged(a_tag, a), Value::Tagged(b_tag, b)) => {
+            a_tag == b_tag && same_value(a, b)
+        }
+        _ => a == b,
+    }
+}
+
+/// Returns `true` if two objects hold the same entries, as compared by
+/// [`same_value`].
+fn same_entries(a: &Frontmatter, b: &Frontmatter) -> bool {
+    a.len() == b.len()
+        && a.iter().all(|(key, value)| {
+            b.get(key).is_some_and(|other| same_value(value, other))
+        })
+}
+
+/// Returns the error for a nested edit in a format that only supports
+/// rewriting whole top-level entries.
+fn nested_edit_error(path: &str) -> Error {
+    Error::EditError(format!("`{path}` cannot be edited in place"))
+}
+
+/// Replaces `range` of `raw` with `replacement`.
+fn splice(raw: &str, range: Range<usize>, replacement: &str) -> String {
+    let mut edited =
+        String::with_capacity(raw.len() + replacement.len());
+    edited.push_str(&raw[..range.start]);
+    edited.push_str(replacement);
+    edited.push_str(&raw[range.end..]);
+    edited
+}
+
+/// Returns the byte offset of the start of the line containing `at`.
+fn line_start(raw: &str, at: usize) -> usize {
+    raw[..at].rfind('\n').map_or(0, |i| i + 1)
+}
+
+/// Returns the byte offset of the line break ending the line that
+/// contains `at`, or the end of `raw` on the last line.
+fn line_end(raw: &str, at: usize) -> usize {
+    raw[at..].find('\n').map_or(raw.len(), |i| at + i)
+}
+
+/// Returns the byte range covering whole lines from `start` to `end`,
+/// including one line break so no blank line is left behind.
+fn whole_lines(raw: &str, start: usize, end: usize) -> Range<usize> {
+    let mut start = line_start(raw, start);
+    let mut end = line_end(raw, end);
+    if end < raw.len() {
+        end += 1;
+    } else if start > 0 {
+        start -= 1;
+        if raw[..start].ends_with('\r') {
+            start -= 1;
+        }
+    }
+    start..end
+}
+
+/// Returns the line break used by `raw`.
+fn newline(raw: &str) -> &'static str {
+    if raw.contains("\r\n") {
+        "\r\n"
+    } else {
+        "\n"
+    }
+}
+
+// YAML Implementation
+// -------------------
+
+/// Applies an edit to a YAML block through `noyalib`'s concrete syntax
+/// tree.
+fn edit_yaml(
+    raw: &str,
+    edit: Edit<'_>,
+    before: &Frontmatter,
+) -> Result<String, Error> {
+    let key = edit.key();
+    if key.contains(YAML_PATH_CHARS) {
+        return Err(Error::EditError(format!(
+            "YAML key `{key}` cannot be addressed in place"
+        )));
+    }
+
+    let mut doc =
+        parse_document(raw).map_err(|e| Error::YamlParseError {
+            source: Arc::new(e),
+        })?;
+    let result = match edit {
+        Edit::Set(key, value) => {
+            let exists = before.contains_key(key);
+            // Strings keep the quote style around them. `noyalib`
+            // writes integral floats as integers and NaN as `NaN`, so
+            // other scalars are written as fragments.
+            if value.is_string() {
+                let yaml = value_to_yaml(value);
+                if exists {
+                    doc.set_value(key, &yaml)
+                } else {
+                    doc.insert_entry_value("", key, &yaml)
+                }
+            } else if is_plain_scalar(value) {
+                doc.insert_entry("", key, &yaml_fragment(value))
+            } else if exists {
+                doc.set(key, &replacement_fragment(&doc, key, value))
+            } else {
+                doc.insert_entry("", key, &yaml_fragment(value))
+            }
+        }
+        // The path syntax matches `noyalib`'s once keys containing
+        // path characters are ruled out.
+        Edit::SetIn(path, _, value)
+            if before.get_path(path).is_some() =>
+        {
+            if value.is_string() {
+                doc.set_value(path, &value_to_yaml(value))
+            } else if is_plain_scalar(value) {
+                doc.set(path, &yaml_fragment(value))
+            } else {
+                doc.set(path, &replacement_fragment(&doc, path, value))
+            }
+        }
+        // A new key is added to its parent mapping in the style of its
+        // siblings, or as a flow fragment if `noyalib` cannot format
+        // the value.
+        Edit::SetIn(path, _, value) => {
+            let segments = path::parse(path)?;
+            let Some((Segment::Key(name), parent)) =
+                segments.split_last()
+            else {
+                return Err(nested_edit_error(path));
+            };
+            let parent = path::join(parent);
+            if is_plain_scalar(value) && !value.is_string() {
+                doc.insert_entry(&parent, name, &yaml_fragment(value))
+            } else {
+                doc.insert_entry_value(
+                    &parent,
+                    name,
+                    &value_to_yaml(value),
+                )
+                .or_else(|_| {
+                    doc.insert_entry(
+                        &parent,
+                        name,
+                        &yaml_fragment(value),
+                    )
+                })
+            }
+        }
+        Edit::Rename(key, new_key) => doc.rename_key(key, new_key),
+        Edit::Remove(key) => doc.remove(key),
+    };
+    result.map_err(|e| Error::EditError(e.to_string()))?;
+
+    Ok(doc.to_string())
+}
+
+/// Renders a value to replace the existing value of `key`.
+///
+/// `noyalib` splices the fragment over the span of the old value, which
+/// for a block collection starts at the beginning of the following line,
+/// so the fragment is indented to keep it inside the entry.
+fn replacement_fragment(
+    doc: &YamlDocument,
+    key: &str,
+    value: &Value,
+) -> String {
+    let fragment = yaml_fragment(value);
+    let on_next_line = doc
+        .key_span(key)
+        .zip(doc.span_at(key))
+        .and_then(|((_, key_end), (value_start, _))| {
+            doc.source().get(key_end..value_start)
+        })
+        .is_some_and(|between| between.contains('\n'));
+    if on_next_line {
+        format!("{}{fragment}", " ".repeat(doc.indent_unit().max(1)))
+    } else {
+        fragment
+    }
+}
+
+/// Returns `true` for values `noyalib` can write in the existing style.
+const fn is_plain_scalar(value: &Value) -> bool {
+    matches!(
+        value,
+        Value::Null
+            | Value::String(_)
+            | Value::Integer(_)
+            | Value::Float(_)
+            | Value::Boolean(_)
+    )
+}
+
+/// Renders a value as a single-line YAML flow fragment.
+///
+/// Strings are always double-quoted so that flow indicators inside them
+/// cannot change the structure of a collection.
+fn yaml_fragment(value: &Value) -> String {
+    match value {
+        Value::Null => "null".to_string(),
+        Value::String(s) => quote(s),
+        Value::Integer(i) => i.to_string(),
+        Value::Float(f) if f.is_nan() => ".nan".to_string(),
+        Value::Float(f) if f.is_infinite() => if f.is_sign_positive() {
+            ".inf"
+        } else {
+            "-.inf"
+        }
+        .to_string(),
+        Value::Float(f) => format!("{f:?}"),
+        Value::DateTime(dt) => format!("!!timestamp {dt}"),
+        Value::Boolean(b) => b.to_string(),
+        Value::Array(arr) => format!(
+            "[{}]",
+            arr.iter()
+                .map(yaml_fragment)
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        Value::Object(obj) => format!(
+            "{{{}}}",
+            obj.iter()
+                .map(|(k, v)| format!(
+                    "{}: {}",
+                    quote(k),
+                    yaml_fragment(v)
+                ))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        Value::Tagged(tag, val) => {
+            format!("{tag} {}", yaml_fragment(val))
+        }
+    }
+}
+
+/// Quotes a string using JSON escapes, which YAML double-quoted scalars
+/// share.
+fn quote(s: &str) -> String {
+    serde_json::Value::from(s).to_string()
+}
+
+// TOML Implementation
+// -------------------
+
+/// Applies an edit to a TOML block by splicing at the spans `toml_edit`
+/// reports for the existing entries.
+fn edit_toml(raw: &str, edit: Edit<'_>) -> Result<String, Error> {
+    let doc = TomlDocument::parse(raw)
+        .map_err(|e| Error::ParseError(e.to_string()))?;
+    let entry = doc.as_table().get_key_value(edit.key());
+
+    match (edit, entry) {
+        (Edit::SetIn(path, ..), _) => Err(nested_edit_error(path)),
+        (Edit::Set(_, value), Some((_, Item::Value(old)))) => {
+            let span = span_of(old.span(), edit.key())?;
+            Ok(splice(raw, span, &toml_repr(value)?.to_string()))
+        }
+        // Tables and dotted keys have no single span to replace.
+        (Edit::Set(key, value), Some(_)) => {
+            let mut doc = doc.into_mut();
+            doc[key] = Item::Value(toml_repr(value)?);
+            Ok(doc.to_string())
+        }
+        (Edit::Set(key, value), None) => {
+            let entry = format!(
+                "{} = {}",
+                Key::new(key).display_repr(),
+                toml_repr(value)?
+            );
+            let last_value = doc
+                .as_table()
+                .iter()
+                .filter_map(|(_, item)| item.as_value()?.span())
+                .map(|span| span.end)
+                .max();
+            let newline = newline(raw);
+            Ok(match last_value {
+                Some(end) => {
+                    let at = line_end(raw, end);
+                    let at = if raw[..at].ends_with('\r') {
+                        at - 1
+                    } else {
+                        at
+                    };
+                    splice(raw, at..at, &format!("{newline}{entry}"))
+                }
+                None => splice(raw, 0..0, &format!("{entry}{newline}")),
+            })
+        }
+        (Edit::Rename(_, new_key), Some((key, _))) => {
+            let span = span_of(key.span(), key.get())?;
+            Ok(splice(raw, span, &Key::new(new_key).display_repr()))
+        }
+        (Edit::Remove(_), Some((key, Item::Value(old)))) => {
+            let start = span_of(key.span(), key.get())?.start;
+            let end = span_of(old.span(), key.get())?.end;
+            Ok(splice(raw, whole_lines(raw, start, end), ""))
+        }
+        (Edit::Remove(key), Some(_)) => {
+            let mut doc = doc.into_mut();
+            let _ = doc.remove(key);
+            Ok(doc.to_string())
+        }
+        (_, None) => Err(Error::KeyNotFound(edit.key().to_string())),
+    }
+}
+
+/// Converts a `Value` into a `toml_edit::Value` with default formatting.
+fn toml_repr(value: &Value) -> Result<toml_edit::Value, Error> {
+    fn convert(value: &TomlValue) -> toml_edit::Value {
+        match value {
+            TomlValue::String(s) => s.into(),
+            TomlValue::Integer(i) => (*i).into(),
+            TomlValue::Float(f) => (*f).into(),
+            TomlValue::Boolean(b) => (*b).into(),
+            TomlValue::Datetime(dt) => (*dt).into(),
+            TomlValue::Array(arr) => toml_edit::Value::Array(
+                arr.iter().map(convert).collect(),
+            ),
+            TomlValue::Table(table) => toml_edit::Value::InlineTable(
+                table
+                    .iter()
+                    .map(|(k, v)| (k.as_str(), convert(v)))
+                    .collect(),
+            ),
+        }
+    }
+    Ok(convert(&value_to_toml(value)?))
+}
+
+/// Unwraps a span reported by `toml_edit`.
+fn span_of(
+    span: Option<Range<usize>>,
+    key: &str,
+) -> Result<Range<usize>, Error> {
+    span.ok_or_else(|| {
+        Error::EditError(format!("no source position for key `{key}`"))
+    })
+}
+
+// JSON Implementation
+// -------------------
+
+/// A top-level member of a JSON object, located by byte spans.
+#[derive(Debug)]
+struct JsonMember {
+    key: String,
+    key_span: Range<usize>,
+    value_span: Range<usize>,
+}
+
+/// The top-level object of a JSON block.
+#[derive(Debug)]
+struct JsonObject {
+    /// Offset just past the opening brace.
+    open: usize,
+    members: Vec<JsonMember>,
+}
+
+/// Applies an edit to a JSON block by splicing at the spans of its
+/// top-level members.
+fn edit_json(raw: &str, edit: Edit<'_>) -> Result<String, Error> {
+    let object = JsonScanner::new(raw).object()?;
+    let members = &object.members;
+    // Later duplicates win when parsing, so edit the last occurrence.
+    let index = members.iter().rposition(|m| m.key == edit.key());
+
+    match (edit, index) {
+        (Edit::SetIn(path, ..), _) => Err(nested_edit_error(path)),
+        (Edit::Set(_, value), Some(i)) => Ok(splice(
+            raw,
+            members[i].value_span.clone(),
+            &json_repr(&Plain(value))?,
+        )),
+        (Edit::Set(key, value), None) => match members.last() {
+            // Reuse the indentation and separator of the last member.
+            Some(last) => {
+                let start = last.key_span.start;
+                let indent = &raw[raw[..start].trim_end().len()..start];
+                let separator =
+                    &raw[last.key_span.end..last.value_span.start];
+                let end = last.value_span.end;
+                Ok(splice(
+                    raw,
+                    end..end,
+                    &format!(
+                        ",{indent}{}{separator}{}",
+                        json_repr(key)?,
+                        json_repr(&Plain(value))?
+                    ),
+                ))
+            }
+            None => Ok(splice(
+                raw,
+                object.open..object.open,
+                &format!(
+                    "{}: {}",
+                    json_repr(key)?,
+                    json_repr(&Plain(value))?
+                ),
+            )),
+        },
+        (Edit::Rename(_, new_key), Some(i)) => Ok(splice(
+            raw,
+            members[i].key_span.clone(),
+            &json_repr(new_key)?,
+        )),
+        (Edit::Remove(_), Some(i)) => {
+            let member = &members[i];
+            let range = match (i.checked_sub(1), members.get(i + 1)) {
+                (_, Some(next)) => {
+                    member.key_span.start..next.key_span.start
+                }
+                (Some(prev), None) => {
+                    members[prev].value_span.end..member.value_span.end
+                }
+                (None, None) => object.open..member.value_span.end,
+            };
+            Ok(splice(raw, range, ""))
+        }
+        (_, None) => Err(Error::KeyNotFound(edit.key().to_string())),
+    }
+}
+
+/// Serialises a value as compact JSON.
+fn json_repr<T: serde::Serialize + ?Sized>(
+    value: &T,
+) -> Result<String, Error> {
+    serde_json::to_string(value).map_err(|e| Error::SerdeError {
+        source: Arc::new(e),
+    })
+}
+
+/// A minimal scanner that records the spans of top-level JSON members.
+///
+/// The block has already been parsed by `serde_json`, so the scanner
+/// only needs to find boundaries, not to validate.
+#[derive(Debug)]
+struct JsonScanner<'a> {
+    raw: &'a str,
+    pos: usize,
+}
+
+impl<'a> JsonScanner<'a> {
+    const fn new(raw: &'a str) -> Self {
+        Self { raw, pos: 0 }
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.raw.as_bytes().get(self.pos).copied()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while matches!(self.peek(), Some(b' ' | b'\t' | b'\n' | b'\r'))
+        {
+            self.pos += 1;
+        }
+    }
+
+    fn expect(&mut self, byte: u8) -> Result<(), Error> {
+        if self.peek() == Some(byte) {
+            self.pos += 1;
+            Ok(())
+        } else {
+            Err(Error::InvalidJson)
+        }
+    }
+
+    fn object(&mut self) -> Result<JsonObject, Error> {
+        self.skip_whitespace();
+        self.expect(b'{')?;
+        let mut object = JsonObject {
+            open: self.pos,
+            members: Vec::new(),
+        };
+
+        self.skip_whitespace();
+        if self.peek() == Some(b'}') {
+            return Ok(object);
+        }
+        loop {
+            self.skip_whitespace();
+            let key_span = self.string()?;
+            let key = serde_json::from_str(&self.raw[key_span.clone()])
+                .map_err(|e| Error::JsonParseError(Arc::new(e)))?;
+            self.skip_whitespace();
+            self.expect(b':')?;
+            self.skip_whitespace();
+            let value_span = self.value()?;
+            object.members.push(JsonMember {
+                key,
+                key_span,
+                value_span,
+            });
+
+            self.skip_whitespace();
+            match self.peek() {
+                Some(b',') => self.pos += 1,
+                Some(b'}') => return Ok(object),
+                _ => return Err(Error::InvalidJson),
+            }
+        }
+    }
+
+    fn string(&mut self) -> Result<Range<usize>, Error> {
+        let start = self.pos;
+        self.expect(b'"')?;
+        loop {
+            match self.peek() {
+                Some(b'\\') => self.pos += 2,
+                Some(b'"') => {
+                    self.pos += 1;
+                    return Ok(start..self.pos);
+                }
+                Some(_) => self.pos += 1,
+                None => return Err(Error::InvalidJson),
+            }
+        }
+    }
+
+    fn value(&mut self) -> Result<Range<usize>, Error> {
+        let start = self.pos;
+        match self.peek() {
+            Some(b'"') => {
+                let _ = self.string()?;
+            }
+            Some(b'{' | b'[') => {
+                let mut depth = 0_usize;
+                loop {
+                    match self.peek() {
+                        Some(b'"') => {
+                            let _ = self.string()?;
+                        }
+                        Some(b'{' | b'[') => {
+                            depth += 1;
+                            self.pos += 1;
+                        }
+                        Some(b'}' | b']') => {
+                            depth -= 1;
+                            self.pos += 1;
+                            if depth == 0 {
+                                break;
+                            }
+                        }
+                        Some(_) => self.pos += 1,
+                        None => return Err(Error::InvalidJson),
+                    }
+                }
+            }
+            _ => {
+                while !matches!(
+                    self.peek(),
+                    None | Some(
+                        b',' | b'}'
+                            | b']'
+                            | b' '
+                            | b'\t'
+                            | b'\n'
+                            | b'\r'
+                    )
+                ) {
+                    self.pos += 1;
+                }
+            }
+        }
+
+        if self.pos == start {
+            Err(Error::InvalidJson)
+        } else {
+            Ok(start..self.pos)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn string(s: &str) -> Value {
+        Value::String(s.to_string())
+    }
+
+    mod yaml_tests {
+        use super::*;
+
+        const DOC: &str = "---\n# Front Matter (YAML)\n\ntitle: \"My Post\" ## The title.\nupdated: 2024-01-01\ntags:\n  - rust\n---\n\n# Body\n";
+
+        #[test]
+        fn test_set_existing_key_keeps_comments_and_quotes() {
+            let mut editor = Editor::new(DOC).unwrap();
+            editor.set("title", &string("New Title")).unwrap();
+            editor.set("updated", &string("2025-09-09")).unwrap();
+            assert_eq!(
+                editor.as_str(),
+                DOC.replace("\"My Post\"", "\"New Title\"")
+                    .replace("2024-01-01", "2025-09-09")
+            );
+        }
+
+        #[test]
+        fn test_set_new_key_appends_entry() {
+            let mut editor = Editor::new(DOC).unwrap();
+            editor.set("draft", &Value::Boolean(false)).unwrap();
+            assert_eq!(
+                editor.as_str(),
+                DOC.replace("  - rust\n", "  - rust\ndraft: false\n")
+            );
+        }
+
+        #[test]
+        fn test_set_keeps_float_values() {
+            let mut editor = Editor::new(
+                "---\ntitle: A # kept\nseo:\n  w: 2\n---\n",
+            )
+            .unwrap();
+            editor.set("title", &Value::Float(1.0)).unwrap();
+            editor.set("ratio", &Value::Float(f64::NAN)).unwrap();
+            editor.set_path("seo.w", &Value::Float(3.0)).unwrap();
+            assert_eq!(
+                editor.as_str(),
+                "---\ntitle: 1.0 # kept\nseo:\n  w: 3.0\nratio: .nan\n---\n"
+            );
+
+            let fm = editor.frontmatter().unwrap();
+            assert_eq!(fm.get("title"), Some(&Value::Float(1.0)));
+            assert!(fm
+                .get("ratio")
+                .and_then(Value::as_f64)
+                .unwrap()
+                .is_nan());
+            assert_eq!(fm.get_path("seo.w"), Some(&Value::Float(3.0)));
+
+            editor.set("ratio", &Value::Float(f64::NAN)).unwrap();
+            editor.set("title", &string("B")).unwrap();
+        }
+
+        #[test]
+        fn test_set_collection_value() {
+            let mut editor = Editor::new(DOC).unwrap();
+            let tags = Value::Array(vec![string("a, b"), string("c")]);
+            editor.set("keywords", &tags).unwrap();
+            let fm = editor.frontmatter().unwrap();
+            assert_eq!(fm.get("keywords"), Some(&tags));
+            assert!(editor.as_str().contains("## The title."));
+        }
+
+        #[test]
+        fn test_replace_block_collection() {
+            let content = "---\nseo: # social\n  og:\n    image: a.png\ntags:\n  - rust\nweight: 1\n---\n";
+            let mut editor = Editor::new(content).unwrap();
+            let og = Value::Object(Box::new(
+                [("image".to_string(), string("b.png"))]
+                    .into_iter()
+                    .collect(),
+            ));
+            let tags = Value::Array(vec![string("go")]);
+            editor.set("seo", &og).unwrap();
+            editor.set("tags", &tags).unwrap();
+            let fm = editor.frontmatter().unwrap();
+            assert_eq!(fm.get("seo"), Some(&og));
+            assert_eq!(fm.get("tags"), Some(&tags));
+            assert!(editor.as_str().contains("seo: # social\n"));
+            assert!(editor.as_str().ends_with("weight: 1\n---\n"));
+        }
+
+        #[test]
+        fn test_set_path() {
+            let content = "---\nseo:\n  og:\n    image: 'a.png' # 1200x630\nauthors:\n  - name: Jane\n---\n";
+            let mut editor = Editor::new(content).unwrap();
+            editor.set_path("seo.og.image", &string("b.png")).unwrap();
+            assert_eq!(
+                editor.as_str(),
+                content.replace("'a.png'", "'b.png'")
+            );
+
+            editor
+                .set_path("authors[0].email", &string("j@example.com"))
+                .unwrap();
+            let fm = editor.frontmatter().unwrap();
+            assert_eq!(
+                fm.get_path("authors[0].email"),
+                Some(&string("j@example.com"))
+            );
+            assert_eq!(
+                fm.get_path("seo.og.image"),
+                Some(&string("b.png"))
+            );
+
+            let err = editor.set_path("seo.og.image.alt", &Value::Null);
+            assert!(matches!(err, Err(Error::InvalidPath(_))));
+        }
+
+        #[test]
+        fn test_set_path_inserts_nested_keys_in_place() {
+            let content =
+                "---\nseo:\n  w: 2 # keep\n  y: a\n---\nBody\n";
+            let mut editor = Editor::new(content).unwrap();
+            editor.set_path("seo.z", &Value::Integer(1)).unwrap();
+            assert_eq!(
+                editor.as_str(),
+                "---\nseo:\n  w: 2 # keep\n  y: a\n  z: 1\n---\nBody\n"
+            );
+
+            editor.set_path("seo.og.image", &string("b.png")).unwrap();
+            let fm = editor.frontmatter().unwrap();
+            assert_eq!(
+                fm.get_path("seo.og.image"),
+                Some(&string("b.png"))
+            );
+            assert!(editor.as_str().ends_with(
+                "  z: 1\n  og:\n    image: b.png\n---\nBody\n"
+            ));
+        }
+
+        #[test]
+        fn test_rename_and_remove() {
+            let mut editor = Editor::new(DOC).unwrap();
+            editor.rename("title", "name").unwrap();
+            editor.remove("updated").unwrap();
+            assert_eq!(
+                editor.as_str(),
+                DOC.replace("title:", "name:")
+                    .replace("updated: 2024-01-01\n", "")
+            );
+        }
+
+        #[test]
+        fn test_float_and_datetime_round_trip() {
+            let mut editor = Editor::new(DOC).unwrap();
+            let date: crate::types::Datetime =
+                "2025-09-09T10:00:00Z".parse().unwrap();
+            editor.set("weight", &Value::Float(1.0)).unwrap();
+            editor.set("published", &Value::DateTime(date)).unwrap();
+            let fm = editor.frontmatter().unwrap();
+            assert_eq!(fm.get("weight"), Some(&Value::Float(1.0)));
+            assert_eq!(
+                fm.get("published"),
+                Some(&Value::DateTime(date))
+            );
+        }
+    }
+
+    mod toml_tests {
+        use super::*;
+
+        #[test]
+        fn test_set_path_rewrites_top_level_entry() {
+            let content = "+++\ntitle = \"Post\" # keep\nseo = { image = \"a.png\" }\n+++\n";
+            let mut editor = Editor::new(content).unwrap();
+            editor.set_path("seo.image", &string("b.png")).unwrap();
+            assert_eq!(
+                editor.as_str(),
+                content.replace("a.png", "b.png")
+            );
+        }
+
+        const DOC: &str = "+++\n# Site settings\ntitle = 'My Post' # single quotes\n\ndraft = true\n\n[extra]\nauthor = \"Jane\"\n+++\nBody\n";
+
+        #[test]
+        fn test_set_existing_key_keeps_comment() {
+            let mut editor = Editor::new(DOC).unwrap();
+            editor.set("title", &string("New")).unwrap();
+            assert_eq!(
+                editor.as_str(),
+                DOC.replace("'My Post'", "\"New\"")
+            );
+        }
+
+        #[test]
+        fn test_set_new_key_after_last_value() {
+            let mut editor = Editor::new(DOC).unwrap();
+            editor.set("weight", &Value::Integer(3)).unwrap();
+            assert_eq!(
+                editor.as_str(),
+                DOC.replace(
+                    "draft = true\n",
+                    "draft = true\nweight = 3\n"
+                )
+            );
+        }
+
+        #[test]
+        fn test_rename_and_remove() {
+            let mut editor = Editor::new(DOC).unwrap();
+            editor.rename("draft", "hidden").unwrap();
+            editor.remove("title").unwrap();
+            assert_eq!(
+                editor.as_str(),
+                DOC.replace("draft", "hidden")
+                    .replace("title = 'My Post' # single quotes\n", "")
+            );
+        }
+
+        #[test]
+        fn test_null_is_rejected() {
+            let mut editor = Editor::new(DOC).unwrap();
+            assert!(matches!(
+                editor.set("title", &Value::Null),
+                Err(Error::ConversionError(_))
+            ));
+            assert_eq!(editor.as_str(), DOC);
+        }
+    }
+
+    mod json_tests {
+        use super::*;
+
+        #[test]
+        fn test_set_rejects_values_json_cannot_hold() {
+            let mut editor =
+                Editor::new("{\"ratio\": 1.5}\nBody").unwrap();
+            editor.set("ratio", &Value::Float(1.0)).unwrap();
+            assert_eq!(editor.as_str(), "{\"ratio\": 1.0}\nBody");
+
+            // JSON has no NaN, so it would be written as `null`.
+            assert!(editor
+                .set("ratio", &Value::Float(f64::NAN))
+                .is_err());
+            assert_eq!(editor.as_str(), "{\"ratio\": 1.0}\nBody");
+        }
+
+        const DOC: &str = "{\n    \"title\": \"My Post\",\n    \"draft\": true,\n    \"tags\": [\"a\", \"b\"]\n}\nBody\n";
+
+        #[test]
+        fn test_set_existing_and_new_keys() {
+            let mut editor = Editor::new(DOC).unwrap();
+            editor.set("draft", &Value::Boolean(false)).unwrap();
+            editor.set("weight", &Value::Integer(3)).unwrap();
+            assert_eq!(
+                editor.as_str(),
+                "{\n    \"title\": \"My Post\",\n    \"draft\": false,\n    \"tags\": [\"a\", \"b\"],\n    \"weight\": 3\n}\nBody\n"
+            );
+        }
+
+        #[test]
+        fn test_rename_and_remove() {
+            let mut editor = Editor::new(DOC).unwrap();
+            editor.rename("title", "name").unwrap();
+            editor.remove("tags").unwrap();
+            editor.remove("name").unwrap();
+            assert_eq!(
+                editor.as_str(),
+                "{\n    \"draft\": true\n}\nBody\n"
+            );
+        }
+
+        #[test]
+        fn test_set_into_empty_object() {
+            let mut editor = Editor::new("{}\nBody").unwrap();
+            editor.set("title", &string("x")).unwrap();
+            assert_eq!(editor.as_str(), "{\"title\": \"x\"}\nBody");
+        }
+    }
+
+    mod error_tests {
+        use super::*;
+        use crate::SecurityPolicy;
+
+        #[test]
+        fn test_options_apply_on_open_and_edit() {
+            let content = "---\ntitle: x\nimage: a.png\n---\n";
+            let options =
+                ParseOptions::builder().max_depth(1).build().unwrap();
+            assert!(Editor::with_options(
+                "---\nseo: { og: { image: a } }\n---\n",
+                &options
+            )
+            .is_err());
+
+            let options =
+                ParseOptions::builder().max_size(8).build().unwrap();
+            assert!(matches!(
+                Editor::with_options(content, &options),
+                Err(Error::ContentTooLarge { .. })
+            ));
+
+            let options = ParseOptions::builder()
+                .security(SecurityPolicy {
+                    deny_path_traversal: true,
+                    ..SecurityPolicy::default()
+                })
+                .build()
+                .unwrap();
+            assert!(matches!(
+                Editor::with_options(
+                    "---\nimage: ../a.png\n---\n",
+                    &options
+                ),
+                Err(Error::PolicyViolation { .. })
+            ));
+
+            let mut editor =
+                Editor::with_options(content, &options).unwrap();
+            assert!(matches!(
+                editor.set("image", &string("../b.png")),
+                Err(Error::PolicyViolation { .. })
+            ));
+            assert_eq!(editor.as_str(), content);
+        }
+
+        #[test]
+        fn test_missing_key() {
+            let mut editor =
+                Editor::new("---\ntitle: x\n---\n").unwrap();
+            assert!(matches!(
+                editor.remove("missing"),
+                Err(Error::KeyNotFound(ref k)) if k == "missing"
+            ));
+            assert!(matches!(
+                editor.rename("missing", "other"),
+                Err(Error::KeyNotFound(_))
+            ));
+        }
+
+        #[test]
+        fn test_rename_onto_existing_key() {
+            let mut editor =
+                Editor::new("---\na: 1\nb: 2\n---\n").unwrap();
+            assert!(matches!(
+                editor.rename("a", "b"),
+                Err(Error::EditError(_))
+            ));
+        }
+
+        #[test]
+        fn test_no_frontmatter() {
+            assert!(Editor::new("Just a body").is_err());
+        }
+
+        #[test]
+        fn test_formats_without_in_place_edits() {
+            for (content, format) in [
+                ("---json5\n{title: 'x',}\n---\n", Format::Json5),
+                ("---json5\n{\"title\": \"x\"}\n---\n", Format::Json5),
+                ("---ron\n(title: \"x\")\n---\n", Format::Ron),
+                ("#+TITLE: x\n\nBody", Format::Org),
+                ("= x\n:draft: yes\n\nBody", Format::AsciiDoc),
+            ] {
+                let mut delimiters = Delimiters::default();
+                let mut editor = Editor::with_delimiters(
+                    content,
+                    delimiters.headers(true),
+                )
+                .unwrap();
+                assert_eq!(editor.format(), format);
+                let err = editor.set("title", &"y".into()).unwrap_err();
+                assert!(
+                    err.to_string().contains(&format!(
+                        "{format} front matter cannot be edited in place"
+                    )),
+                    "{err}"
+                );
+                assert_eq!(editor.as_str(), content);
+            }
+        }
+    }
+}
@@ -42,6 +42,8 @@
 use std::sync::Arc;
 use thiserror::Error;
 
+use crate::{parser::ExpansionLimit, security::PolicyRule};
+
 /// Provides additional context for front matter errors.
 #[derive(Debug, Clone)]
 pub struct Context {
@@ -53,6 +55,89 @@ pub struct Context {
     pub snippet: Option<String>,
 }
 
+impl Context {
+    /// Describes the position of a byte offset within `source`.
+    ///
+    /// Lines and columns are 1-based, and columns count characters. The
+    /// snippet is the whole line containing the offset, without its line
+    /// break. Offsets past the end point just after the last character.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use frontmatter_gen::error::Context;
+    ///
+    /// let context = Context::from_offset("title: A\ndate: x", 15);
+    /// assert_eq!(context.line, Some(2));
+    /// assert_eq!(context.column, Some(7));
+    /// assert_eq!(context.snippet.as_deref(), Some("date: x"));
+    /// ```
+    #[must_use]
+    pub fn from_offset(source: &str, offset: usize) -> Self {
+        let mut offset = offset.min(source.len());
+        while !source.is_char_boundary(offset) {
+            offset -= 1;
+        }
+        let line_start =
+            source[..offset].rfind('\n').map_or(0, |i| i + 1);
+        let line_end = source[offset..]
+            .find('\n')
+            .map_or(source.len(), |i| offset + i);
+
+        Self {
+            line: Some(source[..line_start].matches('\n').count() + 1),
+            column: Some(
+                source[line_start..offset].chars().count() + 1,
+            ),
+            snippet: Some(
+                source[line_start..line_end]
+                    .trim_end_matches('\r')
+                    .to_string(),
+            ),
+        }
+    }
+
+    /// Shows the position as a `--> name:line:column` header followed by
+    /// the line and a caret under the column, or `None` if the line or
+    /// column is unknown.
+    pub(crate) fn excerpt(&self, name: &str) -> Option<String> {
+        let (line, column) = (self.line?, self.column?);
+        let snippet = self.snippet.as_deref().unwrap_or_default();
+        let gutter = " ".repeat(line.to_string().len());
+        // Keep tabs so the caret lines up with the excerpt.
+        let indent: String = snippet
+            .chars()
+            .take(column.saturating_sub(1))
+            .map(|c| if c == '\t' { '\t' } else { ' ' })
+            .collect();
+
+        Some(format!(
+            "{gutter}--> {name}:{line}:{column}\n{gutter} |\n{line} | {snippet}\n{gutter} | {indent}^"
+        ))
+    }
+
+    /// Returns the byte offset of this position within `source`, the
+    /// inverse of [`Context::from_offset`].
+    fn offset_in(&self, source: &str) -> Option<usize> {
+        let line = self.line?.checked_sub(1)?;
+        let column = self.column?.saturating_sub(1);
+        let line_start = if line == 0 {
+            0
+        } else {
+            source.match_indices('\n').nth(line - 1)?.0 + 1
+        };
+        let text = &source[line_start..];
+        let text = &text[..text.find('\n').unwrap_or(text.len())];
+        Some(
+            line_start
+                + text
+                    .char_indices()
+                    .nth(column)
+                    .map_or(text.len(), |(i, _)| i),
+        )
+    }
+}
+
 impl std::fmt::Display for Context {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
@@ -110,6 +195,23 @@ pub enum Error {
         max: usize,
     },
 
+    /// YAML front matter expands past a limit.
+    ///
+    /// This error occurs when aliases that repeat an anchored value make
+    /// the YAML larger than a limit of
+    /// [`ParseOptions`](crate::ParseOptions) allows. It
+    /// is raised while the YAML is read, before the structure is
+    /// validated.
+    #[error(
+        "Your YAML front matter expands past the limit of {max} {limit}."
+    )]
+    ExpansionLimitExceeded {
+        /// The limit that was exceeded
+        limit: ExpansionLimit,
+        /// The value of the limit
+        max: usize,
+    },
+
     /// Error occurred whilst parsing YAML content.
     ///
     /// This error occurs when the YAML parser encounters invalid syntax or
@@ -134,6 +236,20 @@ pub enum Error {
     #[error("Failed to parse JSON: {0}")]
     JsonParseError(Arc<JsonError>),
 
+    /// Error occurred whilst parsing JSON5 content.
+    ///
+    /// This error occurs when the JSON5 parser encounters invalid syntax
+    /// or structure.
+    #[error("Failed to parse JSON5: {0}")]
+    Json5ParseError(Arc<json5::Error>),
+
+    /// Error occurred whilst parsing RON content.
+    ///
+    /// This error occurs when the RON parser encounters invalid syntax
+    /// or structure.
+    #[error("Failed to parse RON: {0}")]
+    RonParseError(Arc<ron::error::SpannedError>),
+
     /// The front matter format is invalid or unsupported.
     ///
     /// This error occurs when the front matter format cannot be determined or
@@ -240,11 +356,123 @@ pub enum Error {
     #[error("Input validation error: {0}")]
     ValidationError(String),
 
+    /// A key was not present in the front matter.
+    ///
+    /// This error occurs when an edit refers to a key that the front
+    /// matter block does not contain.
+    #[error("Key not found in front matter: {0}")]
+    KeyNotFound(String),
+
+    /// An in-place edit of the front matter block failed.
+    ///
+    /// This error occurs when an edit cannot be applied without changing
+    /// other parts of the block, or when the edited block no longer parses.
+    #[error("Failed to edit front matter: {0}")]
+    EditError(String),
+
+    /// A path into the front matter was malformed or could not be
+    /// followed.
+    ///
+    /// This error occurs when a dotted path such as `seo.og.image` does
+    /// not parse, or when setting it would pass through a value that is
+    /// not an object or an array.
+    #[error("Invalid front matter path: {0}")]
+    InvalidPath(String),
+
+    /// A deep merge found two values it was told not to reconcile.
+    ///
+    /// This error occurs when merging with `ConflictStrategy::Fail` and
+    /// both sides hold different values for the key at the given path.
+    #[error("Conflicting values for front matter key: {0}")]
+    MergeConflict(String),
+
+    /// A JSON Patch could not be applied.
+    ///
+    /// This error occurs when a patch is malformed, refers to a location
+    /// that does not exist, or contains a `test` operation that fails.
+    #[error("Failed to apply JSON Patch: {0}")]
+    PatchError(String),
+
+    /// Front matter could not be deserialised into a typed value.
+    ///
+    /// This error occurs when a value does not match the type it is
+    /// deserialised into. `path` names the offending key, such as
+    /// `author.name` or `tags[2]`, and is `.` for the front matter itself.
+    #[error(
+        "Failed to deserialise front matter at `{path}`: {message}"
+    )]
+    DeserializeError {
+        /// The path of the offending key
+        path: String,
+        /// A description of the mismatch
+        message: String,
+    },
+
+    /// A front matter value broke a rule of the security policy.
+    ///
+    /// This error occurs when a rule of
+    /// [`SecurityPolicy`](crate::security::SecurityPolicy) rejects a
+    /// value. `key` names the offending key, such as `image` or
+    /// `assets[0]`, and `rule` the rule it broke.
+    #[error(
+        "Security policy rule `{rule}` rejected `{key}`: {message}"
+    )]
+    PolicyViolation {
+        /// The path of the offending key
+        key: String,
+        /// The rule that was broken
+        rule: PolicyRule,
+        /// What was wrong with the value
+        message: String,
+    },
+
+    /// An option was given a value it cannot take.
+    ///
+    /// This error occurs when [`ParseOptions`](crate::ParseOptions) are
+    /// built with an out-of-range limit, or from an environment variable
+    /// that does not parse. `name` names the option or the variable.
+    #[error("Invalid value `{value}` for `{name}`: {reason}")]
+    InvalidOption {
+        /// The option or environment variable
+        name: String,
+        /// The value it was given
+        value: String,
+        /// Why the value was rejected
+        reason: String,
+    },
+
+    /// A file or directory could not be read or written.
+    ///
+    /// `context` says what was being done, such as which directory was
+    /// being read.
+    #[error("{context}: {source}")]
+    IoError {
+        /// What was being done
+        context: String,
+        /// The underlying I/O error
+        source: Arc<std::io::Error>,
+    },
+
     /// Generic error with a custom message.
     ///
     /// This error occurs when a generic error is encountered with a custom message.
     #[error("Generic error: {0}")]
     Other(String),
+
+    /// An error at a known position in the source.
+    ///
+    /// Parse errors are wrapped in this variant when the parser reports
+    /// where it failed. `parser::parse_with_options` reports positions
+    /// within the front matter block, while `extract` reports them
+    /// within the whole document. Use [`Error::inner`] to match on the
+    /// underlying error.
+    #[error("{} {context}", .source.summary())]
+    Located {
+        /// The underlying error
+        source: Box<Error>,
+        /// Where the error occurred
+        context: Context,
+    },
 }
 
 impl Clone for Error {
@@ -262,6 +490,12 @@ fn clone(&self) -> Self {
                     max: *max,
                 }
             }
+            Self::ExpansionLimitExceeded { limit, max } => {
+                Self::ExpansionLimitExceeded {
+                    limit: *limit,
+                    max: *max,
+                }
+            }
             Self::YamlParseError { source } => Self::YamlParseError {
                 source: Arc::clone(source),
             },
@@ -273,6 +507,12 @@ fn clone(&self) -> Self {
             Self::TomlParseError(err) => {
                 Self::TomlParseError(err.clone())
             }
+            Self::Json5ParseError(err) => {
+                Self::Json5ParseError(Arc::clone(err))
+            }
+            Self::RonParseError(err) => {
+                Self::RonParseError(Arc::clone(err))
+            }
             Self::SerdeError { source } => Self::SerdeError {
                 source: Arc::clone(source),
             },
@@ -296,11 +536,48 @@ fn clone(&self) -> Self {
             Self::ValidationError(msg) => {
                 Self::ValidationError(msg.clone())
             }
+            Self::KeyNotFound(key) => Self::KeyNotFound(key.clone()),
+            Self::EditError(msg) => Self::EditError(msg.clone()),
+            Self::InvalidPath(msg) => Self::InvalidPath(msg.clone()),
+            Self::MergeConflict(path) => {
+                Self::MergeConflict(path.clone())
+            }
+            Self::PatchError(msg) => Self::PatchError(msg.clone()),
+            Self::DeserializeError { path, message } => {
+                Self::DeserializeError {
+                    path: path.clone(),
+                    message: message.clone(),
+                }
+            }
+            Self::PolicyViolation { key, rule, message } => {
+                Self::PolicyViolation {
+                    key: key.clone(),
+                    rule: *rule,
+                    message: message.clone(),
+                }
+            }
+            Self::InvalidOption {
+                name,
+                value,
+                reason,
+            } => Self::InvalidOption {
+                name: name.clone(),
+                value: value.clone(),
+                reason: reason.clone(),
+            },
+            Self::IoError { context, source } => Self::IoError {
+                context: context.clone(),
+                source: Arc::clone(source),
+            },
             Self::InvalidUrl(msg) => Self::InvalidUrl(msg.clone()),
             Self::InvalidLanguage(msg) => {
                 Self::InvalidLanguage(msg.clone())
             }
             Self::Other(msg) => Self::Other(msg.clone()),
+            Self::Located { source, context } => Self::Located {
+                source: source.clone(),
+                context: context.clone(),
+            },
             Self::InvalidFormat => Self::InvalidFormat,
         }
     }
@@ -322,6 +599,17 @@ pub enum Category {
     Configuration,
 }
 
+impl std::fmt::Display for Category {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Parsing => "parsing",
+            Self::Validation => "validation",
+            Self::Conversion => "conversion",
+            Self::Configuration => "configuration",
+        })
+    }
+}
+
 impl Error {
     /// Returns the category of the error.
     ///
@@ -334,6 +622,8 @@ pub const fn category(&self) -> Category {
             Self::YamlParseError { .. }
             | Self::TomlParseError(_)
             | Self::JsonParseError(_)
+            | Self::Json5ParseError(_)
+            | Self::RonParseError(_)
             | Self::SerdeError { .. }
             | Self::ParseError(_)
             | Self::InvalidFormat
@@ -346,11 +636,22 @@ pub const fn category(&self) -> Category {
             | Self::ExtractionError(_)
             | Self::InvalidUrl(_)
             | Self::InvalidLanguage(_) => Category::Parsing,
-            Self::ValidationError(_) => Category::Validation,
-            Self::ConversionError(_) => Category::Conversion,
+            Self::ValidationError(_)
+            | Self::KeyNotFound(_)
+            | Self::InvalidPath(_)
+            | Self::MergeConflict(_)
+            | Self::PolicyViolation { .. } => Category::Validation,
+            Self::ConversionError(_)
+            | Self::EditError(_)
+            | Self::PatchError(_)
+            | Self::DeserializeError { .. } => Category::Conversion,
             Self::ContentTooLarge { .. }
             | Self::NestingTooDeep { .. }
+            | Self::ExpansionLimitExceeded { .. }
+            | Self::InvalidOption { .. }
+            | Self::IoError { .. }
             | Self::Other(_) => Category::Configuration,
+            Self::Located { source, .. } => source.category(),
         }
     }
 
@@ -413,6 +714,9 @@ pub fn validation_error(message: &str) -> Self {
 
     /// Adds context to an error.
     ///
+    /// Any error can be given a position; an error that already has one
+    /// has it replaced.
+    ///
     /// # Arguments
     ///
     /// * `context` - Additional context information about the error.
@@ -430,28 +734,157 @@ pub fn validation_error(message: &str) -> Self {
     ///
     /// let error = Error::ParseError("Invalid syntax".to_string())
     ///     .with_context(&context);
+    /// assert_eq!(
+    ///     error.to_string(),
+    ///     "Failed to parse front matter: Invalid syntax at 42:10 near 'invalid content'"
+    /// );
+    /// assert!(matches!(error.inner(), Error::ParseError(_)));
     /// ```
     #[must_use]
     pub fn with_context(self, context: &Context) -> Self {
-        let context_info = format!(
-            " (line: {}, column: {})",
-            context.line.unwrap_or(0),
-            context.column.unwrap_or(0)
-        );
-        let snippet_info = context
-            .snippet
-            .as_ref()
-            .map(|s| format!(" near '{}'", s))
-            .unwrap_or_default();
+        let source = match self {
+            Self::Located { source, .. } => source,
+            other => Box::new(other),
+        };
+        Self::Located {
+            source,
+            context: context.clone(),
+        }
+    }
+
+    /// Returns the position of the error, if it is known.
+    #[must_use]
+    pub const fn context(&self) -> Option<&Context> {
+        match self {
+            Self::Located { context, .. } => Some(context),
+            _ => None,
+        }
+    }
 
+    /// Returns the error without its position.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use frontmatter_gen::{error::Error, extract};
+    ///
+    /// let err = extract("---\ntitle: [unclosed\n---\n").unwrap_err();
+    /// assert!(matches!(err.inner(), Error::YamlParseError { .. }));
+    /// ```
+    #[must_use]
+    pub fn inner(&self) -> &Self {
+        match self {
+            Self::Located { source, .. } => source.inner(),
+            _ => self,
+        }
+    }
+
+    /// Renders the error in the style of compiler diagnostics, with the
+    /// offending line and a caret under the position.
+    ///
+    /// The message comes first, so a caller can prefix the result with a
+    /// label such as `error: `. Errors without a position render as the
+    /// message alone.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the source, usually its file path.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use frontmatter_gen::extract;
+    ///
+    /// let err = extract("---\ntitle: Post\nbad: : x\n---\n").unwrap_err();
+    /// assert_eq!(
+    ///     err.render("post.md"),
+    ///     "Failed to parse YAML: mapping values are not allowed in this context
+    ///  --> post.md:3:6
+    ///   |
+    /// 3 | bad: : x
+    ///   |      ^"
+    /// );
+    /// ```
+    #[must_use]
+    pub fn render(&self, name: &str) -> String {
+        match self.context().and_then(|context| context.excerpt(name)) {
+            Some(excerpt) => {
+                format!("{}\n{excerpt}", self.inner().summary())
+            }
+            None => self.inner().summary(),
+        }
+    }
+
+    /// Moves the position of an error found in `block` so that it
+    /// refers to `document`, which contains `block`.
+    pub(crate) fn relocate(self, block: &str, document: &str) -> Self {
+        let offset = match &self {
+            Self::Located { context, .. } => context.offset_in(block),
+            _ => None,
+        };
+        let start = (block.as_ptr() as usize)
+            .checked_sub(document.as_ptr() as usize)
+            .filter(|start| start + block.len() <= document.len());
+
+        match (offset, start) {
+            (Some(offset), Some(start)) => self.with_context(
+                &Context::from_offset(document, start + offset),
+            ),
+            _ => self,
+        }
+    }
+
+    /// Returns the message of the error, leaving out any position the
+    /// underlying parser included in it.
+    pub(crate) fn summary(&self) -> String {
         match self {
-            Self::ParseError(msg) => Self::ParseError(format!(
-                "{msg}{context_info}{snippet_info}"
-            )),
             Self::YamlParseError { source } => {
-                Self::YamlParseError { source }
+                let mut error: &YamlError = source;
+                while let Some(inner) = error.as_inner() {
+                    error = inner;
+                }
+                match error {
+                    YamlError::ParseWithLocation {
+                        message, ..
+                    }
+                    | YamlError::DeserializeWithLocation {
+                        message,
+                        ..
+                    } => format!("Failed to parse YAML: {message}"),
+                    _ => self.to_string(),
+                }
             }
-            _ => self, // For unsupported variants
+            Self::TomlParseError(err) => {
+                format!("Failed to parse TOML: {}", err.message())
+            }
+            Self::JsonParseError(err) => {
+                let message = err.to_string();
+                let position = format!(
+                    " at line {} column {}",
+                    err.line(),
+                    err.column()
+                );
+                format!(
+                    "Failed to parse JSON: {}",
+                    message.strip_suffix(&position).unwrap_or(&message)
+                )
+            }
+            Self::Json5ParseError(err) => {
+                let message = err.to_string();
+                let position = err
+                    .position()
+                    .map(|position| format!(" at {position}"))
+                    .unwrap_or_default();
+                format!(
+                    "Failed to parse JSON5: {}",
+                    message.strip_suffix(&position).unwrap_or(&message)
+                )
+            }
+            Self::RonParseError(err) => {
+                format!("Failed to parse RON: {}", err.code)
+            }
+            Self::Located { source, .. } => source.summary(),
+            _ => self.to_string(),
         }
     }
 }
@@ -719,6 +1152,32 @@ fn test_category_method() {
                 Category::Conversion
             );
 
+            let missing_key = Error::KeyNotFound("title".to_string());
+            assert_eq!(missing_key.category(), Category::Validation);
+
+            let invalid_path = Error::InvalidPath("a..b".to_string());
+            assert_eq!(invalid_path.category(), Category::Validation);
+
+            let conflict = Error::MergeConflict("title".to_string());
+            assert_eq!(conflict.category(), Category::Validation);
+
+            let edit_error =
+                Error::EditError("Edit failed".to_string());
+            assert_eq!(edit_error.category(), Category::Conversion);
+
+            let patch_error =
+                Error::PatchError("test failed".to_string());
+            assert_eq!(patch_error.category(), Category::Conversion);
+
+            let deserialize_error = Error::DeserializeError {
+                path: "tags[0]".to_string(),
+                message: "invalid type".to_string(),
+            };
+            assert_eq!(
+                deserialize_error.category(),
+                Category::Conversion
+            );
+
             let config_error =
                 Error::ContentTooLarge { size: 100, max: 50 };
             assert_eq!(
@@ -727,6 +1186,18 @@ fn test_category_method() {
             );
         }
 
+        /// Test the `Display` implementation for `Category`.
+        #[test]
+        fn test_category_display() {
+            assert_eq!(Category::Parsing.to_string(), "parsing");
+            assert_eq!(Category::Validation.to_string(), "validation");
+            assert_eq!(Category::Conversion.to_string(), "conversion");
+            assert_eq!(
+                Category::Configuration.to_string(),
+                "configuration"
+            );
+        }
+
         /// Test the `Clone` implementation for `Error`.
         #[test]
         fn test_error_clone() {
@@ -776,6 +1247,60 @@ fn test_engine_error_to_error_conversion() {
     mod context_tests {
         use super::super::*;
 
+        /// Test positions derived from byte offsets.
+        #[test]
+        fn test_context_from_offset() {
+            let source = "a: 1\r\nnamé: é x\nlast";
+            let context = Context::from_offset(source, 13);
+            assert_eq!(context.line, Some(2));
+            assert_eq!(context.column, Some(7));
+            assert_eq!(context.snippet.as_deref(), Some("namé: é x"));
+            assert_eq!(context.offset_in(source), Some(13));
+
+            // Offsets inside a character or past the end are clamped
+            let inside = Context::from_offset(source, 12);
+            assert_eq!(inside.column, Some(6));
+            let end = Context::from_offset(source, 100);
+            assert_eq!(end.line, Some(3));
+            assert_eq!(end.column, Some(5));
+            assert_eq!(end.offset_in(source), Some(source.len()));
+        }
+
+        /// Test that any error can carry a position.
+        #[test]
+        fn test_with_context_wraps_any_error() {
+            let context = Context::from_offset("x\ny", 2);
+            let error = Error::InvalidFormat.with_context(&context);
+            assert_eq!(
+                error.to_string(),
+                "Invalid front matter format at 2:1 near 'y'"
+            );
+            assert_eq!(error.category(), Category::Parsing);
+            assert!(matches!(error.inner(), Error::InvalidFormat));
+
+            let moved =
+                error.with_context(&Context::from_offset("z", 0));
+            assert_eq!(moved.context().and_then(|c| c.line), Some(1));
+            assert!(matches!(moved.inner(), Error::InvalidFormat));
+            assert!(Error::InvalidFormat.context().is_none());
+        }
+
+        /// Test the diagnostic layout.
+        #[test]
+        fn test_render() {
+            let source = "a\nb\nc\nd\ne\nf\ng\nh\ni\n\tkey = ?";
+            let error = Error::ParseError("bad value".to_string())
+                .with_context(&Context::from_offset(source, 25));
+            assert_eq!(
+                error.render("post.md"),
+                "Failed to parse front matter: bad value\n  --> post.md:10:8\n   |\n10 | \tkey = ?\n   | \t      ^"
+            );
+            assert_eq!(
+                Error::InvalidFormat.render("post.md"),
+                "Invalid front matter format"
+            );
+        }
+
         /// Test the `Display` implementation of `Context`.
         #[test]
         fn test_context_display() {
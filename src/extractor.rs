@@ -2,14 +2,444 @@
 //!
 //! It includes functions to extract frontmatter in various formats (YAML, TOML, JSON) from a given string content, as well as utilities to detect the format of the frontmatter.
 
-use crate::error::Error;
+use std::{ops::Range, path::Path, sync::LazyLock};
+
+use crate::error::{Context, Error};
+use crate::header;
+use crate::parser::{parse_with_options, ParseOptions};
 use crate::types::Format;
 
+/// The location of a front matter block within a document.
+///
+/// Both ranges are byte ranges into the document. `frontmatter` covers
+/// the lines between the fences, without the line break before the
+/// closing fence, and is empty for a block with no lines. `body` starts
+/// after the line break that ends the closing fence and runs to the end
+/// of the document.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Block {
+    /// The byte range of the front matter, without its fences.
+    pub frontmatter: Range<usize>,
+    /// The byte range of the body that follows the front matter.
+    pub body: Range<usize>,
+    /// The format named by the fences, or of the unfenced header.
+    pub format: Format,
+}
+
+/// A pair of fences that encloses front matter in a given format.
+///
+/// Fences are whole lines. They are compared after trailing spaces and
+/// tabs are removed, from both the line and the fence.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Delimiter {
+    /// The line that opens the block, such as `---`.
+    pub open: String,
+    /// The lines that may close the block, such as `---` and `...`.
+    pub close: Vec<String>,
+    /// The format of the front matter between the fences.
+    pub format: Format,
+}
+
+impl Delimiter {
+    /// Creates a delimiter from its opening fence, the fences that may
+    /// close it, and the format between them.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use frontmatter_gen::{extractor::Delimiter, Format};
+    ///
+    /// // Pandoc closes YAML metadata blocks with `---` or `...`.
+    /// let pandoc = Delimiter::new("---", ["---", "..."], Format::Yaml);
+    /// assert_eq!(pandoc.close, ["---", "..."]);
+    /// ```
+    #[must_use]
+    pub fn new<S: Into<String>>(
+        open: impl Into<String>,
+        close: impl IntoIterator<Item = S>,
+        format: Format,
+    ) -> Self {
+        Self {
+            open: open.into(),
+            close: close.into_iter().map(Into::into).collect(),
+            format,
+        }
+    }
+
+    /// Returns `true` if `line`, as returned by [`fence_text`], closes
+    /// this delimiter's block.
+    pub(crate) fn closes(&self, line: &str) -> bool {
+        self.close
+            .iter()
+            .any(|fence| fence.trim_end_matches(BLANKS) == line)
+    }
+}
+
+impl std::str::FromStr for Delimiter {
+    type Err = Error;
+
+    /// Parses a delimiter written as `FORMAT:OPEN[:CLOSE...]`, such as
+    /// `json:;;;` or `yaml:---:---:...`. Without a closing fence the
+    /// block closes with its opening fence.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ValidationError` if the format is not `yaml`,
+    /// `toml`, `json`, `json5` or `ron`, or if a fence is empty.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.split(':');
+        let format = match parts.next().map(str::to_lowercase).as_deref() {
+            Some("yaml") => Format::Yaml,
+            Some("toml") => Format::Toml,
+            Some("json") => Format::Json,
+            Some("json5") => Format::Json5,
+            Some("ron") => Format::Ron,
+            _ => {
+                return Err(Error::ValidationError(format!(
+                    "Invalid delimiter `{s}`: expected FORMAT:OPEN[:CLOSE...] with a format of yaml, toml, json, json5 or ron"
+                )))
+            }
+        };
+        let open = parts.next().unwrap_or_default();
+        let mut close: Vec<&str> = parts.collect();
+        if close.is_empty() {
+            close.push(open);
+        }
+        if open.trim().is_empty()
+            || close.iter().any(|c| c.trim().is_empty())
+        {
+            return Err(Error::ValidationError(format!(
+                "Invalid delimiter `{s}`: fences cannot be empty"
+            )));
+        }
+        Ok(Self::new(open, close, format))
+    }
+}
+
+/// The set of delimiters an extractor recognises.
+///
+/// The default set recognises `---` for YAML, `+++` for TOML, and the
+/// `---json5` and `---ron` fences, closed by `---`, for JSON5 and RON. A
+/// document that starts with `{` is always read as a JSON object,
+/// whatever the registry holds. [`Delimiters::extended`] adds the
+/// fences found in older content, and [`Delimiters::register`] adds
+/// custom ones. Org and AsciiDoc headers, which have no fences, are
+/// only recognised once [`Delimiters::headers`] enables them.
+///
+/// # Examples
+///
+/// ```rust
+/// use frontmatter_gen::{extractor::{Delimiter, Delimiters}, Format};
+///
+/// let mut delimiters = Delimiters::default();
+/// delimiters.register(Delimiter::new("<!--", ["-->"], Format::Yaml));
+///
+/// let content = "<!--\ntitle: Hidden\n-->\nBody";
+/// let block = delimiters.find_block(content)?;
+/// assert_eq!(&content[block.frontmatter], "title: Hidden");
+/// assert_eq!(block.format, Format::Yaml);
+/// # Ok::<(), frontmatter_gen::Error>(())
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Delimiters {
+    delimiters: Vec<Delimiter>,
+    headers: bool,
+}
+
+impl Default for Delimiters {
+    fn default() -> Self {
+        Self {
+            headers: false,
+            delimiters: vec![
+                Delimiter::new("---", ["---"], Format::Yaml),
+                Delimiter::new("+++", ["+++"], Format::Toml),
+                Delimiter::new("---json5", ["---"], Format::Json5),
+                Delimiter::new("---ron", ["---"], Format::Ron),
+            ],
+        }
+    }
+}
+
+impl Delimiters {
+    /// Creates a registry that recognises only JSON objects.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            delimiters: Vec::new(),
+            headers: false,
+        }
+    }
+
+    /// Creates a registry with the default delimiters and the ones
+    /// found in older content: `---yaml` and `---toml` language-tagged
+    /// fences closed by `---`, `;;;` around JSON, and the Pandoc `...`
+    /// closer for `---` blocks.
+    #[must_use]
+    pub fn extended() -> Self {
+        let mut delimiters = Self::default();
+        let _ = delimiters
+            .register(Delimiter::new(
+                "---",
+                ["---", "..."],
+                Format::Yaml,
+            ))
+            .register(Delimiter::new("---yaml", ["---"], Format::Yaml))
+            .register(Delimiter::new("---toml", ["---"], Format::Toml))
+            .register(Delimiter::new(";;;", [";;;"], Format::Json));
+        delimiters
+    }
+
+    /// Adds a delimiter, replacing any with the same opening fence.
+    pub fn register(&mut self, delimiter: Delimiter) -> &mut Self {
+        let open = delimiter.open.trim_end_matches(BLANKS);
+        match self
+            .delimiters
+            .iter_mut()
+            .find(|d| d.open.trim_end_matches(BLANKS) == open)
+        {
+            Some(existing) => *existing = delimiter,
+            None => self.delimiters.push(delimiter),
+        }
+        self
+    }
+
+    /// Sets whether a document that opens with an Org `#+KEYWORD:` line,
+    /// or an AsciiDoc `= Title` line or `:name:` attribute entry, holds
+    /// that header as its front matter.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use frontmatter_gen::{extractor::Delimiters, Format};
+    ///
+    /// let content = ":smile: Great news today\n\nBody";
+    /// assert!(Delimiters::default().find_block(content).is_err());
+    ///
+    /// let mut delimiters = Delimiters::default();
+    /// let block = delimiters.headers(true).find_block(content)?;
+    /// assert_eq!(block.format, Format::AsciiDoc);
+    /// # Ok::<(), frontmatter_gen::Error>(())
+    /// ```
+    pub fn headers(&mut self, enabled: bool) -> &mut Self {
+        self.headers = enabled;
+        self
+    }
+
+    /// Returns the registry to read the file at `path` with: this one,
+    /// with headers recognised if the file is an Org (`.org`) or
+    /// AsciiDoc (`.adoc`, `.asciidoc`) document.
+    #[must_use]
+    pub fn for_path(&self, path: impl AsRef<Path>) -> Self {
+        let is_header_file = path
+            .as_ref()
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| {
+                ["org", "adoc", "asciidoc"]
+                    .iter()
+                    .any(|header| ext.eq_ignore_ascii_case(header))
+            });
+        let mut delimiters = self.clone();
+        let _ = delimiters.headers(self.headers || is_header_file);
+        delimiters
+    }
+
+    /// Returns the delimiter opened by `line`, as returned by
+    /// [`fence_text`].
+    pub(crate) fn opening(&self, line: &str) -> Option<&Delimiter> {
+        self.iter()
+            .find(|d| d.open.trim_end_matches(BLANKS) == line)
+    }
+
+    /// Returns the registered delimiters, in the order they were added.
+    pub fn iter(&self) -> std::slice::Iter<'_, Delimiter> {
+        self.delimiters.iter()
+    }
+
+    /// Finds the front matter block at the start of a document.
+    ///
+    /// The opening fence must be the first line of the document, after
+    /// an optional byte order mark, and must match a registered
+    /// delimiter. The closing fence must be a line of its own that the
+    /// same delimiter accepts, so a `---` inside the body, such as a
+    /// horizontal rule, never ends the block early. Fences may be
+    /// followed by spaces or tabs, and lines may end with LF or CRLF,
+    /// mixed freely. A document that starts with `{` holds a JSON block
+    /// that ends at the brace that balances the first. With
+    /// [`Delimiters::headers`] enabled, one that starts with no fence but
+    /// an Org `#+KEYWORD:` line or an AsciiDoc `= Title` or `:name:` line
+    /// holds that header.
+    ///
+    /// # Arguments
+    ///
+    /// * `content` - The whole document.
+    ///
+    /// # Errors
+    ///
+    /// - `Error::InvalidFormat`: If the document does not open with a
+    ///   fence.
+    /// - `Error::ExtractionError`: If the block is never closed, with
+    ///   the position of the opening fence.
+    /// - `Error::JsonDepthLimitExceeded`: If a JSON block is nested too
+    ///   deeply.
+    pub fn find_block(&self, content: &str) -> Result<Block, Error> {
+        let start = if content.starts_with(BOM) {
+            BOM.len()
+        } else {
+            0
+        };
+        let mut lines = lines(content, start);
+        let opening = lines.next().ok_or(Error::InvalidFormat)?;
+
+        if content[start..].starts_with('{') {
+            return json_block(content, start);
+        }
+
+        let fence = fence_text(content, &opening);
+        let Some(delimiter) = self.opening(fence) else {
+            return self
+                .headers
+                .then(|| header_block(content, &opening))
+                .flatten()
+                .ok_or(Error::InvalidFormat);
+        };
+
+        // `previous_end` is where the last line's text ends, before its
+        // line break, so the block stops short of the break before the
+        // fence.
+        let mut previous_end = None;
+        for line in lines {
+            if delimiter.closes(fence_text(content, &line)) {
+                return Ok(Block {
+                    frontmatter: opening.next
+                        ..previous_end.unwrap_or(opening.next),
+                    body: line.next..content.len(),
+                    format: delimiter.format,
+                });
+            }
+            previous_end = Some(line.end);
+        }
+
+        let closers = delimiter
+            .close
+            .iter()
+            .map(|fence| format!("`{fence}`"))
+            .collect::<Vec<_>>()
+            .join(" or ");
+        Err(Error::ExtractionError(format!(
+            "The front matter opened with `{fence}` is never closed with {closers}"
+        ))
+        .with_context(&Context::from_offset(content, start)))
+    }
+
+    /// Extracts the raw front matter and the remaining content, as
+    /// [`extract_raw_frontmatter`] does with the default delimiters.
+    ///
+    /// # Errors
+    ///
+    /// Returns the errors of [`Delimiters::find_block`].
+    pub fn extract_raw<'a>(
+        &self,
+        content: &'a str,
+    ) -> Result<(&'a str, &'a str), Error> {
+        let block = self.find_block(content)?;
+        Ok((
+            &content[block.frontmatter],
+            content[block.body].trim_start(),
+        ))
+    }
+}
+
+impl<'a> IntoIterator for &'a Delimiters {
+    type Item = &'a Delimiter;
+    type IntoIter = std::slice::Iter<'a, Delimiter>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.delimiters.iter()
+    }
+}
+
+/// Finds the front matter block at the start of a document, using the
+/// default [`Delimiters`].
+///
+/// See [`Delimiters::find_block`] for the rules the fences follow.
+///
+/// # Arguments
+///
+/// * `content` - The whole document.
+///
+/// # Errors
+///
+/// - `Error::InvalidFormat`: If the document does not open with a fence.
+/// - `Error::ExtractionError`: If the block is never closed, with the
+///   position of the opening fence.
+/// - `Error::JsonDepthLimitExceeded`: If a JSON block is nested too
+///   deeply.
+///
+/// # Examples
+///
+/// ```rust
+/// use frontmatter_gen::{extractor::find_block, Format};
+///
+/// let content = "---\r\ntitle: Example\n---  \r\nBody\n\n---\nMore body";
+/// let block = find_block(content)?;
+/// assert_eq!(&content[block.frontmatter], "title: Example");
+/// assert_eq!(&content[block.body], "Body\n\n---\nMore body");
+/// assert_eq!(block.format, Format::Yaml);
+/// # Ok::<(), frontmatter_gen::Error>(())
+/// ```
+pub fn find_block(content: &str) -> Result<Block, Error> {
+    DEFAULT_DELIMITERS.find_block(content)
+}
+
+/// Finds a JSON block that starts at byte `start`.
+fn json_block(content: &str, start: usize) -> Result<Block, Error> {
+    let json = extract_json_frontmatter(&content[start..])?;
+    let end = start + json.len();
+    // The body starts on the next line unless the object is followed by
+    // more text on its own line.
+    let rest = &content[end..];
+    let line_end = rest.find('\n').map_or(rest.len(), |i| i + 1);
+    let body = if rest[..line_end].trim().is_empty() {
+        end + line_end
+    } else {
+        end
+    };
+    Ok(Block {
+        frontmatter: start..end,
+        body: body..content.len(),
+        format: Format::Json,
+    })
+}
+
+/// Finds the Org or AsciiDoc header that opens with `opening`.
+///
+/// An Org header runs while lines are keywords or comments, and an
+/// AsciiDoc header until the first blank line.
+fn header_block(content: &str, opening: &Line) -> Option<Block> {
+    let format = header::opening(fence_text(content, opening))?;
+    let last = lines(content, opening.next)
+        .take_while(|line| {
+            header::continues(format, fence_text(content, line))
+        })
+        .last();
+    let (end, next) = last
+        .map_or((opening.end, opening.next), |line| {
+            (line.end, line.next)
+        });
+    Some(Block {
+        frontmatter: opening.start..end,
+        body: next..content.len(),
+        format,
+    })
+}
+
 /// Extracts raw frontmatter from the content, detecting YAML, TOML, or JSON formats.
 ///
-/// This function tries to extract frontmatter based on the common delimiters for
-/// YAML (`---`), TOML (`+++`), and JSON (`{}`). If frontmatter is detected, it
-/// returns the extracted frontmatter and the remaining content.
+/// This function locates the front matter with [`find_block`], so the
+/// fences follow the same rules. The front matter is returned exactly as
+/// it appears between the fences, and the remaining content has its
+/// leading whitespace removed.
 ///
 /// # Arguments
 ///
@@ -23,7 +453,7 @@
 /// # Errors
 ///
 /// - `Error::InvalidFormat`: When the frontmatter format is not recognized.
-/// - `Error::ExtractionError`: When there is an issue extracting frontmatter.
+/// - `Error::ExtractionError`: When the front matter is never closed.
 ///
 /// # Example
 ///
@@ -37,52 +467,71 @@
 pub fn extract_raw_frontmatter(
     content: &str,
 ) -> Result<(&str, &str), Error> {
-    // Extract YAML frontmatter
-    if let Some(yaml) =
-        extract_delimited_frontmatter(content, "---\n", "\n---")
-            .or_else(|| {
-                extract_delimited_frontmatter(
-                    content, "---\r\n", "\r\n---",
-                )
-            })
-    {
-        let remaining = &content[content
-            .find("\n---\n")
-            .or_else(|| content.find("\r\n---\r\n"))
-            .map_or(content.len(), |i| i + 5)..];
-        return Ok((yaml, remaining.trim_start()));
-    }
+    DEFAULT_DELIMITERS.extract_raw(content)
+}
 
-    // Extract TOML frontmatter
-    if let Some(toml) =
-        extract_delimited_frontmatter(content, "+++\n", "\n+++")
-            .or_else(|| {
-                extract_delimited_frontmatter(
-                    content, "+++\r\n", "\r\n+++",
-                )
-            })
-    {
-        let remaining = &content[content
-            .find("\n+++\n")
-            .or_else(|| content.find("\r\n+++\r\n"))
-            .map_or(content.len(), |i| i + 5)..];
-        return Ok((toml, remaining.trim_start()));
-    }
+/// The byte order mark that may precede the opening fence.
+pub(crate) const BOM: &str = "\u{feff}";
 
-    // Extract JSON frontmatter
-    if let Ok(json) = extract_json_frontmatter(content) {
-        let remaining = &content[json.len()..];
-        return Ok((json, remaining.trim_start()));
-    }
+/// The characters allowed after a fence.
+const BLANKS: [char; 2] = [' ', '\t'];
 
-    // Handle cases where frontmatter delimiters exist but are empty
-    if content.starts_with("---\n---")
-        || content.starts_with("+++\n+++")
-    {
-        return Err(Error::InvalidFormat);
-    }
+/// The delimiters used when none are given.
+pub(crate) static DEFAULT_DELIMITERS: LazyLock<Delimiters> =
+    LazyLock::new(Delimiters::default);
+
+/// A line of a document, as byte offsets into it.
+struct Line {
+    /// Where the line starts.
+    start: usize,
+    /// Where the line's text ends, before any line break.
+    end: usize,
+    /// Where the next line starts, after the line break.
+    next: usize,
+}
+
+/// Returns the lines of `content` from byte `start`, ending at LF or
+/// CRLF.
+fn lines(
+    content: &str,
+    start: usize,
+) -> impl Iterator<Item = Line> + '_ {
+    let mut position = start;
+    std::iter::from_fn(move || {
+        if position >= content.len() {
+            return None;
+        }
+        let rest = &content[position..];
+        let (text_len, next) = match rest.find('\n') {
+            Some(i) => (i, position + i + 1),
+            None => (rest.len(), content.len()),
+        };
+        let text = rest[..text_len]
+            .strip_suffix('\r')
+            .unwrap_or(&rest[..text_len]);
+        let line = Line {
+            start: position,
+            end: position + text.len(),
+            next,
+        };
+        position = next;
+        Some(line)
+    })
+}
+
+/// Returns the text of a line without trailing spaces or tabs, as it is
+/// compared against a fence.
+fn fence_text<'a>(content: &'a str, line: &Line) -> &'a str {
+    content[line.start..line.end].trim_end_matches(BLANKS)
+}
 
-    Err(Error::InvalidFormat)
+/// Returns the text of a single line, which may end with LF or CRLF, as
+/// it is compared with fences.
+pub(crate) fn line_fence(line: &str) -> &str {
+    let line = line.strip_suffix('\n').unwrap_or(line);
+    line.strip_suffix('\r')
+        .unwrap_or(line)
+        .trim_end_matches(BLANKS)
 }
 
 /// Extracts JSON frontmatter from the content by detecting balanced curly braces (`{}`).
@@ -166,10 +615,14 @@ pub fn extract_json_frontmatter(content: &str) -> Result<&str, Error> {
     Err(Error::InvalidJson)
 }
 
-/// Detects the format of the extracted frontmatter.
+/// Detects the format of front matter that has no fence to name it.
 ///
-/// This function analyzes the raw frontmatter and determines whether it is in YAML,
-/// TOML, or JSON format by examining the structure of the data.
+/// Front matter found with [`find_block`] already carries the format
+/// its fence implies in [`Block::format`], so this is only a fallback.
+/// If `raw_frontmatter` still starts with a registered fence, the fence
+/// decides. Otherwise the text is parsed as JSON, TOML and YAML in turn,
+/// and the first format that yields a non-empty mapping wins, so a TOML
+/// string such as `title = "Part 1: Intro"` is never mistaken for YAML.
 ///
 /// # Arguments
 ///
@@ -181,7 +634,7 @@ pub fn extract_json_frontmatter(content: &str) -> Result<&str, Error> {
 ///
 /// # Errors
 ///
-/// - `Error::InvalidFormat`: If the format cannot be determined.
+/// - `Error::InvalidFormat`: If the text parses as none of the formats.
 ///
 /// # Example
 ///
@@ -189,34 +642,29 @@ pub fn extract_json_frontmatter(content: &str) -> Result<&str, Error> {
 /// use frontmatter_gen::extractor::detect_format;
 /// use frontmatter_gen::Format;
 /// let raw = "---\ntitle: Example\n---";
-/// let format = detect_format(raw).unwrap();
-/// assert_eq!(format, Format::Yaml);
+/// assert_eq!(detect_format(raw).unwrap(), Format::Yaml);
+///
+/// let raw = "title = \"Part 1: Intro\"";
+/// assert_eq!(detect_format(raw).unwrap(), Format::Toml);
 /// ```
 pub fn detect_format(raw_frontmatter: &str) -> Result<Format, Error> {
-    let trimmed = raw_frontmatter.trim_start();
-
-    // Check for YAML front matter marker
-    if trimmed.starts_with("---") {
-        return Ok(Format::Yaml);
-    }
-
-    // Check for JSON structure
-    if trimmed.starts_with('{') {
-        return Ok(Format::Json);
-    }
-
-    // Check for YAML-like structure
-    if trimmed.contains(':') && !trimmed.contains('{') {
-        return Ok(Format::Yaml);
+    if let Ok(block) = find_block(raw_frontmatter) {
+        return Ok(block.format);
     }
 
-    // Check for TOML-like structure
-    if trimmed.contains('=') {
-        return Ok(Format::Toml);
-    }
-
-    // Default to an error if none of the formats match
-    Err(Error::InvalidFormat)
+    let options = ParseOptions {
+        validate: false,
+        ..ParseOptions::default()
+    };
+    [Format::Json, Format::Toml, Format::Yaml]
+        .into_iter()
+        .find(|&format| {
+            parse_with_options(raw_frontmatter, format, Some(options))
+                .is_ok_and(|frontmatter| {
+                    format == Format::Json || !frontmatter.is_empty()
+                })
+        })
+        .ok_or(Error::InvalidFormat)
 }
 
 /// Extracts frontmatter enclosed by the given start and end delimiters.
@@ -310,6 +758,264 @@ fn test_invalid_format() {
         }
     }
 
+    /// Tests for locating the front matter block
+    mod find_block {
+        use super::*;
+
+        fn parts(content: &str) -> (&str, &str) {
+            let block = find_block(content).unwrap();
+            (&content[block.frontmatter], &content[block.body])
+        }
+
+        #[test]
+        fn test_body_fences_do_not_close_the_block() {
+            let content =
+                "---\ntitle: A\nrule: x---\n---\nIntro\n\n---\n\nMore";
+            assert_eq!(
+                parts(content),
+                ("title: A\nrule: x---", "Intro\n\n---\n\nMore")
+            );
+
+            // A `---` before the closing fence is part of the YAML.
+            let content = "---\na: 1\n---x\n----\n---\nBody";
+            assert_eq!(parts(content), ("a: 1\n---x\n----", "Body"));
+        }
+
+        #[test]
+        fn test_line_endings_and_trailing_whitespace() {
+            let content =
+                "+++ \t\r\na = 1\r\nb = 2\n+++\t\r\n\r\nBody\r\n";
+            assert_eq!(
+                parts(content),
+                ("a = 1\r\nb = 2", "\r\nBody\r\n")
+            );
+        }
+
+        #[test]
+        fn test_exact_ranges() {
+            let content = "\u{feff}---\n  a: 1\n\n---";
+            let block = find_block(content).unwrap();
+            assert_eq!(block.frontmatter, 7..14);
+            assert_eq!(block.body, 18..18);
+
+            let block = find_block("---\n---\nBody").unwrap();
+            assert_eq!(block.frontmatter, 4..4);
+            assert_eq!(block.body, 8..12);
+        }
+
+        #[test]
+        fn test_opening_fence_must_come_first() {
+            for content in [
+                "\n---\na: 1\n---\n",
+                " ---\na: 1\n---\n",
+                "--- a\n---\n",
+                "",
+            ] {
+                assert!(matches!(
+                    find_block(content),
+                    Err(Error::InvalidFormat)
+                ));
+            }
+        }
+
+        #[test]
+        fn test_unclosed_block_points_at_the_fence() {
+            let err = find_block("---\ntitle: A\n+++\n").unwrap_err();
+            assert!(matches!(err.inner(), Error::ExtractionError(_)));
+            assert_eq!(err.context().and_then(|c| c.line), Some(1));
+        }
+
+        #[test]
+        fn test_json_block() {
+            let content = "{\"a\": \"}\"}  \nBody";
+            assert_eq!(parts(content), ("{\"a\": \"}\"}", "Body"));
+
+            let content = "{\"a\": 1} Body";
+            assert_eq!(parts(content), ("{\"a\": 1}", " Body"));
+        }
+    }
+
+    /// Tests for the delimiter registry
+    mod delimiters {
+        use super::*;
+
+        fn parts(
+            delimiters: &Delimiters,
+            content: &str,
+        ) -> (String, Format) {
+            let block = delimiters.find_block(content).unwrap();
+            (content[block.frontmatter].to_string(), block.format)
+        }
+
+        #[test]
+        fn test_default_formats() {
+            let delimiters = Delimiters::default();
+            assert_eq!(
+                parts(
+                    &delimiters,
+                    "---
+a: 1
+---
+"
+                ),
+                ("a: 1".to_string(), Format::Yaml)
+            );
+            assert_eq!(
+                parts(
+                    &delimiters,
+                    "+++
+a = 1
++++
+"
+                ),
+                ("a = 1".to_string(), Format::Toml)
+            );
+            assert_eq!(
+                parts(&delimiters, "{\"a\": 1}\n"),
+                ("{\"a\": 1}".to_string(), Format::Json)
+            );
+            assert_eq!(
+                parts(&delimiters, "---json5\n{a: 1,}\n---\n"),
+                ("{a: 1,}".to_string(), Format::Json5)
+            );
+            assert_eq!(
+                parts(&delimiters, "---ron\n(a: 1)\n---\n"),
+                ("(a: 1)".to_string(), Format::Ron)
+            );
+            assert!(matches!(
+                delimiters.find_block(";;;\n{}\n;;;\n"),
+                Err(Error::InvalidFormat)
+            ));
+        }
+
+        #[test]
+        fn test_extended_fences() {
+            let delimiters = Delimiters::extended();
+            assert_eq!(
+                parts(&delimiters, ";;;\n{\"a\": 1}\n;;;\nBody"),
+                ("{\"a\": 1}".to_string(), Format::Json)
+            );
+            assert_eq!(
+                parts(&delimiters, "---toml\na = 1\n---\n"),
+                ("a = 1".to_string(), Format::Toml)
+            );
+            assert_eq!(
+                parts(&delimiters, "---yaml \na: 1\n---\n"),
+                ("a: 1".to_string(), Format::Yaml)
+            );
+            // Pandoc closes YAML metadata blocks with `...`.
+            assert_eq!(
+                parts(&delimiters, "---\na: 1\n...\nBody\n---\n"),
+                ("a: 1".to_string(), Format::Yaml)
+            );
+        }
+
+        #[test]
+        fn test_register_replaces_the_same_opening_fence() {
+            let mut delimiters = Delimiters::default();
+            let _ = delimiters.register(Delimiter::new(
+                "+++ ",
+                ["==="],
+                Format::Yaml,
+            ));
+            assert_eq!(delimiters.iter().count(), 4);
+            assert_eq!(
+                parts(&delimiters, "+++\na: 1\n+++\n===\n"),
+                ("a: 1\n+++".to_string(), Format::Yaml)
+            );
+
+            let err = delimiters.find_block("+++\na: 1\n").unwrap_err();
+            assert!(err.to_string().contains("`===`"));
+        }
+
+        #[test]
+        fn test_headers_are_opt_in() {
+            let mut delimiters = Delimiters::default();
+            for content in [
+                "#+TITLE: A\n\nBody",
+                "= A\n\nBody",
+                ":smile: Great news today\n\nBody",
+            ] {
+                assert!(matches!(
+                    delimiters.find_block(content),
+                    Err(Error::InvalidFormat)
+                ));
+            }
+
+            let _ = delimiters.headers(true);
+            let content = "#+TITLE: A\n# note\n#+TAGS: b\n* Heading\n";
+            let block = delimiters.find_block(content).unwrap();
+            assert_eq!(block.format, Format::Org);
+            assert_eq!(
+                &content[block.frontmatter],
+                "#+TITLE: A\n# note\n#+TAGS: b"
+            );
+            assert_eq!(&content[block.body], "* Heading\n");
+
+            let content = "= A\r\nJane Doe\r\n:toc:\r\n\r\nBody";
+            let block = delimiters.find_block(content).unwrap();
+            assert_eq!(block.format, Format::AsciiDoc);
+            assert_eq!(
+                &content[block.frontmatter],
+                "= A\r\nJane Doe\r\n:toc:"
+            );
+            assert_eq!(&content[block.body], "\r\nBody");
+
+            assert!(matches!(
+                delimiters.find_block("# Heading\n#+TITLE: A\n"),
+                Err(Error::InvalidFormat)
+            ));
+        }
+
+        #[test]
+        fn test_for_path_enables_headers() {
+            let delimiters = Delimiters::extended();
+            for (path, headers) in [
+                ("notes/guide.org", true),
+                ("manual.ADOC", true),
+                ("manual.asciidoc", true),
+                ("post.md", false),
+                ("README", false),
+            ] {
+                let for_path = delimiters.for_path(path);
+                assert_eq!(for_path.headers, headers, "{path}");
+                assert_eq!(for_path.delimiters, delimiters.delimiters);
+            }
+        }
+
+        #[test]
+        fn test_parse_delimiter() {
+            assert_eq!(
+                "JSON:;;;".parse::<Delimiter>().unwrap(),
+                Delimiter::new(";;;", [";;;"], Format::Json)
+            );
+            assert_eq!(
+                "yaml:---:---:...".parse::<Delimiter>().unwrap(),
+                Delimiter::new("---", ["---", "..."], Format::Yaml)
+            );
+            assert_eq!(
+                "ron:---ron:---".parse::<Delimiter>().unwrap(),
+                Delimiter::new("---ron", ["---"], Format::Ron)
+            );
+            for invalid in ["xml:<<<", "yaml", "toml:", "yaml:---:"] {
+                assert!(matches!(
+                    invalid.parse::<Delimiter>(),
+                    Err(Error::ValidationError(_))
+                ));
+            }
+        }
+
+        #[test]
+        fn test_empty_registry_reads_only_json() {
+            let delimiters = Delimiters::new();
+            assert!(delimiters.find_block("---\na: 1\n---\n").is_err());
+            assert_eq!(
+                delimiters.extract_raw("{\"a\": 1}\n\nBody").unwrap(),
+                ("{\"a\": 1}", "Body")
+            );
+        }
+    }
+
     /// Tests for JSON frontmatter extraction
     mod extract_json_frontmatter {
         use super::*;
@@ -394,6 +1100,43 @@ fn test_json_format() {
             assert_eq!(result, Format::Json);
         }
 
+        #[test]
+        fn test_colons_in_toml_strings() {
+            let content =
+                "title = \"Part 1: Intro\"\nurl = \"https://a.b\"";
+            assert_eq!(detect_format(content).unwrap(), Format::Toml);
+        }
+
+        #[test]
+        fn test_fences_decide() {
+            assert_eq!(
+                detect_format("+++\ntitle = \"A: B\"\n+++\n").unwrap(),
+                Format::Toml
+            );
+            assert_eq!(
+                detect_format("---\ntitle: A = B\n---\n").unwrap(),
+                Format::Yaml
+            );
+        }
+
+        #[test]
+        fn test_yaml_with_equals_signs() {
+            let content = "title: a = b\ntags: [x]";
+            assert_eq!(detect_format(content).unwrap(), Format::Yaml);
+        }
+
+        #[test]
+        fn test_empty_and_scalar_content() {
+            for content in
+                ["", "# a = b", "just: [unclosed", "- a\n- b"]
+            {
+                assert!(matches!(
+                    detect_format(content),
+                    Err(Error::InvalidFormat)
+                ));
+            }
+        }
+
         #[test]
         fn test_invalid_format() {
             let content = "Invalid content";
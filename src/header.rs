@@ -0,0 +1,653 @@
+// header.rs
+
+//! Org-mode and AsciiDoc document headers.
+//!
+//! Org files keep their metadata in `#+KEYWORD: value` lines, and
+//! AsciiDoc files in a header of a `= Title` line followed by
+//! `:name: value` attribute entries. Neither is fenced: an Org header is
+//! the run of keyword and comment lines the document opens with, and an
+//! AsciiDoc header ends at the first blank line.
+//!
+//! Both syntaxes hold text, so every value is read as a string. Org
+//! keywords are case-insensitive and are read in lowercase; a keyword
+//! given more than once becomes an array. The AsciiDoc title is read as
+//! `title`, the author line as `author` and `email` (and `authors` when
+//! it names several), and the revision line as `revnumber`, `revdate`
+//! and `revremark`. An attribute unset with `:name!:` is read as `null`.
+//!
+//! ## Usage Example
+//!
+//! ```rust
+//! use frontmatter_gen::{parser, Format, Value};
+//!
+//! let org = "#+TITLE: My Post\n#+TAGS: rust\n#+TAGS: org\n";
+//! let front_matter = parser::parse(org, Format::Org)?;
+//! assert_eq!(front_matter.get("title"), Some(&Value::from("My Post")));
+//!
+//! let asciidoc = parser::to_string(&front_matter, Format::AsciiDoc)?;
+//! assert_eq!(asciidoc, "= My Post\n:tags: rust, org\n");
+//! # Ok::<(), frontmatter_gen::Error>(())
+//! ```
+
+use indexmap::map::Entry;
+
+use crate::{
+    error::{Context, Error},
+    types::{Format, Frontmatter, Value},
+};
+
+/// Returns the header format a document opening with `line` uses, if
+/// any.
+pub(crate) fn opening(line: &str) -> Option<Format> {
+    if org_keyword(line).is_some() {
+        Some(Format::Org)
+    } else if asciidoc_title(line).is_some()
+        || asciidoc_attribute(line).is_some()
+    {
+        Some(Format::AsciiDoc)
+    } else {
+        None
+    }
+}
+
+/// Returns `true` if `line` still belongs to a header of `format`.
+pub(crate) fn continues(format: Format, line: &str) -> bool {
+    match format {
+        Format::Org => {
+            org_keyword(line).is_some() || is_org_comment(line)
+        }
+        _ => !line.trim().is_empty(),
+    }
+}
+
+/// Returns the lines of `raw`, trimmed, with the offsets they start at.
+fn lines(raw: &str) -> impl Iterator<Item = (usize, &str)> {
+    raw.split_inclusive('\n').scan(0, |offset, line| {
+        let start = *offset;
+        *offset += line.len();
+        Some((start, line.trim()))
+    })
+}
+
+/// Returns a parse error pointing at byte `offset` of `raw`.
+fn error(raw: &str, offset: usize, message: String) -> Error {
+    Error::ParseError(message)
+        .with_context(&Context::from_offset(raw, offset))
+}
+
+/// Returns the text of a scalar value, as a header line holds it.
+fn text(
+    key: &str,
+    value: &Value,
+    format: Format,
+) -> Result<String, Error> {
+    let text = match value {
+        Value::String(s) if !s.contains(['\r', '\n']) => s.clone(),
+        Value::Integer(i) => i.to_string(),
+        Value::Float(f) => f.to_string(),
+        Value::Boolean(b) => b.to_string(),
+        Value::DateTime(dt) => dt.to_string(),
+        Value::String(_) => {
+            return Err(Error::ConversionError(format!(
+                "{format} cannot write `{key}` across several lines"
+            )))
+        }
+        other => {
+            return Err(Error::ConversionError(format!(
+                "{format} cannot write the {} value of `{key}`",
+                other.type_name()
+            )))
+        }
+    };
+    Ok(text)
+}
+
+/// Appends `prefix`, then a space and `text` unless it is empty.
+fn push_line(out: &mut String, prefix: &str, text: &str) {
+    out.push_str(prefix);
+    if !text.is_empty() {
+        out.push(' ');
+        out.push_str(text);
+    }
+    out.push('\n');
+}
+
+// Org Implementation
+// ------------------
+
+/// Splits an Org keyword line, `#+KEY: value`, into its key and value.
+fn org_keyword(line: &str) -> Option<(&str, &str)> {
+    let (key, value) =
+        line.trim_start().strip_prefix("#+")?.split_once(':')?;
+    if key.is_empty()
+        || key.contains(char::is_whitespace)
+        || !(value.is_empty() || value.starts_with([' ', '\t']))
+    {
+        return None;
+    }
+    Some((key, value.trim()))
+}
+
+/// Returns `true` if `line` is an Org comment.
+fn is_org_comment(line: &str) -> bool {
+    let line = line.trim();
+    line == "#" || line.starts_with("# ")
+}
+
+/// Parses Org keyword lines into a `Frontmatter` object.
+///
+/// # Errors
+///
+/// Returns `Error::ParseError`, with its position, for a line that is
+/// neither a keyword, a comment nor blank.
+pub(crate) fn parse_org(raw: &str) -> Result<Frontmatter, Error> {
+    let mut front_matter = Frontmatter::new();
+    for (offset, line) in lines(raw) {
+        if line.is_empty() || is_org_comment(line) {
+            continue;
+        }
+        let (key, value) = org_keyword(line).ok_or_else(|| {
+            error(
+                raw,
+                offset,
+                format!(
+                    "`{line}` is not an Org keyword such as `#+TITLE: value`"
+                ),
+            )
+        })?;
+
+        let value = Value::String(value.to_string());
+        match front_matter.0.entry(key.to_lowercase()) {
+            Entry::Vacant(entry) => {
+                let _ = entry.insert(value);
+            }
+            Entry::Occupied(mut entry) => match entry.get_mut() {
+                Value::Array(values) => values.push(value),
+                first => {
+                    let first = std::mem::replace(first, Value::Null);
+                    let _ =
+                        entry.insert(Value::Array(vec![first, value]));
+                }
+            },
+        }
+    }
+    Ok(front_matter)
+}
+
+/// Serialises a `Frontmatter` object into Org keyword lines.
+///
+/// Keys are written in uppercase, arrays as one line per item, and
+/// `null` values are skipped.
+///
+/// # Errors
+///
+/// Returns `Error::ConversionError` for a key that is not a keyword
+/// name, or a value that is not a scalar or an array of scalars.
+pub(crate) fn to_org(
+    front_matter: &Frontmatter,
+) -> Result<String, Error> {
+    let mut out = String::new();
+    for (key, value) in front_matter.iter() {
+        if key.is_empty()
+            || key.contains(|c: char| c.is_whitespace() || c == ':')
+        {
+            return Err(Error::ConversionError(format!(
+                "`{key}` cannot be written as an Org keyword"
+            )));
+        }
+        let prefix = format!("#+{}:", key.to_uppercase());
+        match value {
+            Value::Null => {}
+            Value::Array(items) => {
+                for item in items {
+                    push_line(
+                        &mut out,
+                        &prefix,
+                        &text(key, item, Format::Org)?,
+                    );
+                }
+            }
+            value => {
+                push_line(
+                    &mut out,
+                    &prefix,
+                    &text(key, value, Format::Org)?,
+                );
+            }
+        }
+    }
+    Ok(out)
+}
+
+// AsciiDoc Implementation
+// -----------------------
+
+/// Returns the text of an AsciiDoc document title line, `= Title`.
+fn asciidoc_title(line: &str) -> Option<&str> {
+    let title = line.strip_prefix("= ")?.trim();
+    (!title.is_empty()).then_some(title)
+}
+
+/// Splits an AsciiDoc attribute entry, `:name: value`, into its name
+/// and value. The value is `None` where the entry unsets the
+/// attribute, as `:name!:` or `:!name:` do.
+fn asciidoc_attribute(line: &str) -> Option<(&str, Option<&str>)> {
+    let (name, value) = line.strip_prefix(':')?.split_once(':')?;
+    if !(value.is_empty() || value.starts_with([' ', '\t'])) {
+        return None;
+    }
+    let (name, unset) =
+        match name.strip_prefix('!').or_else(|| name.strip_suffix('!'))
+        {
+            Some(name) => (name, true),
+            None => (name, false),
+        };
+    is_attribute_name(name)
+        .then(|| (name, (!unset).then(|| value.trim())))
+}
+
+/// Returns `true` if `name` can name an AsciiDoc attribute.
+fn is_attribute_name(name: &str) -> bool {
+    name.starts_with(|c: char| c.is_alphanumeric() || c == '_')
+        && name
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == '_' || c == '-')
+}
+
+/// Where an AsciiDoc header line falls, which decides how an implicit
+/// line is read.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Previous {
+    /// No line has been read yet.
+    Nothing,
+    /// The document title.
+    Title,
+    /// The author line.
+    Author,
+    /// An attribute entry or the revision line.
+    Other,
+}
+
+/// Parses an AsciiDoc document header into a `Frontmatter` object.
+///
+/// Attribute values wrapped onto the next line with a trailing ` \`
+/// are joined with a space.
+///
+/// # Errors
+///
+/// Returns `Error::ParseError`, with its position, for a line that is
+/// not part of a document header.
+pub(crate) fn parse_asciidoc(raw: &str) -> Result<Frontmatter, Error> {
+    let mut front_matter = Frontmatter::new();
+    let mut previous = Previous::Nothing;
+    // An attribute whose value continues on the next line.
+    let mut wrapped: Option<(String, String)> = None;
+
+    for (offset, line) in lines(raw) {
+        if let Some((name, mut value)) = wrapped.take() {
+            value.push(' ');
+            value.push_str(line);
+            match soft_wrap(&value) {
+                Some(head) => wrapped = Some((name, head.to_string())),
+                None => {
+                    let _ =
+                        front_matter.insert(name, Value::String(value));
+                }
+            }
+            continue;
+        }
+        if line.is_empty() || line.starts_with("//") {
+            continue;
+        }
+
+        if let Some(title) = asciidoc_title(line)
+            .filter(|_| previous == Previous::Nothing)
+        {
+            let _ = front_matter.insert(
+                "title".to_string(),
+                Value::String(title.to_string()),
+            );
+            previous = Previous::Title;
+        } else if let Some((name, value)) = asciidoc_attribute(line) {
+            let name = name.to_lowercase();
+            match value.map(|value| (value, soft_wrap(value))) {
+                Some((_, Some(head))) => {
+                    wrapped = Some((name, head.to_string()));
+                }
+                Some((value, None)) => {
+                    let _ = front_matter
+                        .insert(name, Value::String(value.to_string()));
+                }
+                None => {
+                    let _ = front_matter.insert(name, Value::Null);
+                }
+            }
+            previous = Previous::Other;
+        } else if previous == Previous::Title {
+            author_line(&mut front_matter, line);
+            previous = Previous::Author;
+        } else if previous == Previous::Author {
+            revision_line(&mut front_matter, line);
+            previous = Previous::Other;
+        } else {
+            return Err(error(
+                raw,
+                offset,
+                format!(
+                    "`{line}` is not an AsciiDoc attribute entry such as `:name: value`"
+                ),
+            ));
+        }
+    }
+
+    if let Some((name, value)) = wrapped {
+        let _ = front_matter.insert(name, Value::String(value));
+    }
+    Ok(front_matter)
+}
+
+/// Returns the start of a value that ends with the ` \` soft wrap.
+fn soft_wrap(value: &str) -> Option<&str> {
+    let head = value.strip_suffix('\\')?;
+    (head.is_empty() || head.ends_with(' ')).then(|| head.trim_end())
+}
+
+/// Reads an author line, `Name <email>; Name <email>`.
+fn author_line(front_matter: &mut Frontmatter, line: &str) {
+    let authors: Vec<(&str, Option<&str>)> = line
+        .split(';')
+        .map(str::trim)
+        .filter(|author| !author.is_empty())
+        .map(|author| match author.split_once('<') {
+            Some((name, email)) => {
+                (name.trim(), Some(email.trim_end_matches('>').trim()))
+            }
+            None => (author, None),
+        })
+        .collect();
+
+    if let Some(&(name, email)) = authors.first() {
+        let _ = front_matter.insert("author".to_string(), name.into());
+        if let Some(email) = email {
+            let _ =
+                front_matter.insert("email".to_string(), email.into());
+        }
+    }
+    if authors.len() > 1 {
+        let names: Vec<&str> =
+            authors.iter().map(|&(name, _)| name).collect();
+        let _ = front_matter
+            .insert("authors".to_string(), names.join(", ").into());
+    }
+}
+
+/// Reads a revision line, `v1.0, 2025-09-09: remark`. Without a comma
+/// the line holds the number if it starts with `v` and a digit, and
+/// the date otherwise.
+fn revision_line(front_matter: &mut Frontmatter, line: &str) {
+    let (revision, remark) = match line.split_once(':') {
+        Some((revision, remark)) => {
+            (revision.trim(), Some(remark.trim()))
+        }
+        None => (line, None),
+    };
+    let is_number = |s: &str| {
+        s.strip_prefix(['v', 'V']).is_some_and(|rest| {
+            rest.starts_with(|c: char| c.is_ascii_digit())
+        })
+    };
+    let (number, date) = match revision.split_once(',') {
+        Some((number, date)) => {
+            (Some(number.trim()), Some(date.trim()))
+        }
+        None if is_number(revision) => (Some(revision), None),
+        None => (None, Some(revision)),
+    };
+
+    let number =
+        number.map(|n| n.strip_prefix(['v', 'V']).unwrap_or(n));
+    for (name, value) in [
+        ("revnumber", number),
+        ("revdate", date),
+        ("revremark", remark),
+    ] {
+        if let Some(value) = value.filter(|value| !value.is_empty()) {
+            let _ = front_matter.insert(name.to_string(), value.into());
+        }
+    }
+}
+
+/// Serialises a `Frontmatter` object into an AsciiDoc document header.
+///
+/// A string `title` becomes the `= Title` line and every other key an
+/// attribute entry. Arrays are joined with commas, and `null` values
+/// unset the attribute with `:name!:`.
+///
+/// # Errors
+///
+/// Returns `Error::ConversionError` for a key that is not an attribute
+/// name, or a value that is not a scalar or an array of scalars.
+pub(crate) fn to_asciidoc(
+    front_matter: &Frontmatter,
+) -> Result<String, Error> {
+    let mut out = String::new();
+    let title = match front_matter.get("title") {
+        Some(Value::String(title))
+            if !title.is_empty()
+                && title.trim() == title
+                && !title.contains(['\r', '\n']) =>
+        {
+            push_line(&mut out, "=", title);
+            true
+        }
+        _ => false,
+    };
+
+    for (key, value) in front_matter.iter() {
+        if title && key == "title" {
+            continue;
+        }
+        if !is_attribute_name(key) {
+            return Err(Error::ConversionError(format!(
+                "`{key}` cannot be written as an AsciiDoc attribute"
+            )));
+        }
+        match value {
+            Value::Null => {
+                push_line(&mut out, &format!(":{key}!:"), "")
+            }
+            Value::Array(items) => {
+                let items = items
+                    .iter()
+                    .map(|item| text(key, item, Format::AsciiDoc))
+                    .collect::<Result<Vec<_>, _>>()?;
+                push_line(
+                    &mut out,
+                    &format!(":{key}:"),
+                    &items.join(", "),
+                );
+            }
+            value => push_line(
+                &mut out,
+                &format!(":{key}:"),
+                &text(key, value, Format::AsciiDoc)?,
+            ),
+        }
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Tests for Org keyword lines.
+    mod org_tests {
+        use super::*;
+
+        #[test]
+        fn test_parse_keywords() {
+            let raw = "#+TITLE: My Post\n# a comment\n#+Date: 2025-09-09\n#+filetags:\n#+TAGS: rust\n#+TAGS: org\n";
+            let fm = parse_org(raw).unwrap();
+            assert_eq!(
+                fm.0.keys().collect::<Vec<_>>(),
+                ["title", "date", "filetags", "tags"]
+            );
+            assert_eq!(fm.get("title"), Some(&Value::from("My Post")));
+            assert_eq!(
+                fm.get("date"),
+                Some(&Value::from("2025-09-09"))
+            );
+            assert_eq!(fm.get("filetags"), Some(&Value::from("")));
+            assert_eq!(
+                fm.get("tags"),
+                Some(&Value::Array(vec!["rust".into(), "org".into()]))
+            );
+        }
+
+        #[test]
+        fn test_parse_error_reports_position() {
+            let err = parse_org("#+TITLE: A\n#+BEGIN_SRC rust\n")
+                .unwrap_err();
+            assert!(matches!(err.inner(), Error::ParseError(_)));
+            assert_eq!(err.context().and_then(|c| c.line), Some(2));
+        }
+
+        #[test]
+        fn test_round_trip() {
+            let raw = "#+TITLE: My Post\n#+DRAFT: false\n#+TAGS: rust\n#+TAGS: org\n#+EMPTY:\n";
+            let fm = parse_org(raw).unwrap();
+            assert_eq!(to_org(&fm).unwrap(), raw);
+        }
+
+        #[test]
+        fn test_serialise_rejects_nesting() {
+            let mut fm = Frontmatter::new();
+            let _ = fm.insert(
+                "meta".to_string(),
+                Value::Object(Box::new(Frontmatter::new())),
+            );
+            assert!(matches!(
+                to_org(&fm),
+                Err(Error::ConversionError(_))
+            ));
+
+            let mut fm = Frontmatter::new();
+            let _ = fm.insert("two words".to_string(), "x".into());
+            assert!(matches!(
+                to_org(&fm),
+                Err(Error::ConversionError(_))
+            ));
+        }
+    }
+
+    /// Tests for AsciiDoc document headers.
+    mod asciidoc_tests {
+        use super::*;
+
+        #[test]
+        fn test_parse_header() {
+            let raw = "= Document Title\nKismet R. Lee <kismet@example.org>\nv2.9, October 31, 2021: Fall incarnation\n// a comment\n:description: The document's \\\n  description.\n:sectanchors:\n:toc!:\n";
+            let fm = parse_asciidoc(raw).unwrap();
+            assert_eq!(
+                fm.0.keys().collect::<Vec<_>>(),
+                [
+                    "title",
+                    "author",
+                    "email",
+                    "revnumber",
+                    "revdate",
+                    "revremark",
+                    "description",
+                    "sectanchors",
+                    "toc"
+                ]
+            );
+            assert_eq!(
+                fm.get("author"),
+                Some(&Value::from("Kismet R. Lee"))
+            );
+            assert_eq!(
+                fm.get("email"),
+                Some(&Value::from("kismet@example.org"))
+            );
+            assert_eq!(fm.get("revnumber"), Some(&Value::from("2.9")));
+            assert_eq!(
+                fm.get("revdate"),
+                Some(&Value::from("October 31, 2021"))
+            );
+            assert_eq!(
+                fm.get("description"),
+                Some(&Value::from("The document's description."))
+            );
+            assert_eq!(fm.get("sectanchors"), Some(&Value::from("")));
+            assert_eq!(fm.get("toc"), Some(&Value::Null));
+        }
+
+        #[test]
+        fn test_parse_several_authors() {
+            let fm = parse_asciidoc(
+                "= T\nA One; B Two <b@example.org>\n2025-09-09\n",
+            )
+            .unwrap();
+            assert_eq!(fm.get("author"), Some(&Value::from("A One")));
+            assert_eq!(fm.get("email"), None);
+            assert_eq!(
+                fm.get("authors"),
+                Some(&Value::from("A One, B Two"))
+            );
+            assert_eq!(
+                fm.get("revdate"),
+                Some(&Value::from("2025-09-09"))
+            );
+        }
+
+        #[test]
+        fn test_parse_error_reports_position() {
+            let err =
+                parse_asciidoc(":a: 1\nplain text\n").unwrap_err();
+            assert!(matches!(err.inner(), Error::ParseError(_)));
+            assert_eq!(err.context().and_then(|c| c.line), Some(2));
+        }
+
+        #[test]
+        fn test_round_trip() {
+            let raw = "= My Post\n:author: Jane\n:keywords: rust, org\n:toc!:\n";
+            let fm = parse_asciidoc(raw).unwrap();
+            assert_eq!(to_asciidoc(&fm).unwrap(), raw);
+        }
+
+        #[test]
+        fn test_serialise_values() {
+            let mut fm = Frontmatter::new();
+            let _ = fm.insert("count".to_string(), Value::Integer(3));
+            let _ = fm.insert(
+                "tags".to_string(),
+                Value::Array(vec!["a".into(), "b".into()]),
+            );
+            let _ = fm.insert("title".to_string(), "Late title".into());
+            assert_eq!(
+                to_asciidoc(&fm).unwrap(),
+                "= Late title\n:count: 3\n:tags: a, b\n"
+            );
+
+            let _ = fm.insert("body".to_string(), "two\nlines".into());
+            assert!(matches!(
+                to_asciidoc(&fm),
+                Err(Error::ConversionError(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_opening() {
+        assert_eq!(opening("#+TITLE: A"), Some(Format::Org));
+        assert_eq!(opening("= A"), Some(Format::AsciiDoc));
+        assert_eq!(opening(":author: B"), Some(Format::AsciiDoc));
+        for line in
+            ["#+BEGIN_SRC rust", "# Heading", "==", "::", "title: A"]
+        {
+            assert_eq!(opening(line), None, "{line}");
+        }
+    }
+}
@@ -0,0 +1,681 @@
+//! # JSON Schema Module
+//!
+//! This module converts between front matter schemas and JSON Schema, so
+//! that schemas shared with editors and content management systems can be
+//! used to validate front matter, and schemas inferred from existing
+//! content can be handed back to them.
+//!
+//! The supported subset of draft 2020-12 is:
+//!
+//! - `type` (a single type, or a list of one type and `null`, which
+//!   makes the field nullable),
+//! - `properties` and `required`,
+//! - `enum` and `const`,
+//! - `pattern`, `minLength` and `maxLength`,
+//! - `minimum` and `maximum`,
+//! - `items`, `minItems` and `maxItems`,
+//! - `format` of `date-time`, `date` or `time` on strings.
+//!
+//! Other keywords are ignored on import, as JSON Schema ignores unknown
+//! keywords.
+//!
+//! ## Usage Example
+//!
+//! ```rust
+//! use frontmatter_gen::{extract, json_schema};
+//! use serde_json::json;
+//!
+//! let schema = json_schema::import(&json!({
+//!     "type": "object",
+//!     "properties": {
+//!         "title": { "type": "string", "maxLength": 8 },
+//!         "draft": { "type": "boolean" }
+//!     },
+//!     "required": ["title"]
+//! }))?;
+//!
+//! let (frontmatter, _) = extract("---\ntitle: A very long title\n---\n")?;
+//! let violations = schema.validate(&frontmatter);
+//! assert_eq!(violations.len(), 1);
+//! assert_eq!(violations[0].path, "title");
+//! # Ok::<(), frontmatter_gen::Error>(())
+//! ```
+
+use indexmap::IndexMap;
+use serde_json::{Map, Value as JsonValue};
+use std::{path::Path, sync::Arc};
+
+use crate::{
+    error::Error,
+    parser::json_to_value,
+    schema::{FieldSchema, FieldType, Pattern, Schema},
+    types::{Frontmatter, Plain},
+};
+
+/// The dialect written to the `$schema` keyword on export.
+pub const DRAFT_2020_12: &str =
+    "https://json-schema.org/draft/2020-12/schema";
+
+/// Converts a JSON Schema into a front matter [`Schema`].
+///
+/// The root must describe an object; its `properties` become the fields
+/// of the schema.
+///
+/// # Errors
+///
+/// Returns `Error::ConversionError` if a supported keyword has a value of
+/// the wrong shape, a `type` is not recognised, or a `pattern` is not a
+/// valid regular expression.
+pub fn import(json_schema: &JsonValue) -> Result<Schema, Error> {
+    let root = json_schema
+        .as_object()
+        .ok_or_else(|| invalid("the root schema must be an object"))?;
+    Ok(Schema {
+        fields: import_properties(root)?,
+    })
+}
+
+/// Reads a JSON Schema file and converts it into a front matter
+/// [`Schema`].
+///
+/// # Errors
+///
+/// Returns an error if the file cannot be read, is not valid JSON or
+/// cannot be imported.
+pub fn from_file(path: impl AsRef<Path>) -> Result<Schema, Error> {
+    let content = std::fs::read_to_string(path)?;
+    let json_schema: JsonValue = serde_json::from_str(&content)
+        .map_err(|e| Error::JsonParseError(Arc::new(e)))?;
+    import(&json_schema)
+}
+
+/// Converts a front matter [`Schema`] into a draft 2020-12 JSON Schema.
+///
+/// # Examples
+///
+/// ```rust
+/// use frontmatter_gen::{json_schema, parser, schema::Schema, Format};
+/// use serde_json::json;
+///
+/// let docs = [parser::parse("title: Hello\ntags: [rust]", Format::Yaml)?];
+/// let exported = json_schema::export(&Schema::infer(&docs));
+///
+/// assert_eq!(exported["properties"]["tags"]["items"], json!({ "type": "string" }));
+/// assert_eq!(exported["required"], json!(["title", "tags"]));
+/// # Ok::<(), frontmatter_gen::Error>(())
+/// ```
+#[must_use]
+pub fn export(schema: &Schema) -> JsonValue {
+    let mut root = Map::new();
+    let _ = root.insert(
+        "$schema".to_string(),
+        DRAFT_2020_12.to_string().into(),
+    );
+    root.extend(export_properties(&schema.fields));
+    JsonValue::Object(root)
+}
+
+/// Infers a JSON Schema from a corpus of front matter documents.
+///
+/// This is [`Schema::infer`] followed by [`export`].
+#[must_use]
+pub fn infer(documents: &[Frontmatter]) -> JsonValue {
+    export(&Schema::infer(documents))
+}
+
+fn invalid(message: &str) -> Error {
+    Error::ConversionError(format!("Invalid JSON Schema: {message}"))
+}
+
+// Import
+// ------
+
+/// Imports the `properties` and `required` keywords of an object schema.
+fn import_properties(
+    object: &Map<String, JsonValue>,
+) -> Result<IndexMap<String, FieldSchema>, Error> {
+    let mut fields = IndexMap::new();
+
+    if let Some(properties) = object.get("properties") {
+        let properties = properties
+            .as_object()
+            .ok_or_else(|| invalid("`properties` must be an object"))?;
+        for (name, property) in properties {
+            let _ =
+                fields.insert(name.clone(), import_field(property)?);
+        }
+    }
+
+    if let Some(required) = object.get("required") {
+        let required = required
+            .as_array()
+            .ok_or_else(|| invalid("`required` must be an array"))?;
+        for name in required {
+            let name = name.as_str().ok_or_else(|| {
+                invalid("`required` must contain strings")
+            })?;
+            fields
+                .entry(name.to_string())
+                .or_insert_with(FieldSchema::default)
+                .required = true;
+        }
+    }
+
+    Ok(fields)
+}
+
+/// Imports the schema of a single property.
+fn import_field(json_schema: &JsonValue) -> Result<FieldSchema, Error> {
+    let object = match json_schema {
+        // `true` accepts any value.
+        JsonValue::Bool(true) => return Ok(FieldSchema::default()),
+        JsonValue::Object(object) => object,
+        _ => {
+            return Err(invalid("a property schema must be an object"))
+        }
+    };
+
+    let allowed = match (object.get("enum"), object.get("const")) {
+        (Some(values), _) => Some(
+            values
+                .as_array()
+                .ok_or_else(|| invalid("`enum` must be an array"))?
+                .iter()
+                .map(json_to_value)
+                .collect(),
+        ),
+        (None, Some(value)) => Some(vec![json_to_value(value)]),
+        (None, None) => None,
+    };
+
+    let pattern = object
+        .get("pattern")
+        .map(|pattern| {
+            let pattern = pattern
+                .as_str()
+                .ok_or_else(|| invalid("`pattern` must be a string"))?;
+            Pattern::new(pattern).map_err(|e| invalid(&e.to_string()))
+        })
+        .transpose()?;
+
+    let items = object
+        .get("items")
+        .map(import_field)
+        .transpose()?
+        .map(Box::new);
+
+    let fields = if object.contains_key("properties")
+        || object.contains_key("required")
+    {
+        Some(import_properties(object)?)
+    } else {
+        None
+    };
+
+    let (field_type, nullable) = import_type(object)?;
+    Ok(FieldSchema {
+        field_type,
+        nullable,
+        required: false,
+        allowed,
+        pattern,
+        min_length: length(object, "minLength")?
+            .or(length(object, "minItems")?),
+        max_length: length(object, "maxLength")?
+            .or(length(object, "maxItems")?),
+        minimum: number(object, "minimum")?,
+        maximum: number(object, "maximum")?,
+        items,
+        fields,
+    })
+}
+
+/// Imports the `type` keyword, using `format` to recognise datetimes,
+/// and whether it lists `null`.
+fn import_type(
+    object: &Map<String, JsonValue>,
+) -> Result<(Option<FieldType>, bool), Error> {
+    let names: Vec<&str> = match object.get("type") {
+        None => return Ok((None, false)),
+        Some(JsonValue::String(name)) => vec![name.as_str()],
+        Some(JsonValue::Array(names)) => names
+            .iter()
+            .map(|name| {
+                name.as_str().ok_or_else(|| {
+                    invalid("`type` must contain strings")
+                })
+            })
+            .collect::<Result<_, _>>()?,
+        Some(_) => {
+            return Err(invalid("`type` must be a string or an array"))
+        }
+    };
+
+    // `null` makes the field nullable. A union of other types cannot be
+    // expressed, and is rejected rather than checked as any type.
+    let nullable = names.contains(&"null");
+    let names: Vec<&str> =
+        names.into_iter().filter(|name| *name != "null").collect();
+    let name = match names.as_slice() {
+        [] => return Ok((None, false)),
+        [name] => name,
+        _ => {
+            return Err(invalid(&format!(
+                "unsupported union of types `{}`",
+                names.join("`, `")
+            )))
+        }
+    };
+
+    let is_datetime = matches!(
+        object.get("format").and_then(JsonValue::as_str),
+        Some("date-time" | "date" | "time")
+    );
+    let field_type = match *name {
+        "string" if is_datetime => FieldType::DateTime,
+        "string" => FieldType::String,
+        "integer" => FieldType::Integer,
+        "number" => FieldType::Number,
+        "boolean" => FieldType::Boolean,
+        "array" => FieldType::Array,
+        "object" => FieldType::Object,
+        other => {
+            return Err(invalid(&format!("unsupported type `{other}`")))
+        }
+    };
+    Ok((Some(field_type), nullable))
+}
+
+/// Reads a non-negative integer keyword.
+fn length(
+    object: &Map<String, JsonValue>,
+    keyword: &str,
+) -> Result<Option<usize>, Error> {
+    object
+        .get(keyword)
+        .map(|value| {
+            value
+                .as_u64()
+                .and_then(|n| usize::try_from(n).ok())
+                .ok_or_else(|| {
+                    invalid(&format!(
+                        "`{keyword}` must be a non-negative integer"
+                    ))
+                })
+        })
+        .transpose()
+}
+
+/// Reads a numeric keyword.
+fn number(
+    object: &Map<String, JsonValue>,
+    keyword: &str,
+) -> Result<Option<f64>, Error> {
+    object
+        .get(keyword)
+        .map(|value| {
+            value.as_f64().ok_or_else(|| {
+                invalid(&format!("`{keyword}` must be a number"))
+            })
+        })
+        .transpose()
+}
+
+// Export
+// ------
+
+/// Exports fields as the keywords of an object schema.
+fn export_properties(
+    fields: &IndexMap<String, FieldSchema>,
+) -> Map<String, JsonValue> {
+    let mut object = Map::new();
+    let _ = object.insert("type".to_string(), "object".into());
+    let _ = object.insert(
+        "properties".to_string(),
+        JsonValue::Object(
+            fields
+                .iter()
+                .map(|(name, field)| {
+                    (name.clone(), export_field(field))
+                })
+                .collect(),
+        ),
+    );
+
+    let required: Vec<JsonValue> = fields
+        .iter()
+        .filter(|(_, field)| field.required)
+        .map(|(name, _)| name.as_str().into())
+        .collect();
+    if !required.is_empty() {
+        let _ = object.insert("required".to_string(), required.into());
+    }
+    object
+}
+
+/// Exports the schema of a single field.
+fn export_field(field: &FieldSchema) -> JsonValue {
+    let mut object = match (&field.fields, field.field_type) {
+        (Some(fields), _) => export_properties(fields),
+        (None, Some(field_type)) => {
+            let mut object = Map::new();
+            let (name, format) = match field_type {
+                FieldType::String => ("string", None),
+                FieldType::Integer => ("integer", None),
+                FieldType::Number => ("number", None),
+                FieldType::Boolean => ("boolean", None),
+                FieldType::DateTime => ("string", Some("date-time")),
+                FieldType::Array => ("array", None),
+                FieldType::Object => ("object", None),
+            };
+            let _ = object.insert("type".to_string(), name.into());
+            if let Some(format) = format {
+                let _ =
+                    object.insert("format".to_string(), format.into());
+            }
+            object
+        }
+        (None, None) => Map::new(),
+    };
+    if field.nullable {
+        if let Some(name) = object.remove("type") {
+            let _ = object.insert(
+                "type".to_string(),
+                JsonValue::Array(vec![name, "null".into()]),
+            );
+        }
+    }
+
+    if let Some(allowed) = &field.allowed {
+        let values = allowed
+            .iter()
+            .map(|value| {
+                serde_json::to_value(Plain(value)).unwrap_or_default()
+            })
+            .collect();
+        let _ =
+            object.insert("enum".to_string(), JsonValue::Array(values));
+    }
+    if let Some(pattern) = &field.pattern {
+        let _ = object
+            .insert("pattern".to_string(), pattern.as_str().into());
+    }
+
+    // Length bounds apply to strings or arrays depending on the type;
+    // an untyped field gets both, as each only constrains its own type.
+    let bounds = match field.field_type {
+        Some(FieldType::Array) => &["Items"][..],
+        None => &["Length", "Items"][..],
+        Some(_) => &["Length"][..],
+    };
+    for suffix in bounds {
+        if let Some(min) = field.min_length {
+            let _ = object.insert(format!("min{suffix}"), min.into());
+        }
+        if let Some(max) = field.max_length {
+            let _ = object.insert(format!("max{suffix}"), max.into());
+        }
+    }
+
+    if let Some(minimum) = field.minimum {
+        let _ = object
+            .insert("minimum".to_string(), export_number(minimum));
+    }
+    if let Some(maximum) = field.maximum {
+        let _ = object
+            .insert("maximum".to_string(), export_number(maximum));
+    }
+    if let Some(items) = &field.items {
+        let _ = object.insert("items".to_string(), export_field(items));
+    }
+
+    JsonValue::Object(object)
+}
+
+/// Writes whole numbers as JSON integers.
+fn export_number(n: f64) -> JsonValue {
+    #[allow(clippy::cast_possible_truncation)]
+    let integer = n as i64;
+    #[allow(clippy::cast_precision_loss)]
+    if n.fract() == 0.0 && integer as f64 == n {
+        integer.into()
+    } else {
+        n.into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parser, schema::Rule, types::Format, Value};
+    use serde_json::json;
+
+    fn frontmatter(yaml: &str) -> Frontmatter {
+        parser::parse(yaml, Format::Yaml).unwrap()
+    }
+
+    mod import_tests {
+        use super::*;
+
+        #[test]
+        fn test_import_supported_keywords() {
+            let schema = import(&json!({
+                "$schema": DRAFT_2020_12,
+                "type": "object",
+                "properties": {
+                    "title": { "type": "string", "minLength": 1, "pattern": "^[A-Z]" },
+                    "status": { "enum": ["draft", "published"] },
+                    "weight": { "type": ["integer", "null"], "minimum": 0, "maximum": 10 },
+                    "date": { "type": "string", "format": "date" },
+                    "tags": { "type": "array", "maxItems": 2, "items": { "type": "string" } },
+                    "author": {
+                        "type": "object",
+                        "properties": { "name": { "type": "string" } },
+                        "required": ["name"]
+                    }
+                },
+                "required": ["title", "slug"]
+            }))
+            .unwrap();
+
+            let fields = &schema.fields;
+            assert!(fields["title"].required);
+            assert!(fields["slug"].required);
+            assert_eq!(fields["title"].min_length, Some(1));
+            assert_eq!(
+                fields["status"].allowed,
+                Some(vec![
+                    Value::String("draft".to_string()),
+                    Value::String("published".to_string()),
+                ])
+            );
+            assert_eq!(
+                fields["weight"].field_type,
+                Some(FieldType::Integer)
+            );
+            assert!(fields["weight"].nullable);
+            assert!(!fields["title"].nullable);
+            assert_eq!(fields["weight"].maximum, Some(10.0));
+            assert_eq!(
+                fields["date"].field_type,
+                Some(FieldType::DateTime)
+            );
+            assert_eq!(fields["tags"].max_length, Some(2));
+            assert!(
+                fields["author"].fields.as_ref().unwrap()["name"]
+                    .required
+            );
+        }
+
+        #[test]
+        fn test_validate_with_imported_schema() {
+            let schema = import(&json!({
+                "properties": {
+                    "title": { "type": "string", "pattern": "^[A-Z]" },
+                    "date": { "type": "string", "format": "date" },
+                    "tags": { "items": { "type": "string" } }
+                },
+                "required": ["title", "date"]
+            }))
+            .unwrap();
+
+            let fm = frontmatter("title: lower\ntags: [a, 1]\n");
+            let violations: Vec<(String, Rule)> = schema
+                .validate(&fm)
+                .into_iter()
+                .map(|v| (v.path, v.rule))
+                .collect();
+            assert_eq!(
+                violations,
+                vec![
+                    ("title".to_string(), Rule::Pattern),
+                    ("date".to_string(), Rule::Required),
+                    ("tags[1]".to_string(), Rule::Type),
+                ]
+            );
+
+            let fm = frontmatter("title: Upper\ndate: 2024-01-01\n");
+            assert!(schema.validate(&fm).is_empty());
+        }
+
+        #[test]
+        fn test_nullable_types_accept_null() {
+            let schema = import(&json!({
+                "properties": {
+                    "sub": { "type": ["string", "null"], "minLength": 2 }
+                }
+            }))
+            .unwrap();
+
+            assert!(schema
+                .validate(&frontmatter("sub: ~\n"))
+                .is_empty());
+            assert!(schema
+                .validate(&frontmatter("sub: ab\n"))
+                .is_empty());
+            let rules: Vec<Rule> = ["sub: 1\n", "sub: a\n"]
+                .into_iter()
+                .flat_map(|yaml| schema.validate(&frontmatter(yaml)))
+                .map(|v| v.rule)
+                .collect();
+            assert_eq!(rules, [Rule::Type, Rule::Length]);
+        }
+
+        #[test]
+        fn test_import_rejects_malformed_keywords() {
+            for bad in [
+                json!([]),
+                json!({ "properties": [] }),
+                json!({ "properties": { "a": { "type": "tuple" } } }),
+                json!({ "properties": {
+                    "a": { "type": ["string", "integer", "null"] }
+                } }),
+                json!({ "properties": { "a": { "minLength": -1 } } }),
+                json!({ "properties": { "a": { "pattern": "(" } } }),
+            ] {
+                assert!(
+                    matches!(
+                        import(&bad),
+                        Err(Error::ConversionError(_))
+                    ),
+                    "{bad} was accepted"
+                );
+            }
+        }
+    }
+
+    mod export_tests {
+        use super::*;
+
+        #[test]
+        fn test_infer_and_export() {
+            let docs = [
+                frontmatter(
+                    "title: One\nweight: 1\ndate: 2024-01-01\n",
+                ),
+                frontmatter("title: Two\nweight: 2.5\n"),
+            ];
+            assert_eq!(
+                infer(&docs),
+                json!({
+                    "$schema": DRAFT_2020_12,
+                    "type": "object",
+                    "properties": {
+                        "title": { "type": "string" },
+                        "weight": { "type": "number" },
+                        "date": { "type": "string" }
+                    },
+                    "required": ["title", "weight"]
+                })
+            );
+        }
+
+        #[test]
+        fn test_corpus_validates_against_its_inferred_schema() {
+            let docs = [
+                frontmatter("sub: a\ntags: [x]\n"),
+                frontmatter("sub: ~\ntags: [y, ~]\n"),
+            ];
+            let exported = infer(&docs);
+            assert_eq!(
+                exported["properties"]["sub"],
+                json!({ "type": ["string", "null"] })
+            );
+
+            let schema = import(&exported).unwrap();
+            for fm in &docs {
+                assert!(schema.validate(fm).is_empty(), "{fm:?}");
+            }
+        }
+
+        #[test]
+        fn test_export_round_trips_through_import() {
+            let schema = Schema::parse(
+                r#"
+[fields.title]
+type = "string"
+required = true
+max_length = 64
+
+[fields.published]
+type = "datetime"
+
+[fields.rating]
+type = "number"
+minimum = 0
+maximum = 4.5
+
+[fields.tags]
+type = "array"
+min_length = 1
+items = { type = "string", enum = ["rust", "web"] }
+
+[fields.author]
+type = "object"
+fields.name = { type = "string", required = true }
+"#,
+                Format::Toml,
+            )
+            .unwrap();
+
+            let exported = export(&schema);
+            assert_eq!(
+                exported["properties"]["title"]["maxLength"],
+                json!(64)
+            );
+            assert_eq!(
+                exported["properties"]["rating"]["minimum"],
+                json!(0)
+            );
+            assert_eq!(
+                exported["properties"]["tags"]["minItems"],
+                json!(1)
+            );
+            assert_eq!(
+                exported["properties"]["published"],
+                json!({ "type": "string", "format": "date-time" })
+            );
+            assert_eq!(import(&exported).unwrap(), schema);
+        }
+    }
+}
@@ -23,7 +23,7 @@
 //!
 //! ## Security Features
 //!
-//! - Input validation to prevent malicious content
+//! - A configurable security policy for front matter values
 //! - Size limits to prevent denial of service attacks
 //! - Safe string handling to prevent memory corruption
 //! - Secure path handling for file operations
@@ -62,46 +62,58 @@
 //! Use `frontmatter.get("key")` to access fields and pattern-match on
 //! `Error` variants for granular error handling.
 
-use std::num::NonZeroUsize;
+use crate::extractor::{find_block, Block};
 
 // Re-export core types and traits
 pub use crate::{
+    batch::extract_dir,
+    borrowed::{FrontmatterRef, ValueRef},
     config::Config,
+    editor::Editor,
     error::Error,
-    extractor::{detect_format, extract_raw_frontmatter},
+    extractor::{detect_format, extract_raw_frontmatter, Delimiters},
+    options::{ParseOptions, MAX_FRONTMATTER_SIZE, MAX_NESTING_DEPTH},
     parser::{parse, to_string},
+    scan::{extract_keys, extract_keys_with_options},
+    schema::Schema,
+    security::SecurityPolicy,
+    stream::{
+        extract_from_async_reader,
+        extract_from_async_reader_with_options, extract_from_reader,
+        extract_from_reader_with_options,
+    },
     types::{Format, Frontmatter, Value},
 };
 
 // Module declarations
+pub mod batch;
+pub mod borrowed;
 #[cfg(feature = "cli")]
 pub mod cli;
 pub mod config;
+mod de;
+pub mod diagnostics;
+pub mod diff;
+pub mod editor;
 pub mod engine;
 pub mod error;
 pub mod extractor;
+mod header;
+pub mod json_schema;
+pub mod merge;
+pub mod options;
 pub mod parser;
+pub mod path;
+pub mod scan;
+pub mod schema;
+pub mod security;
+mod ser;
 #[cfg(feature = "ssg")]
 pub mod ssg;
+pub mod stream;
 pub mod types;
 pub mod utils;
 
-macro_rules! non_zero_usize {
-    ($value:expr) => {
-        match NonZeroUsize::new($value) {
-            Some(val) => val,
-            None => panic!("Value must be non-zero"),
-        }
-    };
-}
-
-/// Maximum size allowed for frontmatter content (1MB)
-pub const MAX_FRONTMATTER_SIZE: NonZeroUsize =
-    non_zero_usize!(1024 * 1024);
-
-/// Maximum allowed nesting depth for structured data
-pub const MAX_NESTING_DEPTH: NonZeroUsize = non_zero_usize!(32);
-
 /// A specialized Result type for frontmatter operations.
 ///
 /// This type alias provides a consistent error type throughout the crate
@@ -114,42 +126,20 @@ macro_rules! non_zero_usize {
 /// Import all contents with `use frontmatter_gen::prelude::*`.
 pub mod prelude {
     pub use crate::{
-        extract, to_format, Config, Error, Format, Frontmatter, Result,
-        Value,
+        extract, extract_as, to_format, Config, Error, Format,
+        Frontmatter, Result, Value,
     };
 }
 
-/// Configuration options for parsing operations.
-///
-/// Provides fine-grained control over parsing behaviour and security limits.
-#[derive(Debug, Clone, Copy)]
-pub struct ParseOptions {
-    /// Maximum allowed content size
-    pub max_size: NonZeroUsize,
-    /// Maximum allowed nesting depth
-    pub max_depth: NonZeroUsize,
-    /// Whether to validate content structure
-    pub validate: bool,
-}
-
-impl Default for ParseOptions {
-    fn default() -> Self {
-        Self {
-            max_size: MAX_FRONTMATTER_SIZE,
-            max_depth: MAX_NESTING_DEPTH,
-            validate: true,
-        }
-    }
-}
-
 /// Validates input content against security constraints.
 ///
 /// # Security
 ///
-/// This function helps prevent denial of service attacks by:
-/// - Limiting the maximum size of frontmatter content
-/// - Skipping validation for fenced code blocks
-/// - Checking for malicious patterns
+/// This function helps prevent denial of service attacks by limiting
+/// the maximum size of the content. The text itself is not inspected:
+/// rules about front matter values, such as rejecting `../` in paths,
+/// belong to the [`SecurityPolicy`] in `options.security`, which is
+/// applied once the front matter is parsed.
 ///
 /// # Examples
 ///
@@ -165,37 +155,6 @@ pub fn validate_input(
     content: &str,
     options: &ParseOptions,
 ) -> Result<()> {
-    let mut inside_fenced_code = false;
-
-    for line in content.lines() {
-        if line.trim_start().starts_with("```")
-            || line.trim_start().starts_with("~~~")
-        {
-            inside_fenced_code = !inside_fenced_code;
-            continue; // Skip validation for this line
-        }
-
-        if inside_fenced_code {
-            continue; // Skip validation inside fenced code blocks
-        }
-
-        // Path traversal detection
-        if line.contains("../") || line.contains("..\\") {
-            log::warn!("Potential path traversal detected: {}", line);
-            return Err(Error::ValidationError(
-                "Content contains path traversal patterns".to_string(),
-            ));
-        }
-
-        // Null byte validation
-        if line.contains('\0') {
-            log::warn!("Null byte detected in content");
-            return Err(Error::ValidationError(
-                "Content contains null bytes".to_string(),
-            ));
-        }
-    }
-
     // Check size limit
     if content.len() > options.max_size.get() {
         log::warn!(
@@ -212,18 +171,24 @@ pub fn validate_input(
     Ok(())
 }
 
-/// Extracts and parses frontmatter from content with format auto-detection.
+/// Extracts and parses frontmatter from content.
 ///
 /// This function provides zero-copy extraction of frontmatter where possible,
-/// automatically detecting the format (YAML, TOML, or JSON) and parsing it
-/// into a structured representation.
+/// taking the format from the fence: `---` for YAML, `+++` for TOML, and
+/// a leading `{` for JSON. The contents are never inspected to guess the
+/// format, so a TOML value such as `"Part 1: Intro"` stays TOML.
 ///
 /// # Security
 ///
 /// This function includes several security measures:
 /// - Input validation and size limits
 /// - Safe string handling
-/// - Protection against malicious content
+/// - The rules of the security policy in `SECURITY_POLICY`, applied to
+///   the front matter values only
+///
+/// The limits are read from the environment by
+/// [`ParseOptions::from_env`]; use [`extract_with_options`] to set them
+/// in code.
 ///
 /// # Performance
 ///
@@ -258,15 +223,256 @@ pub fn validate_input(
 /// - Frontmatter format is invalid
 /// - Parsing fails
 pub fn extract(content: &str) -> Result<(Frontmatter, &str)> {
-    let options = ParseOptions::from_env();
-    validate_input(content, &options)?;
+    extract_with_options(content, &ParseOptions::from_env())
+}
+
+/// Extracts and parses front matter under the given options.
+///
+/// This is [`extract`] with the limits, validation and security policy
+/// of `options` in place of those read from the environment. They
+/// apply at every stage: the size check of [`validate_input`], the
+/// expansion and structure limits of the parser, and the security
+/// policy on the parsed values.
+///
+/// # Examples
+///
+/// ```rust
+/// use frontmatter_gen::{extract_with_options, Error, ParseOptions};
+///
+/// let options = ParseOptions::builder().max_depth(1).build()?;
+/// let content = "---\nauthor:\n  name: Jane\n---\nBody";
+///
+/// let result = extract_with_options(content, &options);
+/// assert!(matches!(result, Err(Error::NestingTooDeep { .. })));
+///
+/// let options = ParseOptions::builder().validate(false).build()?;
+/// let (frontmatter, body) = extract_with_options(content, &options)?;
+/// assert!(frontmatter.contains_key("author"));
+/// assert_eq!(body, "Body");
+/// # Ok::<(), frontmatter_gen::Error>(())
+/// ```
+///
+/// # Errors
+///
+/// Returns the same errors as [`extract`], against the limits of
+/// `options`.
+pub fn extract_with_options<'a>(
+    content: &'a str,
+    options: &ParseOptions,
+) -> Result<(Frontmatter, &'a str)> {
+    validate_input(content, options)?;
+
+    parse_block(content, find_block(content)?, options)
+}
+
+/// Extracts front matter as a view that borrows from `content`.
+///
+/// This is [`extract`] without the copies: keys and strings of the
+/// returned [`FrontmatterRef`] point into `content` wherever the format
+/// allows it. Convert the view with
+/// [`FrontmatterRef::into_frontmatter`] when an owned `Frontmatter` is
+/// needed.
+///
+/// # Examples
+///
+/// ```rust
+/// use frontmatter_gen::extract_ref;
+///
+/// let content = "{\"title\": \"My Post\"}\nContent here";
+/// let (frontmatter, body) = extract_ref(content)?;
+/// assert_eq!(frontmatter.get("title").and_then(|v| v.as_str()), Some("My Post"));
+/// assert_eq!(body, "Content here");
+/// # Ok::<(), frontmatter_gen::Error>(())
+/// ```
+///
+/// # Errors
+///
+/// Returns the same errors as [`extract`].
+pub fn extract_ref(
+    content: &str,
+) -> Result<(FrontmatterRef<'_>, &str)> {
+    extract_ref_with_options(content, &ParseOptions::from_env())
+}
+
+/// Extracts front matter as a view that borrows from `content`, under
+/// the given options.
+///
+/// This is [`extract_ref`] with the options of
+/// [`extract_with_options`].
+///
+/// # Examples
+///
+/// ```rust
+/// use frontmatter_gen::{extract_ref_with_options, Error, ParseOptions};
+///
+/// let options = ParseOptions::builder().max_size(8).build()?;
+/// let result = extract_ref_with_options("{\"title\": \"My Post\"}", &options);
+/// assert!(matches!(result, Err(Error::ContentTooLarge { .. })));
+/// # Ok::<(), frontmatter_gen::Error>(())
+/// ```
+///
+/// # Errors
+///
+/// Returns the same errors as [`extract`], against the limits of
+/// `options`.
+pub fn extract_ref_with_options<'a>(
+    content: &'a str,
+    options: &ParseOptions,
+) -> Result<(FrontmatterRef<'a>, &'a str)> {
+    validate_input(content, options)?;
+
+    let block = find_block(content)?;
+    let raw_frontmatter = &content[block.frontmatter];
+    let frontmatter = FrontmatterRef::parse_with_options(
+        raw_frontmatter,
+        block.format,
+        options,
+    )
+    .map_err(|e| e.relocate(raw_frontmatter, content))?;
+    if !options.security.is_empty() {
+        options.security.check(
+            raw_frontmatter,
+            block.format,
+            &frontmatter.clone().into_frontmatter(),
+        )?;
+    }
+
+    Ok((frontmatter, content[block.body].trim_start()))
+}
+
+/// Extracts and parses front matter enclosed by any of the given
+/// delimiters.
+///
+/// This is [`extract`] with a custom [`Delimiters`] registry. The front
+/// matter is parsed in the format its delimiter names.
+///
+/// # Examples
+///
+/// ```rust
+/// use frontmatter_gen::{extract_with_delimiters, extractor::Delimiters};
+///
+/// let content = "---toml\ntitle = \"Post: Part 1\"\n---\nBody";
+/// let (frontmatter, body) =
+///     extract_with_delimiters(content, &Delimiters::extended())?;
+/// assert_eq!(frontmatter.get("title").and_then(|v| v.as_str()), Some("Post: Part 1"));
+/// assert_eq!(body, "Body");
+/// # Ok::<(), frontmatter_gen::Error>(())
+/// ```
+///
+/// # Errors
+///
+/// Returns `Error` if the content fails validation, does not open with a
+/// registered delimiter, or cannot be parsed.
+pub fn extract_with_delimiters<'a>(
+    content: &'a str,
+    delimiters: &Delimiters,
+) -> Result<(Frontmatter, &'a str)> {
+    extract_with_delimiters_and_options(
+        content,
+        delimiters,
+        &ParseOptions::from_env(),
+    )
+}
+
+/// Extracts and parses front matter enclosed by any of the given
+/// delimiters, under the given options.
+///
+/// This is [`extract_with_delimiters`] with the options of
+/// [`extract_with_options`].
+///
+/// # Examples
+///
+/// ```rust
+/// use frontmatter_gen::{
+///     extract_with_delimiters_and_options, extractor::Delimiters, Error,
+///     ParseOptions,
+/// };
+///
+/// let options = ParseOptions::builder().max_keys(1).build()?;
+/// let content = "---toml\na = 1\nb = 2\n---\nBody";
+/// let result = extract_with_delimiters_and_options(
+///     content,
+///     &Delimiters::extended(),
+///     &options,
+/// );
+/// assert!(matches!(result, Err(Error::ContentTooLarge { .. })));
+/// # Ok::<(), frontmatter_gen::Error>(())
+/// ```
+///
+/// # Errors
+///
+/// Returns the same errors as [`extract_with_delimiters`], against the
+/// limits of `options`.
+pub fn extract_with_delimiters_and_options<'a>(
+    content: &'a str,
+    delimiters: &Delimiters,
+    options: &ParseOptions,
+) -> Result<(Frontmatter, &'a str)> {
+    validate_input(content, options)?;
 
-    let (raw_frontmatter, remaining_content) =
-        extract_raw_frontmatter(content)?;
-    let format = detect_format(raw_frontmatter)?;
-    let frontmatter = parse(raw_frontmatter, format)?;
+    parse_block(content, delimiters.find_block(content)?, options)
+}
+
+/// Parses the front matter `block` of `content` in the format of its
+/// fence, returning it with the body.
+///
+/// The limits of `options` apply while parsing, and its security policy
+/// to the parsed values.
+fn parse_block<'a>(
+    content: &'a str,
+    block: Block,
+    options: &ParseOptions,
+) -> Result<(Frontmatter, &'a str)> {
+    let raw_frontmatter = &content[block.frontmatter];
+    let frontmatter = parser::parse_with_options(
+        raw_frontmatter,
+        block.format,
+        Some(*options),
+    )
+    .map_err(|e| e.relocate(raw_frontmatter, content))?;
+    options.security.check(
+        raw_frontmatter,
+        block.format,
+        &frontmatter,
+    )?;
+
+    Ok((frontmatter, content[block.body].trim_start()))
+}
 
-    Ok((frontmatter, remaining_content))
+/// Extracts front matter from content and deserialises it into `T`.
+///
+/// This is [`extract`] followed by [`Frontmatter::deserialize_into`].
+///
+/// # Examples
+///
+/// ```rust
+/// use frontmatter_gen::extract_as;
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// struct Post {
+///     title: String,
+///     draft: bool,
+/// }
+///
+/// let (post, content) =
+///     extract_as::<Post>("---\ntitle: My Post\ndraft: true\n---\nContent here")?;
+/// assert_eq!(post.title, "My Post");
+/// assert!(post.draft);
+/// assert_eq!(content.trim(), "Content here");
+/// # Ok::<(), frontmatter_gen::Error>(())
+/// ```
+///
+/// # Errors
+///
+/// Returns `Error` if extraction fails, or `Error::DeserializeError`
+/// with the path of the offending key if the front matter does not
+/// match `T`.
+pub fn extract_as<T: serde::de::DeserializeOwned>(
+    content: &str,
+) -> Result<(T, &str)> {
+    let (frontmatter, remaining_content) = extract(content)?;
+    Ok((frontmatter.deserialize_into()?, remaining_content))
 }
 
 /// Converts frontmatter to a specific format.
@@ -309,46 +515,6 @@ pub fn to_format(
     to_string(frontmatter, format)
 }
 
-impl ParseOptions {
-    /// Load options from environment variables or use defaults.
-    ///
-    /// Reads the following environment variables:
-    /// - `MAX_FRONTMATTER_SIZE`: Maximum size for frontmatter content.
-    /// - `MAX_NESTING_DEPTH`: Maximum allowed nesting depth.
-    /// - `VALIDATE_STRUCTURE`: Enable or disable structure validation (default: `true`).
-    ///
-    /// # Example
-    ///
-    /// ```rust
-    /// use frontmatter_gen::ParseOptions;
-    /// std::env::set_var("MAX_FRONTMATTER_SIZE", "2048");
-    /// std::env::set_var("MAX_NESTING_DEPTH", "64");
-    ///
-    /// let options = ParseOptions::from_env();
-    /// assert_eq!(options.max_size.get(), 2048);
-    /// assert_eq!(options.max_depth.get(), 64);
-    /// assert!(options.validate);
-    /// ```
-    pub fn from_env() -> Self {
-        let max_size = std::env::var("MAX_FRONTMATTER_SIZE")
-            .ok()
-            .and_then(|val| val.parse::<usize>().ok())
-            .map_or(MAX_FRONTMATTER_SIZE, |size| non_zero_usize!(size));
-
-        let max_depth = std::env::var("MAX_NESTING_DEPTH")
-            .ok()
-            .and_then(|val| val.parse::<usize>().ok())
-            .map_or(MAX_NESTING_DEPTH, |depth| non_zero_usize!(depth));
-
-        Self {
-            max_size,
-            max_depth,
-            validate: std::env::var("VALIDATE_STRUCTURE")
-                .map_or(true, |val| val.eq_ignore_ascii_case("true")),
-        }
-    }
-}
-
 #[cfg(test)]
 mod extractor_tests {
     use crate::Error;
@@ -454,6 +620,87 @@ fn test_invalid_yaml_syntax() {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_extract_errors_use_document_positions() {
+        let content =
+            "---\ntitle: Post\ntags: [a, b]\nbad: : x\n---\nBody";
+        let err = extract(content).unwrap_err();
+        let context = err.context().unwrap();
+        assert_eq!((context.line, context.column), (Some(4), Some(6)));
+        assert!(matches!(err.inner(), Error::YamlParseError { .. }));
+
+        let content = "+++\ntitle = \"A\"\ndate = \n+++\n";
+        let err = extract(content).unwrap_err();
+        assert_eq!(err.context().unwrap().line, Some(3));
+    }
+
+    #[test]
+    fn test_extract_keeps_horizontal_rules_in_the_body() {
+        let content = "---\r\ntitle: Post\n---\nIntro\n\n---\n\nMore\n";
+        let (frontmatter, body) = extract(content).unwrap();
+        assert_eq!(frontmatter.len(), 1);
+        assert_eq!(body, "Intro\n\n---\n\nMore\n");
+    }
+
+    #[test]
+    fn test_extract_reads_toml_strings_with_colons() {
+        let content =
+            "+++\ntitle = \"Part 1: Intro\"\nurl = \"https://a.b\"\n+++\nBody";
+        let (frontmatter, body) = extract(content).unwrap();
+        assert_eq!(
+            frontmatter.get("title").and_then(Value::as_str),
+            Some("Part 1: Intro")
+        );
+        assert_eq!(body, "Body");
+
+        // A YAML block is not read as TOML because it holds `=`.
+        let content = "---\nformula: a = b\n---\n";
+        let (frontmatter, _) = extract(content).unwrap();
+        assert_eq!(
+            frontmatter.get("formula").and_then(Value::as_str),
+            Some("a = b")
+        );
+    }
+
+    #[test]
+    fn test_extract_with_delimiters_uses_the_fence_format() {
+        let delimiters = Delimiters::extended();
+
+        // The block opens with `---` but the fence says it is TOML.
+        let content = "---toml\ntitle = \"Post\"\n---\nBody";
+        let (frontmatter, body) =
+            extract_with_delimiters(content, &delimiters).unwrap();
+        assert_eq!(
+            frontmatter.get("title").and_then(Value::as_str),
+            Some("Post")
+        );
+        assert_eq!(body, "Body");
+
+        let content = "\n;;;\n{\"title\": 1\n;;;\n";
+        assert!(extract_with_delimiters(content, &delimiters).is_err());
+
+        let content = ";;;\n{\n  \"title\": [1,\n}\n;;;\n";
+        let err =
+            extract_with_delimiters(content, &delimiters).unwrap_err();
+        assert_eq!(err.context().and_then(|c| c.line), Some(4));
+    }
+
+    #[test]
+    fn test_headers_are_only_read_when_enabled() {
+        let content = ":smile: Great news today\n\nBody text";
+        assert!(matches!(extract(content), Err(Error::InvalidFormat)));
+
+        let mut delimiters = Delimiters::default();
+        let (frontmatter, body) =
+            extract_with_delimiters(content, delimiters.headers(true))
+                .unwrap();
+        assert_eq!(
+            frontmatter.get("smile").and_then(Value::as_str),
+            Some("Great news today")
+        );
+        assert_eq!(body, "Body text");
+    }
+
     #[test]
     fn test_parse_invalid_toml_syntax() {
         let raw = "title = \"Unmatched quote";
@@ -710,28 +957,15 @@ fn test_skip_validation_in_fenced_code_blocks() {
     }
 
     #[test]
-    fn test_detect_path_traversal_outside_code_blocks() {
-        let options = ParseOptions::default();
-        let content = r#"
-        ---
-        title: Example
-        ---
-        ../malicious/path
-        "#;
-
-        let result = validate_input(content, &options);
-        assert!(result.is_err(), "Validation should detect path traversal outside fenced code blocks.");
-    }
-
-    #[test]
-    fn test_validate_input_null_bytes() {
+    fn test_body_text_is_not_inspected() {
         let options = ParseOptions::default();
-        let malicious_content = "title: Valid\0Post";
-        let result = validate_input(malicious_content, &options);
-        assert!(matches!(
-            result,
-            Err(Error::ValidationError(ref e)) if e == "Content contains null bytes"
-        ));
+        for content in [
+            "---\ntitle: Example\n---\nSee [the guide](../guide.md).",
+            "../malicious/path",
+            "title: Valid\0Post",
+        ] {
+            assert!(validate_input(content, &options).is_ok());
+        }
     }
 
     #[test]
@@ -743,53 +977,37 @@ fn test_validate_input_exceeds_max_size() {
     }
 
     #[test]
-    fn test_validate_input_contains_null_bytes() {
-        let options = ParseOptions::default();
-        let malicious_content = "title: Valid\0Post";
-        let result = validate_input(malicious_content, &options);
-        assert!(matches!(
-            result,
-            Err(Error::ValidationError(ref e)) if e == "Content contains null bytes"
-        ));
+    fn test_extract_allows_relative_links_in_body() {
+        let content = "---\ntitle: Post\n---\nSee [x](../other.md).";
+        let (frontmatter, body) = extract(content).unwrap();
+        assert_eq!(
+            frontmatter.get("title").and_then(Value::as_str),
+            Some("Post")
+        );
+        assert_eq!(body, "See [x](../other.md).");
     }
 
     #[test]
-    fn test_validate_input_path_traversal() {
-        let options = ParseOptions::default();
-        let malicious_content = "../malicious/path";
-        let result = validate_input(malicious_content, &options);
+    fn test_security_policy_applies_to_front_matter_values() {
+        let content = "---\nimage: ../../etc/passwd\n---\n../body";
+        let block = find_block(content).unwrap();
+        let options = ParseOptions {
+            security: SecurityPolicy::strict(),
+            ..ParseOptions::default()
+        };
+
+        let err =
+            parse_block(content, block.clone(), &options).unwrap_err();
         assert!(matches!(
-            result,
-            Err(Error::ValidationError(ref e)) if e == "Content contains path traversal patterns"
+            err,
+            Error::PolicyViolation {
+                ref key,
+                rule: security::PolicyRule::PathTraversal,
+                ..
+            } if key == "image"
         ));
-    }
-}
-
-#[cfg(test)]
-mod parse_options_tests {
-    use super::*;
-
-    #[test]
-    fn test_parse_options_default() {
-        let options = ParseOptions::default();
-        assert_eq!(options.max_size.get(), 1024 * 1024);
-        assert_eq!(options.max_depth.get(), 32);
-        assert!(options.validate);
-    }
-
-    #[test]
-    fn test_parse_options_from_env() {
-        std::env::set_var("MAX_FRONTMATTER_SIZE", "524288");
-        std::env::set_var("MAX_NESTING_DEPTH", "20");
-        std::env::set_var("VALIDATE_STRUCTURE", "false");
-
-        let options = ParseOptions::from_env();
-        assert_eq!(options.max_size.get(), 524288);
-        assert_eq!(options.max_depth.get(), 20);
-        assert!(!options.validate);
 
-        std::env::remove_var("MAX_FRONTMATTER_SIZE");
-        std::env::remove_var("MAX_NESTING_DEPTH");
-        std::env::remove_var("VALIDATE_STRUCTURE");
+        assert!(parse_block(content, block, &ParseOptions::default())
+            .is_ok());
     }
 }
@@ -0,0 +1,551 @@
+// merge.rs
+
+//! # Merge Module
+//!
+//! This module deep-merges front matter, for sites that layer defaults:
+//! site-wide values, then section values, then the page's own front
+//! matter. Unlike [`Frontmatter::merge`], which replaces whole top-level
+//! entries, a deep merge recurses into objects so that a page can
+//! override `seo.og.image` without losing `seo.og.title`.
+//!
+//! [`MergeOptions`] decides what happens when both sides hold an array
+//! ([`ArrayStrategy`]) and when both sides hold different values that
+//! cannot be merged ([`ConflictStrategy`]). [`Merged`] applies layers in
+//! order and records which layer each final key came from.
+//!
+//! ## Usage Example
+//!
+//! ```rust
+//! use frontmatter_gen::{
+//!     merge::{ArrayStrategy, MergeOptions, Merged},
+//!     parser, Format,
+//! };
+//!
+//! let site = parser::parse("seo:\n  og:\n    title: Site\n    image: site.png\ntags: [web]\n", Format::Yaml)?;
+//! let page = parser::parse("seo:\n  og:\n    image: page.png\ntags: [rust]\n", Format::Yaml)?;
+//!
+//! let options = MergeOptions {
+//!     arrays: ArrayStrategy::Append,
+//!     ..MergeOptions::default()
+//! };
+//! let mut merged = Merged::new(options);
+//! merged.layer("site", site)?;
+//! merged.layer("page", page)?;
+//!
+//! let fm = merged.frontmatter();
+//! assert_eq!(fm.get_path("seo.og.title").and_then(|v| v.as_str()), Some("Site"));
+//! assert_eq!(fm.get_path("seo.og.image").and_then(|v| v.as_str()), Some("page.png"));
+//! assert_eq!(fm.get_path("tags[1]").and_then(|v| v.as_str()), Some("rust"));
+//! assert_eq!(merged.source("seo.og.title"), Some("site"));
+//! assert_eq!(merged.source("seo.og.image"), Some("page"));
+//! # Ok::<(), frontmatter_gen::Error>(())
+//! ```
+
+use indexmap::IndexMap;
+
+use crate::{
+    error::Error,
+    path::{self, Segment},
+    types::{Frontmatter, Value},
+};
+
+/// How two arrays at the same key are combined.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ArrayStrategy {
+    /// The right array replaces the left one.
+    #[default]
+    Replace,
+    /// The right array's items are added after the left one's.
+    Append,
+    /// The right array's items are added after the left one's, skipping
+    /// items that are already present.
+    Union,
+}
+
+/// What happens when both sides hold different values for a key and
+/// they cannot be merged.
+///
+/// Objects are always merged key by key and arrays follow the
+/// [`ArrayStrategy`], so a conflict is a differing pair of scalars or a
+/// pair of values of different types.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ConflictStrategy {
+    /// The left value is kept.
+    KeepLeft,
+    /// The right value replaces the left one.
+    #[default]
+    TakeRight,
+    /// The merge fails with `Error::MergeConflict`.
+    Fail,
+}
+
+/// Options for controlling a deep merge.
+///
+/// The default replaces arrays and lets the right side win conflicts,
+/// which matches [`Frontmatter::merge`] except that objects are merged.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MergeOptions {
+    /// How arrays at the same key are combined.
+    pub arrays: ArrayStrategy,
+    /// How conflicting values are resolved.
+    pub conflicts: ConflictStrategy,
+}
+
+/// Front matter merged from named layers, with the layer each key came
+/// from.
+///
+/// Keys are recorded by path, such as `seo.og.image`. A value that was
+/// set, replaced, appended to or unioned with is attributed to the last
+/// layer that changed it. An object is attributed to the layer that
+/// introduced it, while the keys inside it keep their own sources.
+///
+/// # Examples
+///
+/// ```rust
+/// use frontmatter_gen::{merge::{MergeOptions, Merged}, Frontmatter, Value};
+///
+/// let mut defaults = Frontmatter::new();
+/// defaults.insert("layout".to_string(), "post".into());
+/// let mut page = Frontmatter::new();
+/// page.insert("title".to_string(), "Hello".into());
+///
+/// let merged = frontmatter_gen::merge::merge_layers(
+///     [("defaults", defaults), ("page", page)],
+///     MergeOptions::default(),
+/// )?;
+/// assert_eq!(merged.source("layout"), Some("defaults"));
+/// assert_eq!(merged.source("title"), Some("page"));
+/// # Ok::<(), frontmatter_gen::Error>(())
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Merged {
+    frontmatter: Frontmatter,
+    sources: IndexMap<String, String>,
+    options: MergeOptions,
+}
+
+impl Merged {
+    /// Creates an empty result that merges layers with `options`.
+    #[must_use]
+    pub fn new(options: MergeOptions) -> Self {
+        Self {
+            frontmatter: Frontmatter::new(),
+            sources: IndexMap::new(),
+            options,
+        }
+    }
+
+    /// Merges a layer on top of the layers added so far.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name the layer's keys are attributed to.
+    /// * `layer` - The front matter of the layer.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::MergeConflict` with the path of the first
+    /// conflicting key if the options use [`ConflictStrategy::Fail`].
+    /// The result is left unchanged in that case.
+    pub fn layer(
+        &mut self,
+        name: &str,
+        layer: Frontmatter,
+    ) -> Result<(), Error> {
+        let mut frontmatter = self.frontmatter.clone();
+        let mut merger = Merger {
+            options: self.options,
+            layer: name,
+            sources: self.sources.clone(),
+            path: Vec::new(),
+        };
+        merger.merge_object(&mut frontmatter, layer)?;
+
+        self.frontmatter = frontmatter;
+        self.sources = merger.sources;
+        Ok(())
+    }
+
+    /// Returns the merged front matter.
+    #[must_use]
+    pub const fn frontmatter(&self) -> &Frontmatter {
+        &self.frontmatter
+    }
+
+    /// Consumes the result and returns the merged front matter.
+    #[must_use]
+    pub fn into_frontmatter(self) -> Frontmatter {
+        self.frontmatter
+    }
+
+    /// Returns the name of the layer the key at `path` came from.
+    ///
+    /// Returns `None` if the path is malformed or does not name a key
+    /// or array in the merged front matter. Items inside arrays are not
+    /// tracked individually.
+    #[must_use]
+    pub fn source(&self, path: &str) -> Option<&str> {
+        let path = path::join(&path::parse(path).ok()?);
+        self.sources.get(&path).map(String::as_str)
+    }
+
+    /// Returns every tracked path with the layer it came from, in the
+    /// order the paths were first merged.
+    pub fn sources(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.sources
+            .iter()
+            .map(|(path, layer)| (path.as_str(), layer.as_str()))
+    }
+}
+
+/// Merges named layers in order, later layers on top.
+///
+/// # Errors
+///
+/// Returns `Error::MergeConflict` if the options use
+/// [`ConflictStrategy::Fail`] and two layers conflict.
+pub fn merge_layers<I, S>(
+    layers: I,
+    options: MergeOptions,
+) -> Result<Merged, Error>
+where
+    I: IntoIterator<Item = (S, Frontmatter)>,
+    S: AsRef<str>,
+{
+    let mut merged = Merged::new(options);
+    for (name, layer) in layers {
+        merged.layer(name.as_ref(), layer)?;
+    }
+    Ok(merged)
+}
+
+impl Frontmatter {
+    /// Deep-merges another front matter into this one.
+    ///
+    /// Objects present on both sides are merged key by key; arrays and
+    /// conflicting values are handled as `options` specify. New keys are
+    /// appended in the order they appear in `other`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::MergeConflict` with the path of the first
+    /// conflicting key if `options` use [`ConflictStrategy::Fail`]. The
+    /// front matter is left unchanged in that case.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use frontmatter_gen::{merge::MergeOptions, parser, Format};
+    ///
+    /// let mut fm = parser::parse("author: { name: Jane, url: a.com }", Format::Yaml)?;
+    /// let page = parser::parse("author: { url: b.com }", Format::Yaml)?;
+    ///
+    /// fm.merge_deep(page, MergeOptions::default())?;
+    /// assert_eq!(fm.get_path("author.name").and_then(|v| v.as_str()), Some("Jane"));
+    /// assert_eq!(fm.get_path("author.url").and_then(|v| v.as_str()), Some("b.com"));
+    /// # Ok::<(), frontmatter_gen::Error>(())
+    /// ```
+    pub fn merge_deep(
+        &mut self,
+        other: Frontmatter,
+        options: MergeOptions,
+    ) -> Result<(), Error> {
+        let mut merged = Merged {
+            frontmatter: std::mem::take(self),
+            sources: IndexMap::new(),
+            options,
+        };
+        let result = merged.layer("", other);
+        *self = merged.into_frontmatter();
+        result
+    }
+}
+
+/// The state of a single layer being merged.
+struct Merger<'a> {
+    options: MergeOptions,
+    layer: &'a str,
+    sources: IndexMap<String, String>,
+    path: Vec<Segment>,
+}
+
+impl Merger<'_> {
+    /// Merges the keys of `right` into `left`.
+    fn merge_object(
+        &mut self,
+        left: &mut Frontmatter,
+        right: Frontmatter,
+    ) -> Result<(), Error> {
+        for (key, value) in right {
+            self.path.push(Segment::Key(key.clone()));
+            match left.get_mut(&key) {
+                Some(existing) => self.merge_value(existing, value)?,
+                None => {
+                    self.record(&value);
+                    let _ = left.insert(key, value);
+                }
+            }
+            let _ = self.path.pop();
+        }
+        Ok(())
+    }
+
+    /// Merges `right` into the value at the current path.
+    fn merge_value(
+        &mut self,
+        left: &mut Value,
+        right: Value,
+    ) -> Result<(), Error> {
+        match (left, right) {
+            (Value::Object(left), Value::Object(right)) => {
+                self.merge_object(left, *right)
+            }
+            (Value::Array(left), Value::Array(right)) => {
+                let changed = match self.options.arrays {
+                    ArrayStrategy::Replace => {
+                        let changed = *left != right;
+                        *left = right;
+                        changed
+                    }
+                    ArrayStrategy::Append => {
+                        let changed = !right.is_empty();
+                        left.extend(right);
+                        changed
+                    }
+                    ArrayStrategy::Union => {
+                        let before = left.len();
+                        for item in right {
+                            if !left.contains(&item) {
+                                left.push(item);
+                            }
+                        }
+                        left.len() != before
+                    }
+                };
+                if changed {
+                    self.record_path();
+                }
+                Ok(())
+            }
+            (left, right) if *left == right => Ok(()),
+            (left, right) => match self.options.conflicts {
+                ConflictStrategy::KeepLeft => Ok(()),
+                ConflictStrategy::TakeRight => {
+                    self.record(&right);
+                    *left = right;
+                    Ok(())
+                }
+                ConflictStrategy::Fail => {
+                    Err(Error::MergeConflict(path::join(&self.path)))
+                }
+            },
+        }
+    }
+
+    /// Attributes the value at the current path, and every key inside
+    /// it, to the current layer.
+    fn record(&mut self, value: &Value) {
+        self.record_path();
+        if let Value::Object(obj) = value {
+            for (key, value) in obj.iter() {
+                self.path.push(Segment::Key(key.clone()));
+                self.record(value);
+                let _ = self.path.pop();
+            }
+        }
+    }
+
+    /// Attributes the current path to the current layer, forgetting the
+    /// sources of any keys that were nested under it.
+    fn record_path(&mut self) {
+        let path = path::join(&self.path);
+        let nested = [format!("{path}."), format!("{path}[")];
+        self.sources.retain(|key, _| {
+            !nested.iter().any(|prefix| key.starts_with(prefix))
+        });
+        let _ = self.sources.insert(path, self.layer.to_string());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parser::parse, types::Format};
+
+    fn yaml(content: &str) -> Frontmatter {
+        parse(content, Format::Yaml).unwrap()
+    }
+
+    mod strategy_tests {
+        use super::*;
+
+        #[test]
+        fn test_objects_merge_recursively() {
+            let mut fm =
+                yaml("seo:\n  og:\n    title: A\n    image: a.png\n");
+            fm.merge_deep(
+                yaml("seo:\n  og:\n    image: b.png\n  robots: none\n"),
+                MergeOptions::default(),
+            )
+            .unwrap();
+            assert_eq!(
+                fm,
+                yaml("seo:\n  og:\n    title: A\n    image: b.png\n  robots: none\n")
+            );
+        }
+
+        #[test]
+        fn test_array_strategies() {
+            let left = yaml("tags: [a, b]\n");
+            let right = yaml("tags: [b, c]\n");
+            let merged = |arrays| {
+                let mut fm = left.clone();
+                fm.merge_deep(
+                    right.clone(),
+                    MergeOptions {
+                        arrays,
+                        ..MergeOptions::default()
+                    },
+                )
+                .unwrap();
+                fm
+            };
+
+            assert_eq!(
+                merged(ArrayStrategy::Replace),
+                yaml("tags: [b, c]\n")
+            );
+            assert_eq!(
+                merged(ArrayStrategy::Append),
+                yaml("tags: [a, b, b, c]\n")
+            );
+            assert_eq!(
+                merged(ArrayStrategy::Union),
+                yaml("tags: [a, b, c]\n")
+            );
+        }
+
+        #[test]
+        fn test_conflict_strategies() {
+            let left = yaml("title: A\nauthor: { name: Jane }\n");
+            let right = yaml("title: B\nauthor: Joe\ndraft: true\n");
+            let merged = |conflicts| {
+                let mut fm = left.clone();
+                fm.merge_deep(
+                    right.clone(),
+                    MergeOptions {
+                        conflicts,
+                        ..MergeOptions::default()
+                    },
+                )
+                .map(|()| fm)
+            };
+
+            assert_eq!(
+                merged(ConflictStrategy::KeepLeft).unwrap(),
+                yaml("title: A\nauthor: { name: Jane }\ndraft: true\n")
+            );
+            assert_eq!(
+                merged(ConflictStrategy::TakeRight).unwrap(),
+                yaml("title: B\nauthor: Joe\ndraft: true\n")
+            );
+            assert!(matches!(
+                merged(ConflictStrategy::Fail),
+                Err(Error::MergeConflict(path)) if path == "title"
+            ));
+        }
+
+        #[test]
+        fn test_fail_reports_nested_path_and_leaves_input() {
+            let mut fm = yaml("a:\n  b: 1\n  c: [x]\n");
+            let original = fm.clone();
+            let err = fm
+                .merge_deep(
+                    yaml("a:\n  d: 2\n  b: 2\n"),
+                    MergeOptions {
+                        conflicts: ConflictStrategy::Fail,
+                        ..MergeOptions::default()
+                    },
+                )
+                .unwrap_err();
+            assert_eq!(
+                err.to_string(),
+                "Conflicting values for front matter key: a.b"
+            );
+            assert_eq!(fm, original);
+
+            // Equal values do not conflict
+            fm.merge_deep(
+                yaml("a:\n  b: 1\n"),
+                MergeOptions {
+                    conflicts: ConflictStrategy::Fail,
+                    ..MergeOptions::default()
+                },
+            )
+            .unwrap();
+            assert_eq!(fm, original);
+        }
+    }
+
+    mod source_tests {
+        use super::*;
+
+        #[test]
+        fn test_sources_follow_the_winning_layer() {
+            let merged = merge_layers(
+                [
+                    ("site", yaml("layout: post\nseo: { og: { title: S, image: s.png } }\ntags: [a]\n")),
+                    ("section", yaml("seo: { og: { image: c.png } }\n")),
+                    ("page", yaml("title: Hello\ntags: [b]\nlayout: post\n")),
+                ],
+                MergeOptions {
+                    arrays: ArrayStrategy::Union,
+                    ..MergeOptions::default()
+                },
+            )
+            .unwrap();
+
+            assert_eq!(merged.source("layout"), Some("site"));
+            assert_eq!(merged.source("seo"), Some("site"));
+            assert_eq!(merged.source("seo.og.title"), Some("site"));
+            assert_eq!(merged.source("seo.og.image"), Some("section"));
+            assert_eq!(merged.source("tags"), Some("page"));
+            assert_eq!(merged.source("title"), Some("page"));
+            assert_eq!(merged.source("missing"), None);
+            assert_eq!(merged.source("seo..og"), None);
+            assert_eq!(
+                merged
+                    .sources()
+                    .map(|(path, _)| path)
+                    .collect::<Vec<_>>(),
+                [
+                    "layout",
+                    "seo",
+                    "seo.og",
+                    "seo.og.title",
+                    "seo.og.image",
+                    "tags",
+                    "title"
+                ]
+            );
+        }
+
+        #[test]
+        fn test_replaced_objects_drop_nested_sources() {
+            let mut merged = Merged::new(MergeOptions::default());
+            merged
+                .layer("site", yaml("author: { name: A }\n"))
+                .unwrap();
+            merged.layer("page", yaml("author: B\n")).unwrap();
+            assert_eq!(merged.source("author"), Some("page"));
+            assert_eq!(merged.source("author.name"), None);
+
+            merged.layer("late", yaml("author: { url: u }\n")).unwrap();
+            assert_eq!(merged.source("author.url"), Some("late"));
+            assert_eq!(
+                merged.into_frontmatter(),
+                yaml("author: { url: u }\n")
+            );
+        }
+    }
+}
@@ -0,0 +1,474 @@
+// options.rs
+
+//! # Parse Options Module
+//!
+//! This module provides [`ParseOptions`], the one set of limits and
+//! checks that every stage of extraction honours: the size limit of
+//! [`validate_input`](crate::validate_input), the structure and YAML
+//! expansion limits of the [`parser`](crate::parser), and the
+//! [`SecurityPolicy`] applied to the values.
+//!
+//! Options are plain data with public fields. [`ParseOptions::builder`]
+//! checks the values it is given and can overlay the environment
+//! variables read by [`ParseOptions::from_env`], reporting a variable
+//! that does not parse instead of ignoring it.
+//!
+//! ## Usage Example
+//!
+//! ```rust
+//! use frontmatter_gen::{extract_with_options, Error, ParseOptions};
+//!
+//! let options = ParseOptions::builder().max_keys(1).build()?;
+//!
+//! let (frontmatter, body) =
+//!     extract_with_options("---\ntitle: Hello\n---\nBody", &options)?;
+//! assert_eq!(frontmatter.get("title").and_then(|v| v.as_str()), Some("Hello"));
+//! assert_eq!(body, "Body");
+//!
+//! let result =
+//!     extract_with_options("---\ntitle: Hello\ndraft: true\n---\n", &options);
+//! assert!(matches!(result, Err(Error::ContentTooLarge { .. })));
+//! # Ok::<(), frontmatter_gen::Error>(())
+//! ```
+
+use std::num::NonZeroUsize;
+
+use crate::{error::Error, security::SecurityPolicy, Result};
+
+macro_rules! non_zero_usize {
+    ($value:expr) => {
+        match NonZeroUsize::new($value) {
+            Some(val) => val,
+            None => panic!("Value must be non-zero"),
+        }
+    };
+}
+
+/// Maximum size allowed for frontmatter content (1MB)
+pub const MAX_FRONTMATTER_SIZE: NonZeroUsize =
+    non_zero_usize!(1024 * 1024);
+
+/// Maximum allowed nesting depth for structured data
+pub const MAX_NESTING_DEPTH: NonZeroUsize = non_zero_usize!(32);
+
+/// Maximum number of top-level keys
+pub const MAX_KEYS: usize = 1000;
+
+/// Maximum number of YAML aliases
+pub const MAX_ALIASES: usize = 100;
+
+/// Maximum number of values once YAML aliases are expanded
+pub const MAX_NODES: usize = 25_000;
+
+/// Maximum total bytes of keys and strings once YAML aliases are
+/// expanded (1MB)
+pub const MAX_STRING_BYTES: usize = 1024 * 1024;
+
+/// Configuration options for parsing operations.
+///
+/// Provides fine-grained control over parsing behaviour and security
+/// limits. The same options drive [`extract_with_options`], the parser
+/// and the diagnostics, so a limit set here holds everywhere.
+///
+/// The expansion limits bound YAML whose aliases repeat an anchored
+/// value many times over, such as the "billion laughs" document. They
+/// are enforced while the YAML is read and converted, whether or not
+/// `validate` is set, and a breach is reported as
+/// [`Error::ExpansionLimitExceeded`].
+///
+/// [`extract_with_options`]: crate::extract_with_options
+#[derive(Debug, Clone, Copy)]
+pub struct ParseOptions {
+    /// Maximum allowed content size
+    pub max_size: NonZeroUsize,
+    /// Maximum allowed nesting depth
+    pub max_depth: NonZeroUsize,
+    /// Maximum allowed number of top-level keys
+    pub max_keys: usize,
+    /// Whether to validate the nesting depth and number of keys
+    pub validate: bool,
+    /// Maximum number of YAML aliases
+    pub max_aliases: usize,
+    /// Maximum number of values once YAML aliases are expanded
+    pub max_nodes: usize,
+    /// Maximum total bytes of keys and strings once YAML aliases are
+    /// expanded
+    pub max_string_bytes: usize,
+    /// Rules that front matter values must follow
+    pub security: SecurityPolicy,
+}
+
+impl Default for ParseOptions {
+    fn default() -> Self {
+        Self {
+            max_size: MAX_FRONTMATTER_SIZE,
+            max_depth: MAX_NESTING_DEPTH,
+            max_keys: MAX_KEYS,
+            validate: true,
+            max_aliases: MAX_ALIASES,
+            max_nodes: MAX_NODES,
+            max_string_bytes: MAX_STRING_BYTES,
+            security: SecurityPolicy::default(),
+        }
+    }
+}
+
+impl ParseOptions {
+    /// Creates a [`Builder`] starting from the default options.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use frontmatter_gen::{ParseOptions, SecurityPolicy};
+    ///
+    /// let options = ParseOptions::builder()
+    ///     .max_depth(8)
+    ///     .security(SecurityPolicy::strict())
+    ///     .build()?;
+    /// assert_eq!(options.max_depth.get(), 8);
+    /// # Ok::<(), frontmatter_gen::Error>(())
+    /// ```
+    #[must_use]
+    pub fn builder() -> Builder {
+        Builder::default()
+    }
+
+    /// Load options from environment variables or use defaults.
+    ///
+    /// Reads the variables listed on [`Builder::env`]. A variable that
+    /// does not parse is logged and ignored; use
+    /// `ParseOptions::builder().env().build()` to report it instead.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use frontmatter_gen::ParseOptions;
+    /// std::env::set_var("MAX_FRONTMATTER_SIZE", "2048");
+    /// std::env::set_var("MAX_NESTING_DEPTH", "64");
+    ///
+    /// let options = ParseOptions::from_env();
+    /// assert_eq!(options.max_size.get(), 2048);
+    /// assert_eq!(options.max_depth.get(), 64);
+    /// assert!(options.validate);
+    /// ```
+    #[must_use]
+    pub fn from_env() -> Self {
+        let builder = Self::builder().env();
+        for err in &builder.errors {
+            log::warn!("Ignoring environment variable: {err}");
+        }
+        builder.options
+    }
+}
+
+/// Builder for creating [`ParseOptions`] instances
+///
+/// Setters check their values, and [`Builder::build`] reports the first
+/// value that was rejected.
+#[derive(Debug, Clone, Default)]
+pub struct Builder {
+    options: ParseOptions,
+    errors: Vec<Error>,
+}
+
+impl Builder {
+    /// Sets the maximum content size, in bytes
+    #[must_use]
+    pub fn max_size(mut self, max_size: usize) -> Self {
+        self.set(positive("max_size", max_size), |o| &mut o.max_size);
+        self
+    }
+
+    /// Sets the maximum nesting depth
+    #[must_use]
+    pub fn max_depth(mut self, max_depth: usize) -> Self {
+        self.set(positive("max_depth", max_depth), |o| {
+            &mut o.max_depth
+        });
+        self
+    }
+
+    /// Sets the maximum number of top-level keys
+    #[must_use]
+    pub const fn max_keys(mut self, max_keys: usize) -> Self {
+        self.options.max_keys = max_keys;
+        self
+    }
+
+    /// Enables or disables validation of the nesting depth and number
+    /// of keys
+    #[must_use]
+    pub const fn validate(mut self, validate: bool) -> Self {
+        self.options.validate = validate;
+        self
+    }
+
+    /// Sets the maximum number of YAML aliases
+    #[must_use]
+    pub const fn max_aliases(mut self, max_aliases: usize) -> Self {
+        self.options.max_aliases = max_aliases;
+        self
+    }
+
+    /// Sets the maximum number of values once YAML aliases are expanded
+    #[must_use]
+    pub const fn max_nodes(mut self, max_nodes: usize) -> Self {
+        self.options.max_nodes = max_nodes;
+        self
+    }
+
+    /// Sets the maximum total bytes of keys and strings once YAML
+    /// aliases are expanded
+    #[must_use]
+    pub const fn max_string_bytes(
+        mut self,
+        max_string_bytes: usize,
+    ) -> Self {
+        self.options.max_string_bytes = max_string_bytes;
+        self
+    }
+
+    /// Sets the security policy applied to front matter values
+    #[must_use]
+    pub const fn security(mut self, security: SecurityPolicy) -> Self {
+        self.options.security = security;
+        self
+    }
+
+    /// Overlays the options set in environment variables.
+    ///
+    /// Reads the following environment variables:
+    /// - `MAX_FRONTMATTER_SIZE`: Maximum size for frontmatter content.
+    /// - `MAX_NESTING_DEPTH`: Maximum allowed nesting depth.
+    /// - `VALIDATE_STRUCTURE`: Enable or disable structure validation
+    ///   (`true` or `false`).
+    /// - `SECURITY_POLICY`: Rules of the [`SecurityPolicy`], such as
+    ///   `path-traversal,null-byte` or `strict`.
+    ///
+    /// Unset variables leave the options as they are, and setters
+    /// called afterwards override the environment.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use frontmatter_gen::{Error, ParseOptions};
+    ///
+    /// std::env::set_var("VALIDATE_STRUCTURE", "sometimes");
+    /// let result = ParseOptions::builder().env().build();
+    /// assert!(matches!(
+    ///     result,
+    ///     Err(Error::InvalidOption { ref name, .. }) if name == "VALIDATE_STRUCTURE"
+    /// ));
+    /// ```
+    #[must_use]
+    pub fn env(mut self) -> Self {
+        if let Some(value) = var("MAX_FRONTMATTER_SIZE") {
+            self.set(
+                parse_positive("MAX_FRONTMATTER_SIZE", &value),
+                |o| &mut o.max_size,
+            );
+        }
+        if let Some(value) = var("MAX_NESTING_DEPTH") {
+            self.set(
+                parse_positive("MAX_NESTING_DEPTH", &value),
+                |o| &mut o.max_depth,
+            );
+        }
+        if let Some(value) = var("VALIDATE_STRUCTURE") {
+            self.set(parse_bool("VALIDATE_STRUCTURE", &value), |o| {
+                &mut o.validate
+            });
+        }
+        if let Some(value) = var("SECURITY_POLICY") {
+            let policy = value.parse().map_err(|e| {
+                let reason = match e {
+                    Error::ValidationError(reason) => reason,
+                    e => e.to_string(),
+                };
+                invalid("SECURITY_POLICY", &value, reason)
+            });
+            self.set(policy, |o| &mut o.security);
+        }
+        self
+    }
+
+    /// Builds the [`ParseOptions`] instance
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InvalidOption` for the first value that was
+    /// rejected, naming the option or environment variable it was given
+    /// for.
+    pub fn build(self) -> Result<ParseOptions> {
+        match self.errors.into_iter().next() {
+            Some(err) => Err(err),
+            None => Ok(self.options),
+        }
+    }
+
+    /// Stores a checked value in the field `field` selects, or records
+    /// why it was rejected.
+    fn set<T>(
+        &mut self,
+        value: Result<T>,
+        field: fn(&mut ParseOptions) -> &mut T,
+    ) {
+        match value {
+            Ok(value) => *field(&mut self.options) = value,
+            Err(err) => self.errors.push(err),
+        }
+    }
+}
+
+/// Returns the value of an environment variable, if it is set.
+fn var(name: &str) -> Option<String> {
+    std::env::var(name).ok()
+}
+
+/// Returns an `Error::InvalidOption` for `value` given to `name`.
+fn invalid(
+    name: &str,
+    value: &str,
+    reason: impl Into<String>,
+) -> Error {
+    Error::InvalidOption {
+        name: name.to_string(),
+        value: value.to_string(),
+        reason: reason.into(),
+    }
+}
+
+/// Checks that a limit is greater than zero.
+fn positive(name: &str, value: usize) -> Result<NonZeroUsize> {
+    NonZeroUsize::new(value).ok_or_else(|| {
+        invalid(name, &value.to_string(), "must be greater than zero")
+    })
+}
+
+/// Parses a limit greater than zero.
+fn parse_positive(name: &str, value: &str) -> Result<NonZeroUsize> {
+    value.trim().parse().map_err(|_| {
+        invalid(name, value, "expected a positive integer")
+    })
+}
+
+/// Parses `true` or `false`, ignoring case.
+fn parse_bool(name: &str, value: &str) -> Result<bool> {
+    match value.trim().to_ascii_lowercase().as_str() {
+        "true" => Ok(true),
+        "false" => Ok(false),
+        _ => Err(invalid(name, value, "expected `true` or `false`")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod parse_options_tests {
+        use super::*;
+
+        #[test]
+        fn test_parse_options_default() {
+            let options = ParseOptions::default();
+            assert_eq!(options.max_size.get(), 1024 * 1024);
+            assert_eq!(options.max_depth.get(), 32);
+            assert_eq!(options.max_keys, MAX_KEYS);
+            assert!(options.validate);
+            assert_eq!(options.max_aliases, MAX_ALIASES);
+            assert_eq!(options.max_nodes, MAX_NODES);
+            assert_eq!(options.max_string_bytes, MAX_STRING_BYTES);
+        }
+
+        /// All environment variables are tested here, so that no other
+        /// test sees them half set.
+        #[test]
+        fn test_parse_options_from_env() {
+            std::env::set_var("MAX_FRONTMATTER_SIZE", "524288");
+            std::env::set_var("MAX_NESTING_DEPTH", "20");
+            std::env::set_var("VALIDATE_STRUCTURE", "false");
+
+            let options = ParseOptions::from_env();
+            assert_eq!(options.max_size.get(), 524288);
+            assert_eq!(options.max_depth.get(), 20);
+            assert!(!options.validate);
+
+            // Setters called after the overlay win
+            let options = ParseOptions::builder()
+                .env()
+                .max_depth(4)
+                .build()
+                .unwrap();
+            assert_eq!(options.max_size.get(), 524288);
+            assert_eq!(options.max_depth.get(), 4);
+
+            // Non-positive limits no longer panic: `from_env` ignores
+            // them and the builder reports them
+            std::env::set_var("MAX_NESTING_DEPTH", "0");
+            std::env::set_var("MAX_FRONTMATTER_SIZE", "-1");
+            let options = ParseOptions::from_env();
+            assert_eq!(options.max_size, MAX_FRONTMATTER_SIZE);
+            assert_eq!(options.max_depth, MAX_NESTING_DEPTH);
+            let err =
+                ParseOptions::builder().env().build().unwrap_err();
+            assert_eq!(
+                err.to_string(),
+                "Invalid value `-1` for `MAX_FRONTMATTER_SIZE`: expected a positive integer"
+            );
+
+            std::env::remove_var("MAX_FRONTMATTER_SIZE");
+            std::env::remove_var("MAX_NESTING_DEPTH");
+            std::env::remove_var("VALIDATE_STRUCTURE");
+        }
+    }
+
+    mod builder_tests {
+        use super::*;
+
+        #[test]
+        fn test_builder_sets_every_option() {
+            let options = ParseOptions::builder()
+                .max_size(64)
+                .max_depth(2)
+                .max_keys(3)
+                .validate(false)
+                .max_aliases(0)
+                .max_nodes(10)
+                .max_string_bytes(20)
+                .security(SecurityPolicy::strict())
+                .build()
+                .unwrap();
+            assert_eq!(options.max_size.get(), 64);
+            assert_eq!(options.max_depth.get(), 2);
+            assert_eq!(options.max_keys, 3);
+            assert!(!options.validate);
+            assert_eq!(options.max_aliases, 0);
+            assert_eq!(options.max_nodes, 10);
+            assert_eq!(options.max_string_bytes, 20);
+            assert!(!options.security.is_empty());
+        }
+
+        #[test]
+        fn test_builder_rejects_zero_limits() {
+            let err = ParseOptions::builder()
+                .max_depth(0)
+                .build()
+                .unwrap_err();
+            assert!(matches!(
+                err,
+                Error::InvalidOption { ref name, .. } if name == "max_depth"
+            ));
+            assert!(ParseOptions::builder()
+                .max_size(0)
+                .build()
+                .is_err());
+        }
+
+        #[test]
+        fn test_parse_bool() {
+            assert!(parse_bool("V", "TRUE").unwrap());
+            assert!(!parse_bool("V", " false ").unwrap());
+            assert!(parse_bool("V", "yes").is_err());
+        }
+    }
+}
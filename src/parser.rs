@@ -1,7 +1,8 @@
 //! # Front Matter Parser and Serialiser Module
 //!
 //! This module provides robust functionality for parsing and serialising front matter
-//! in various formats (YAML, TOML, and JSON). It focuses on:
+//! in various formats (YAML, TOML, JSON, JSON5 and RON, and Org and
+//! AsciiDoc headers). It focuses on:
 //!
 //! - Memory efficiency through pre-allocation and string optimisation
 //! - Type safety with comprehensive error handling
@@ -11,7 +12,7 @@
 //!
 //! ## Features
 //!
-//! - Multi-format support (YAML, TOML, JSON)
+//! - Multi-format support (YAML, TOML, JSON, JSON5, RON, Org, AsciiDoc)
 //! - Zero-copy parsing where possible
 //! - Efficient memory management
 //! - Comprehensive validation
@@ -33,43 +34,58 @@
 //! # }
 //! ```
 
-use noyalib::Value as YamlValue;
+use indexmap::IndexMap;
+use noyalib::{
+    BudgetBreach, ParserConfig, Tag, TaggedValue, Value as YamlValue,
+};
 use serde::Serialize;
 use serde_json::Value as JsonValue;
-use std::{collections::HashMap, sync::Arc};
+use std::{fmt, sync::Arc};
 use toml::Value as TomlValue;
 
-use crate::{error::Error, types::Frontmatter, Format, Value};
+pub use crate::options::ParseOptions;
+use crate::{
+    error::{Context, Error},
+    header,
+    types::{Datetime, Frontmatter, Plain},
+    Format, Value,
+};
 
 // Constants for optimisation and validation
 const SMALL_STRING_SIZE: usize = 24;
-const MAX_NESTING_DEPTH: usize = 32;
-const MAX_KEYS: usize = 1000;
-
-/// Options for controlling parsing behaviour.
-///
-/// Provides configuration for maximum allowed nesting depth, maximum number of keys,
-/// and whether to perform validation.
-#[derive(Debug, Clone, Copy)]
-pub struct ParseOptions {
-    /// Maximum allowed nesting depth.
-    pub max_depth: usize,
-    /// Maximum allowed number of keys.
-    pub max_keys: usize,
-    /// Whether to validate the structure.
-    pub validate: bool,
+const YAML_TIMESTAMP_TAG: &str = "!!timestamp";
+
+/// A limit on how far YAML aliases may expand the front matter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ExpansionLimit {
+    /// The number of aliases, [`ParseOptions::max_aliases`].
+    Aliases,
+    /// The number of values, [`ParseOptions::max_nodes`].
+    Nodes,
+    /// The bytes of keys and strings,
+    /// [`ParseOptions::max_string_bytes`].
+    StringBytes,
 }
 
-impl Default for ParseOptions {
-    fn default() -> Self {
-        Self {
-            max_depth: MAX_NESTING_DEPTH,
-            max_keys: MAX_KEYS,
-            validate: true,
+impl ExpansionLimit {
+    /// Returns what the limit counts, such as `aliases`.
+    #[must_use]
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            ExpansionLimit::Aliases => "aliases",
+            ExpansionLimit::Nodes => "values",
+            ExpansionLimit::StringBytes => "string bytes",
         }
     }
 }
 
+impl fmt::Display for ExpansionLimit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
 /// Optimises string storage based on length.
 ///
 /// For strings shorter than `SMALL_STRING_SIZE`, uses standard allocation.
@@ -125,7 +141,7 @@ pub fn parse_with_options(
     // Check for unsupported formats
     if format == Format::Unsupported {
         let err_msg = format!(
-            "Unsupported format: {:?}. Supported formats are YAML, TOML, and JSON.",
+            "Unsupported format: {:?}. Supported formats are YAML, TOML, JSON, JSON5, RON, Org, and AsciiDoc.",
             format
         );
         log::error!("{}", err_msg);
@@ -157,36 +173,42 @@ pub fn parse_with_options(
                 ));
             }
         }
+        // JSON5 and RON accept comments before the opening brace, and
+        // RON also accepts struct syntax, so their parsers decide.
+        Format::Json5 | Format::Ron => {}
+        // Headers are checked line by line as they are parsed.
+        Format::Org | Format::AsciiDoc => {}
         Format::Unsupported => unreachable!(), // We've already handled this case above
     };
 
     let front_matter = match format {
-        Format::Yaml => parse_yaml(trimmed_content).map_err(|e| {
-            log::error!("YAML parsing failed: {}", e);
-            e
-        })?,
-        Format::Toml => parse_toml(trimmed_content).map_err(|e| {
-            log::error!("TOML parsing failed: {}", e);
-            e
-        })?,
-        Format::Json => parse_json(trimmed_content).map_err(|e| {
-            log::error!("JSON parsing failed: {}", e);
-            e
-        })?,
+        Format::Yaml => parse_yaml(trimmed_content, &options),
+        Format::Toml => parse_toml(trimmed_content),
+        Format::Json => parse_json(trimmed_content),
+        Format::Json5 => parse_json5(trimmed_content),
+        Format::Ron => parse_ron(trimmed_content),
+        // Header errors carry their own positions, so the parsers see
+        // the block as it was given.
+        Format::Org => header::parse_org(raw_front_matter),
+        Format::AsciiDoc => header::parse_asciidoc(raw_front_matter),
         Format::Unsupported => unreachable!(),
-    };
+    }
+    .map_err(|e| {
+        log::error!("{} parsing failed: {}", format, e);
+        locate(e, raw_front_matter, trimmed_content)
+    })?;
 
     // Perform validation if specified in options
     if options.validate {
         log::debug!(
             "Validating front matter: maximum allowed nesting depth is {}, maximum allowed number of keys is {}",
-            options.max_depth,
+            options.max_depth.get(),
             options.max_keys
         );
 
         validate_frontmatter(
             &front_matter,
-            options.max_depth,
+            options.max_depth.get(),
             options.max_keys,
         )
         .map_err(|e| {
@@ -198,6 +220,66 @@ pub fn parse_with_options(
     Ok(front_matter)
 }
 
+/// Adds the position a parser reported to its error.
+///
+/// The parsers see `trimmed`, a slice of `raw`, so their offsets are
+/// moved to refer to `raw`.
+pub(crate) fn locate(err: Error, raw: &str, trimmed: &str) -> Error {
+    let offset = match &err {
+        Error::YamlParseError { source } => {
+            source.location().map(|location| location.index())
+        }
+        Error::TomlParseError(e) => e.span().map(|span| span.start),
+        // `serde_json` counts columns in bytes
+        Error::JsonParseError(e) if e.line() > 0 => {
+            let line_start = trimmed
+                .split_inclusive('\n')
+                .take(e.line() - 1)
+                .map(str::len)
+                .sum::<usize>();
+            Some(line_start + e.column().saturating_sub(1))
+        }
+        // `json5` counts lines and columns from zero, in characters
+        Error::Json5ParseError(e) => e
+            .position()
+            .map(|pos| char_offset(trimmed, pos.line, pos.column)),
+        // `ron` counts lines and columns from one, in characters, and
+        // may start the span at the blanks before the token
+        Error::RonParseError(e) => {
+            let offset = char_offset(
+                trimmed,
+                e.span.start.line.saturating_sub(1),
+                e.span.start.col.saturating_sub(1),
+            );
+            let rest = &trimmed[offset..];
+            Some(offset + rest.len() - rest.trim_start().len())
+        }
+        _ => None,
+    };
+    let start = trimmed.as_ptr() as usize - raw.as_ptr() as usize;
+
+    match offset {
+        Some(offset) => {
+            err.with_context(&Context::from_offset(raw, start + offset))
+        }
+        None => err,
+    }
+}
+
+/// Returns the byte offset of a zero-based line and character column.
+fn char_offset(text: &str, line: usize, column: usize) -> usize {
+    let line_start = text
+        .split_inclusive('\n')
+        .take(line)
+        .map(str::len)
+        .sum::<usize>();
+    let column = text[line_start..]
+        .char_indices()
+        .nth(column)
+        .map_or(text.len() - line_start, |(index, _)| index);
+    line_start + column
+}
+
 /// Convenience wrapper around `parse_with_options` using default options.
 ///
 /// # Arguments
@@ -246,6 +328,10 @@ pub fn to_string(
         Format::Yaml => to_yaml(front_matter),
         Format::Toml => to_toml(front_matter),
         Format::Json => to_json_optimised(front_matter),
+        Format::Json5 => to_json5(front_matter),
+        Format::Ron => to_ron(front_matter),
+        Format::Org => header::to_org(front_matter),
+        Format::AsciiDoc => header::to_asciidoc(front_matter),
         Format::Unsupported => Err(Error::ConversionError(
             "Unsupported format".to_string(),
         )),
@@ -260,20 +346,24 @@ pub fn to_string(
 /// # Arguments
 ///
 /// * `raw` - The raw YAML string.
+/// * `options` - The options holding the expansion limits.
 ///
 /// # Returns
 ///
 /// A `Result` containing the parsed `Frontmatter` or a `Error`.
-fn parse_yaml(raw: &str) -> Result<Frontmatter, Error> {
-    // Parse the YAML content into a noyalib::Value
-    let yaml_value: YamlValue = noyalib::from_str(raw)
-        .map_err(|e| Error::YamlParseError { source: e.into() })?;
+fn parse_yaml(
+    raw: &str,
+    options: &ParseOptions,
+) -> Result<Frontmatter, Error> {
+    let yaml_value: YamlValue =
+        noyalib::from_str_with_config(raw, &yaml_config(options))
+            .map_err(|e| yaml_error(e, options))?;
 
     // Prepare the front matter container
     let capacity =
         yaml_value.as_mapping().map_or(0, noyalib::Mapping::len);
     let mut front_matter =
-        Frontmatter(HashMap::with_capacity(capacity));
+        Frontmatter(IndexMap::with_capacity(capacity));
 
     // Convert the YAML mapping into the front matter structure.
     //
@@ -281,8 +371,11 @@ fn parse_yaml(raw: &str) -> Result<Frontmatter, Error> {
     // `Value`-keyed mapping), so we can iterate directly without an extra
     // type check.
     if let YamlValue::Mapping(mapping) = yaml_value {
+        let mut expansion = Expansion::new(options);
         for (key, value) in mapping {
-            let _ = front_matter.insert(key, yaml_to_value(&value));
+            expansion.count(key.len())?;
+            let value = yaml_to_value(&value, &mut expansion)?;
+            let _ = front_matter.insert(key, value);
         }
     } else {
         return Err(Error::ParseError(
@@ -293,36 +386,112 @@ fn parse_yaml(raw: &str) -> Result<Frontmatter, Error> {
     Ok(front_matter)
 }
 
-/// Converts a `noyalib::Value` into a `Value`.
-fn yaml_to_value(yaml: &YamlValue) -> Value {
-    match yaml {
+/// Returns the noyalib configuration enforcing the expansion limits.
+///
+/// noyalib expands aliases as it loads, so the limits are passed on to
+/// stop it before an expansion is materialised.
+pub(crate) fn yaml_config(options: &ParseOptions) -> ParserConfig {
+    ParserConfig::new()
+        .max_alias_expansions(options.max_aliases)
+        .max_nodes(options.max_nodes)
+        .max_total_scalar_bytes(options.max_string_bytes)
+}
+
+/// Converts a noyalib error, reporting a breached expansion limit as
+/// [`Error::ExpansionLimitExceeded`].
+pub(crate) fn yaml_error(
+    err: noyalib::Error,
+    options: &ParseOptions,
+) -> Error {
+    let (limit, max) = match &err {
+        noyalib::Error::RepetitionLimitExceeded => {
+            (ExpansionLimit::Aliases, options.max_aliases)
+        }
+        noyalib::Error::Budget(BudgetBreach::MaxNodes { .. }) => {
+            (ExpansionLimit::Nodes, options.max_nodes)
+        }
+        noyalib::Error::Budget(BudgetBreach::MaxTotalScalarBytes {
+            ..
+        }) => (ExpansionLimit::StringBytes, options.max_string_bytes),
+        _ => return Error::YamlParseError { source: err.into() },
+    };
+    Error::ExpansionLimitExceeded { limit, max }
+}
+
+/// Counts the values and string bytes YAML conversion produces.
+///
+/// noyalib counts the values written in the YAML, but a value reached
+/// through an alias is a copy, so the copies are counted here.
+struct Expansion {
+    nodes: usize,
+    string_bytes: usize,
+    max_nodes: usize,
+    max_string_bytes: usize,
+}
+
+impl Expansion {
+    const fn new(options: &ParseOptions) -> Self {
+        Self {
+            nodes: 0,
+            string_bytes: 0,
+            max_nodes: options.max_nodes,
+            max_string_bytes: options.max_string_bytes,
+        }
+    }
+
+    /// Counts one value holding `string_bytes` bytes of text.
+    fn count(&mut self, string_bytes: usize) -> Result<(), Error> {
+        self.nodes += 1;
+        if self.nodes > self.max_nodes {
+            return Err(Error::ExpansionLimitExceeded {
+                limit: ExpansionLimit::Nodes,
+                max: self.max_nodes,
+            });
+        }
+        self.string_bytes =
+            self.string_bytes.saturating_add(string_bytes);
+        if self.string_bytes > self.max_string_bytes {
+            return Err(Error::ExpansionLimitExceeded {
+                limit: ExpansionLimit::StringBytes,
+                max: self.max_string_bytes,
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Converts a `noyalib::Value` into a `Value`, counting what it
+/// produces against the expansion limits.
+fn yaml_to_value(
+    yaml: &YamlValue,
+    expansion: &mut Expansion,
+) -> Result<Value, Error> {
+    let string_bytes = match yaml {
+        YamlValue::String(s) => s.len(),
+        YamlValue::Tagged(tagged) => {
+            let tag: &str = tagged.tag().as_ref();
+            tag.len()
+        }
+        _ => 0,
+    };
+    expansion.count(string_bytes)?;
+
+    Ok(match yaml {
         YamlValue::Null => Value::Null,
         YamlValue::Bool(b) => Value::Boolean(*b),
-        YamlValue::Number(n) => {
-            // `noyalib::Number::as_f64` returns `f64` directly (not
-            // `Option<f64>` — integers are losslessly widened when
-            // possible). We still prefer the integer path when the value
-            // fits in f64's 52-bit mantissa, falling back to the float
-            // representation otherwise.
-            n.as_i64().map_or_else(
-                || Value::Number(n.as_f64()),
-                |i| {
-                    if i.abs() < (1_i64 << 52) {
-                        Value::Number(i as f64)
-                    } else {
-                        log::warn!(
-                            "Integer {} exceeds precision of f64. Defaulting to 0.0",
-                            i
-                        );
-                        Value::Number(0.0) // Fallback for large values outside f64 precision
-                    }
-                },
-            )
-        }
+        // Integers keep their full `i64` range; only non-integral
+        // scalars and integers outside it become floats. `noyalib`
+        // already reads the latter as floats, so they cannot be told
+        // apart from a float literal here.
+        YamlValue::Number(n) => n
+            .as_i64()
+            .map_or_else(|| Value::Float(n.as_f64()), Value::Integer),
         YamlValue::String(s) => Value::String(optimise_string(s)),
         YamlValue::Sequence(seq) => {
             let mut vec = Vec::with_capacity(seq.len());
-            vec.extend(seq.iter().map(yaml_to_value));
+            for item in seq {
+                vec.push(yaml_to_value(item, expansion)?);
+            }
             Value::Array(vec)
         }
         YamlValue::Mapping(map) => {
@@ -330,21 +499,78 @@ fn yaml_to_value(yaml: &YamlValue) -> Value {
             // to filter out non-string keys the way we did with the old
             // `serde_yml::Value`-keyed mapping.
             let mut result =
-                Frontmatter(HashMap::with_capacity(map.len()));
+                Frontmatter(IndexMap::with_capacity(map.len()));
             for (k, v) in map {
-                let _ = result
-                    .0
-                    .insert(optimise_string(k), yaml_to_value(v));
+                expansion.count(k.len())?;
+                let _ = result.0.insert(
+                    optimise_string(k),
+                    yaml_to_value(v, expansion)?,
+                );
             }
             Value::Object(Box::new(result))
         }
-        YamlValue::Tagged(tagged) => Value::Tagged(
-            optimise_string(tagged.tag().as_ref()),
-            Box::new(yaml_to_value(tagged.value())),
-        ),
+        YamlValue::Tagged(tagged) => {
+            let tag: &str = tagged.tag().as_ref();
+            // `!!timestamp` scalars that hold a valid RFC 3339 value are
+            // lifted into a native datetime; anything else stays tagged.
+            if tag == YAML_TIMESTAMP_TAG {
+                if let Some(dt) = tagged
+                    .value()
+                    .as_str()
+                    .and_then(|s| s.parse::<Datetime>().ok())
+                {
+                    return Ok(Value::DateTime(dt));
+                }
+            }
+            Value::Tagged(
+                optimise_string(tag),
+                Box::new(yaml_to_value(tagged.value(), expansion)?),
+            )
+        }
+    })
+}
+
+/// Converts a `Value` into a `noyalib::Value`.
+///
+/// Datetimes are emitted as `!!timestamp` scalars so that they parse
+/// back into `Value::DateTime`.
+pub(crate) fn value_to_yaml(value: &Value) -> YamlValue {
+    match value {
+        Value::Null => YamlValue::Null,
+        Value::String(s) => YamlValue::from(s.as_str()),
+        Value::Integer(i) => YamlValue::from(*i),
+        Value::Float(f) => YamlValue::from(*f),
+        Value::DateTime(dt) => {
+            YamlValue::Tagged(Box::new(TaggedValue::new(
+                Tag::new(YAML_TIMESTAMP_TAG),
+                YamlValue::from(dt.to_string()),
+            )))
+        }
+        Value::Boolean(b) => YamlValue::Bool(*b),
+        Value::Array(arr) => {
+            YamlValue::Sequence(arr.iter().map(value_to_yaml).collect())
+        }
+        Value::Object(obj) => {
+            YamlValue::Mapping(frontmatter_to_yaml(obj))
+        }
+        Value::Tagged(tag, val) => {
+            YamlValue::Tagged(Box::new(TaggedValue::new(
+                Tag::new(tag.as_str()),
+                value_to_yaml(val),
+            )))
+        }
     }
 }
 
+/// Converts a `Frontmatter` into a `noyalib::Mapping`.
+fn frontmatter_to_yaml(fm: &Frontmatter) -> noyalib::Mapping {
+    let mut mapping = noyalib::Mapping::with_capacity(fm.len());
+    for (k, v) in fm.iter() {
+        let _ = mapping.insert(k.as_str(), value_to_yaml(v));
+    }
+    mapping
+}
+
 /// Serialises a `Frontmatter` object into a YAML string.
 ///
 /// # Arguments
@@ -355,7 +581,8 @@ fn yaml_to_value(yaml: &YamlValue) -> Value {
 ///
 /// A `Result` containing the serialised YAML string or a `Error`.
 fn to_yaml(front_matter: &Frontmatter) -> Result<String, Error> {
-    noyalib::to_string(&front_matter.0)
+    let yaml = YamlValue::Mapping(frontmatter_to_yaml(front_matter));
+    noyalib::to_string_value(&yaml)
         .map_err(|e| Error::ConversionError(e.to_string()))
 }
 
@@ -378,7 +605,7 @@ fn parse_toml(raw: &str) -> Result<Frontmatter, Error> {
         raw.parse().map_err(Error::TomlParseError)?;
 
     let mut front_matter =
-        Frontmatter(HashMap::with_capacity(table.len()));
+        Frontmatter(IndexMap::with_capacity(table.len()));
 
     for (key, value) in table {
         let _ = front_matter.0.insert(key, toml_to_value(&value));
@@ -391,8 +618,8 @@ fn parse_toml(raw: &str) -> Result<Frontmatter, Error> {
 fn toml_to_value(toml: &TomlValue) -> Value {
     match toml {
         TomlValue::String(s) => Value::String(optimise_string(s)),
-        TomlValue::Integer(i) => Value::Number(*i as f64),
-        TomlValue::Float(f) => Value::Number(*f),
+        TomlValue::Integer(i) => Value::Integer(*i),
+        TomlValue::Float(f) => Value::Float(*f),
         TomlValue::Boolean(b) => Value::Boolean(*b),
         TomlValue::Array(arr) => {
             let mut vec = Vec::with_capacity(arr.len());
@@ -401,7 +628,7 @@ fn toml_to_value(toml: &TomlValue) -> Value {
         }
         TomlValue::Table(table) => {
             let mut result =
-                Frontmatter(HashMap::with_capacity(table.len()));
+                Frontmatter(IndexMap::with_capacity(table.len()));
             for (k, v) in table {
                 let _ = result
                     .0
@@ -409,10 +636,55 @@ fn toml_to_value(toml: &TomlValue) -> Value {
             }
             Value::Object(Box::new(result))
         }
-        TomlValue::Datetime(dt) => Value::String(dt.to_string()),
+        TomlValue::Datetime(dt) => Value::DateTime(*dt),
     }
 }
 
+/// Converts a `Value` into a `toml::Value`.
+///
+/// # Errors
+///
+/// Returns `Error::ConversionError` for values TOML cannot represent,
+/// such as `null` or an array containing `null`.
+pub(crate) fn value_to_toml(value: &Value) -> Result<TomlValue, Error> {
+    Ok(match value {
+        Value::Null => {
+            return Err(Error::ConversionError(
+                "TOML does not support null values".to_string(),
+            ))
+        }
+        Value::String(s) => TomlValue::String(s.clone()),
+        Value::Integer(i) => TomlValue::Integer(*i),
+        Value::Float(f) => TomlValue::Float(*f),
+        Value::DateTime(dt) => TomlValue::Datetime(*dt),
+        Value::Boolean(b) => TomlValue::Boolean(*b),
+        Value::Array(arr) => TomlValue::Array(
+            arr.iter().map(value_to_toml).collect::<Result<_, _>>()?,
+        ),
+        Value::Object(obj) => {
+            TomlValue::Table(frontmatter_to_toml(obj)?)
+        }
+        // TOML has no tags; keep the tag name as a single-key table.
+        Value::Tagged(tag, val) => {
+            let mut table = toml::Table::new();
+            let _ = table.insert(tag.clone(), value_to_toml(val)?);
+            TomlValue::Table(table)
+        }
+    })
+}
+
+/// Converts a `Frontmatter` into a `toml::Table`, skipping `null` entries.
+fn frontmatter_to_toml(fm: &Frontmatter) -> Result<toml::Table, Error> {
+    let mut table = toml::Table::new();
+    for (k, v) in fm.iter() {
+        if v.is_null() {
+            continue;
+        }
+        let _ = table.insert(k.clone(), value_to_toml(v)?);
+    }
+    Ok(table)
+}
+
 /// Serialises a `Frontmatter` object into a TOML string.
 ///
 /// # Arguments
@@ -423,7 +695,7 @@ fn toml_to_value(toml: &TomlValue) -> Value {
 ///
 /// A `Result` containing the serialised TOML string or a `Error`.
 fn to_toml(front_matter: &Frontmatter) -> Result<String, Error> {
-    toml::to_string(&front_matter.0)
+    toml::to_string(&frontmatter_to_toml(front_matter)?)
         .map_err(|e| Error::ConversionError(e.to_string()))
 }
 
@@ -449,7 +721,7 @@ fn parse_json(raw: &str) -> Result<Frontmatter, Error> {
     };
 
     let mut front_matter =
-        Frontmatter(HashMap::with_capacity(capacity));
+        Frontmatter(IndexMap::with_capacity(capacity));
 
     if let JsonValue::Object(obj) = json_value {
         for (key, value) in obj {
@@ -461,19 +733,15 @@ fn parse_json(raw: &str) -> Result<Frontmatter, Error> {
 }
 
 /// Converts a `serde_json::Value` into a `Value`.
-fn json_to_value(json: &JsonValue) -> Value {
+pub(crate) fn json_to_value(json: &JsonValue) -> Value {
     match json {
         JsonValue::Null => Value::Null,
         JsonValue::Bool(b) => Value::Boolean(*b),
+        // Integers outside the `i64` range become floats, as they do
+        // when parsing YAML.
         JsonValue::Number(n) => n.as_i64().map_or_else(
-            || {
-                if let Some(f) = n.as_f64() {
-                    Value::Number(f)
-                } else {
-                    Value::Number(0.0)
-                }
-            },
-            |i| Value::Number(i as f64),
+            || Value::Float(n.as_f64().unwrap_or_default()),
+            Value::Integer,
         ),
         JsonValue::String(s) => Value::String(optimise_string(s)),
         JsonValue::Array(arr) => {
@@ -483,7 +751,7 @@ fn json_to_value(json: &JsonValue) -> Value {
         }
         JsonValue::Object(obj) => {
             let mut result =
-                Frontmatter(HashMap::with_capacity(obj.len()));
+                Frontmatter(IndexMap::with_capacity(obj.len()));
             for (k, v) in obj {
                 let _ = result
                     .0
@@ -512,8 +780,7 @@ fn to_json_optimised(
     let mut ser =
         serde_json::Serializer::with_formatter(buf, formatter);
 
-    front_matter
-        .0
+    Plain(front_matter)
         .serialize(&mut ser)
         .map_err(|e| Error::ConversionError(e.to_string()))?;
 
@@ -521,6 +788,159 @@ fn to_json_optimised(
         .map_err(|e| Error::ConversionError(e.to_string()))
 }
 
+// JSON5 Implementation
+// --------------------
+
+/// Parses a JSON5 string into a `Frontmatter` object.
+///
+/// # Arguments
+///
+/// * `raw` - The raw JSON5 string.
+///
+/// # Returns
+///
+/// A `Result` containing the parsed `Frontmatter` or a `Error`.
+fn parse_json5(raw: &str) -> Result<Frontmatter, Error> {
+    json5::from_str(raw)
+        .map_err(|e| Error::Json5ParseError(Arc::new(e)))
+}
+
+/// Serialises a `Frontmatter` object into a JSON5 string.
+///
+/// # Arguments
+///
+/// * `front_matter` - The `Frontmatter` object to serialise.
+///
+/// # Returns
+///
+/// A `Result` containing the serialised JSON5 string or a `Error`.
+///
+/// The layout is that of `json5::to_string`, but floats are written
+/// as `serde_json` writes them, so integral floats keep a fraction and
+/// read back as floats.
+fn to_json5(front_matter: &Frontmatter) -> Result<String, Error> {
+    let mut out = String::new();
+    write_json5_map(front_matter.iter(), 0, &mut out)?;
+    Ok(out)
+}
+
+fn write_json5_map<'a>(
+    entries: impl ExactSizeIterator<Item = (&'a String, &'a Value)>,
+    depth: usize,
+    out: &mut String,
+) -> Result<(), Error> {
+    if entries.len() == 0 {
+        out.push_str("{}");
+        return Ok(());
+    }
+    out.push('{');
+    for (key, value) in entries {
+        json5_indent(depth + 1, out);
+        let mut chars = key.chars();
+        let identifier = chars.next().is_some_and(|c| {
+            c.is_ascii_alphabetic() || "$_".contains(c)
+        }) && chars
+            .all(|c| c.is_ascii_alphanumeric() || "$_".contains(c));
+        if identifier {
+            out.push_str(key);
+        } else {
+            out.push_str(&json5_text(key)?);
+        }
+        out.push_str(": ");
+        write_json5_value(value, depth + 1, out)?;
+        out.push(',');
+    }
+    json5_indent(depth, out);
+    out.push('}');
+    Ok(())
+}
+
+fn write_json5_value(
+    value: &Value,
+    depth: usize,
+    out: &mut String,
+) -> Result<(), Error> {
+    match value {
+        Value::Float(f) => match serde_json::Number::from_f64(*f) {
+            Some(number) => out.push_str(&number.to_string()),
+            None => out.push_str(&json5_text(f)?),
+        },
+        Value::Array(items) if items.is_empty() => out.push_str("[]"),
+        Value::Array(items) => {
+            out.push('[');
+            for item in items {
+                json5_indent(depth + 1, out);
+                write_json5_value(item, depth + 1, out)?;
+                out.push(',');
+            }
+            json5_indent(depth, out);
+            out.push(']');
+        }
+        Value::Object(obj) => write_json5_map(obj.iter(), depth, out)?,
+        Value::Tagged(tag, value) => write_json5_map(
+            std::iter::once((tag, &**value)),
+            depth,
+            out,
+        )?,
+        value => out.push_str(&json5_text(&Plain(value))?),
+    }
+    Ok(())
+}
+
+fn json5_indent(depth: usize, out: &mut String) {
+    out.push('\n');
+    out.extend(std::iter::repeat_n(' ', depth * 2));
+}
+
+fn json5_text(value: &impl Serialize) -> Result<String, Error> {
+    json5::to_string(value)
+        .map_err(|e| Error::ConversionError(e.to_string()))
+}
+
+// RON Implementation
+// ------------------
+
+/// Parses a RON string into a `Frontmatter` object.
+///
+/// Both maps (`{"title": "x"}`) and structs (`(title: "x")`) are
+/// accepted.
+///
+/// # Arguments
+///
+/// * `raw` - The raw RON string.
+///
+/// # Returns
+///
+/// A `Result` containing the parsed `Frontmatter` or a `Error`.
+fn parse_ron(raw: &str) -> Result<Frontmatter, Error> {
+    match ron::from_str(raw)
+        .map_err(|e| Error::RonParseError(Arc::new(e)))?
+    {
+        Value::Object(front_matter) => Ok(*front_matter),
+        other => Err(Error::ParseError(format!(
+            "RON front matter must be a map or struct, not {}",
+            other.type_name()
+        ))),
+    }
+}
+
+/// Serialises a `Frontmatter` object into a RON string.
+///
+/// # Arguments
+///
+/// * `front_matter` - The `Frontmatter` object to serialise.
+///
+/// # Returns
+///
+/// A `Result` containing the serialised RON string or a `Error`.
+fn to_ron(front_matter: &Frontmatter) -> Result<String, Error> {
+    ron::ser::to_string_pretty(
+        &Plain(front_matter),
+        ron::ser::PrettyConfig::default(),
+    )
+    .map_err(|e| Error::ConversionError(e.to_string()))
+}
+
 // Validation and Utilities
 // ------------------------
 
@@ -638,10 +1058,11 @@ fn estimate_json_size(fm: &Frontmatter) -> usize {
 /// An estimated size in bytes.
 fn estimate_value_size(value: &Value) -> usize {
     match value {
-        Value::Null => 4,                // null
-        Value::String(s) => s.len() + 2, // "string"
-        Value::Number(_) => 8,           // average number length
-        Value::Boolean(_) => 5,          // false/true
+        Value::Null => 4,                         // null
+        Value::String(s) => s.len() + 2,          // "string"
+        Value::Integer(_) | Value::Float(_) => 8, // average number length
+        Value::DateTime(_) => 37, // quoted RFC 3339 with nanoseconds
+        Value::Boolean(_) => 5,   // false/true
         Value::Array(arr) => {
             2 + arr.iter().map(estimate_value_size).sum::<usize>() // []
         }
@@ -655,8 +1076,13 @@ fn estimate_value_size(value: &Value) -> usize {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::options::{
+        MAX_ALIASES, MAX_KEYS, MAX_NODES, MAX_STRING_BYTES,
+    };
     use std::f64::consts::PI;
 
+    const MAX_NESTING_DEPTH: usize = crate::MAX_NESTING_DEPTH.get();
+
     // Helper for creating a test `Frontmatter`
     fn create_test_frontmatter() -> Frontmatter {
         let mut fm = Frontmatter::new();
@@ -664,14 +1090,14 @@ fn create_test_frontmatter() -> Frontmatter {
             "title".to_string(),
             Value::String("Test".to_string()),
         );
-        let _ = fm.insert("number".to_string(), Value::Number(PI));
+        let _ = fm.insert("number".to_string(), Value::Float(PI));
         let _ = fm.insert("boolean".to_string(), Value::Boolean(true));
         let _ = fm.insert(
             "array".to_string(),
             Value::Array(vec![
-                Value::Number(1.0),
-                Value::Number(2.0),
-                Value::Number(3.0),
+                Value::Integer(1),
+                Value::Integer(2),
+                Value::Integer(3),
             ]),
         );
         fm
@@ -684,9 +1110,136 @@ mod parse_options_tests {
         #[test]
         fn test_parse_options_default() {
             let default_options = ParseOptions::default();
-            assert_eq!(default_options.max_depth, MAX_NESTING_DEPTH);
+            assert_eq!(
+                default_options.max_depth.get(),
+                MAX_NESTING_DEPTH
+            );
             assert_eq!(default_options.max_keys, MAX_KEYS);
             assert!(default_options.validate);
+            assert_eq!(default_options.max_aliases, MAX_ALIASES);
+            assert_eq!(default_options.max_nodes, MAX_NODES);
+            assert_eq!(
+                default_options.max_string_bytes,
+                MAX_STRING_BYTES
+            );
+        }
+    }
+
+    /// Tests for the YAML expansion limits.
+    mod expansion_tests {
+        use super::*;
+
+        /// Builds a "billion laughs" document: each of `levels` anchors
+        /// holds nine aliases of the one before it.
+        fn laughs(levels: usize) -> String {
+            let mut yaml = String::from("l0: &l0 lol\n");
+            for level in 1..=levels {
+                let alias = format!("*l{}", level - 1);
+                yaml.push_str(&format!(
+                    "l{level}: &l{level} [{}]\n",
+                    vec![alias; 9].join(", ")
+                ));
+            }
+            yaml
+        }
+
+        fn limit(result: Result<Frontmatter, Error>) -> ExpansionLimit {
+            match result {
+                Err(Error::ExpansionLimitExceeded {
+                    limit, ..
+                }) => limit,
+                other => {
+                    panic!("expected an expansion error: {other:?}")
+                }
+            }
+        }
+
+        #[test]
+        fn test_billion_laughs_is_rejected() {
+            let result = parse(&laughs(9), Format::Yaml);
+            assert!(matches!(
+                result,
+                Err(Error::ExpansionLimitExceeded { .. })
+            ));
+        }
+
+        #[test]
+        fn test_limits_apply_without_validation() {
+            let options = ParseOptions {
+                validate: false,
+                ..ParseOptions::default()
+            };
+            assert!(parse_with_options(
+                &laughs(9),
+                Format::Yaml,
+                Some(options)
+            )
+            .is_err());
+        }
+
+        #[test]
+        fn test_alias_limit() {
+            let options = ParseOptions {
+                max_aliases: 3,
+                ..ParseOptions::default()
+            };
+            let yaml = "a: &a x\nb: [*a, *a, *a, *a]\n";
+            assert_eq!(
+                limit(parse_with_options(
+                    yaml,
+                    Format::Yaml,
+                    Some(options)
+                )),
+                ExpansionLimit::Aliases
+            );
+        }
+
+        #[test]
+        fn test_expanded_node_limit() {
+            // Two levels of laughs write a dozen values in the YAML
+            // but expand to over a hundred in the front matter
+            let options = ParseOptions {
+                max_nodes: 50,
+                ..ParseOptions::default()
+            };
+            assert_eq!(
+                limit(parse_with_options(
+                    &laughs(2),
+                    Format::Yaml,
+                    Some(options)
+                )),
+                ExpansionLimit::Nodes
+            );
+        }
+
+        #[test]
+        fn test_expanded_string_bytes_limit() {
+            let options = ParseOptions {
+                max_string_bytes: 64,
+                ..ParseOptions::default()
+            };
+            let yaml =
+                format!("a: &a {}\nb: [*a, *a]\n", "x".repeat(30));
+            let err =
+                parse_with_options(&yaml, Format::Yaml, Some(options))
+                    .unwrap_err();
+            assert_eq!(
+                err.to_string(),
+                "Your YAML front matter expands past the limit of 64 string bytes."
+            );
+        }
+
+        #[test]
+        fn test_aliases_within_limits_expand() {
+            let fm = parse(&laughs(2), Format::Yaml).unwrap();
+            let Some(Value::Array(level)) = fm.get("l2") else {
+                panic!("l2 should be an array");
+            };
+            assert_eq!(level.len(), 9);
+            assert_eq!(
+                level[0],
+                Value::Array(vec![Value::String("lol".to_string()); 9])
+            );
         }
     }
 
@@ -718,7 +1271,7 @@ mod parsing_tests {
         #[test]
         fn test_parse_yaml() {
             let yaml = "key: value";
-            let result = parse_yaml(yaml);
+            let result = parse_yaml(yaml, &ParseOptions::default());
             assert!(result.is_ok());
             let fm = result.unwrap();
             assert_eq!(
@@ -751,6 +1304,35 @@ fn test_parse_json() {
             );
         }
 
+        #[test]
+        fn test_parse_json5() {
+            let json5 = "// Post\n{key: 'value', count: 0x10,}";
+            let fm =
+                parse_with_options(json5, Format::Json5, None).unwrap();
+            assert_eq!(fm.get("key"), Some(&Value::from("value")));
+            assert_eq!(fm.get("count"), Some(&Value::Integer(16)));
+        }
+
+        #[test]
+        fn test_parse_ron() {
+            for ron in [
+                "(key: \"value\", tags: [\"a\"], draft: None)",
+                "{\"key\": \"value\", \"tags\": [\"a\"], \"draft\": None}",
+            ] {
+                let fm = parse_with_options(ron, Format::Ron, None).unwrap();
+                assert_eq!(
+                    fm.0.keys().collect::<Vec<_>>(),
+                    ["key", "tags", "draft"]
+                );
+                assert_eq!(fm.get("key"), Some(&Value::from("value")));
+                assert_eq!(fm.get("draft"), Some(&Value::Null));
+            }
+
+            let result =
+                parse_with_options("[1, 2]", Format::Ron, None);
+            assert!(matches!(result, Err(Error::ParseError(_))));
+        }
+
         #[test]
         fn test_parse_with_options() {
             let yaml = "key: value";
@@ -763,6 +1345,30 @@ fn test_parse_with_options() {
             );
         }
 
+        #[test]
+        fn test_parse_errors_report_positions() {
+            let cases = [
+                ("\n\ntitle: A\nbad: : x\n", Format::Yaml, (4, 6)),
+                ("title = \"A\"\n  x = = 1\n", Format::Toml, (2, 7)),
+                ("{\n  \"a\": 1,\n  \"b\": ?\n}", Format::Json, (3, 8)),
+                ("{\n  a: 1,\n  b: ?\n}", Format::Json5, (3, 6)),
+                ("(\n  a: 1,\n  b: ?\n)", Format::Ron, (3, 6)),
+            ];
+            for (raw, format, (line, column)) in cases {
+                let err = parse(raw, format).unwrap_err();
+                let context = err.context().expect("a position");
+                assert_eq!(
+                    (context.line, context.column),
+                    (Some(line), Some(column)),
+                    "{format}: {err}"
+                );
+                assert_eq!(
+                    context.snippet.as_deref(),
+                    raw.lines().nth(line - 1)
+                );
+            }
+        }
+
         #[test]
         fn test_parse_with_invalid_format() {
             let yaml = "key: value";
@@ -813,6 +1419,202 @@ fn test_to_string() {
             // Test JSON format
             let json = to_string(&fm, Format::Json).unwrap();
             assert!(json.contains("\"title\":\"Test\""));
+
+            // Test JSON5 format
+            let json5 = to_string(&fm, Format::Json5).unwrap();
+            assert!(json5.contains("title: \"Test\""));
+
+            // Test RON format
+            let ron = to_string(&fm, Format::Ron).unwrap();
+            assert!(ron.contains("\"title\": \"Test\""));
+        }
+
+        #[test]
+        fn test_json5_and_ron_round_trip() {
+            let mut fm = create_test_frontmatter();
+            let _ = fm.insert(
+                "date".to_string(),
+                Value::DateTime("2024-01-01".parse().unwrap()),
+            );
+            let _ = fm.insert(
+                "nested".to_string(),
+                Value::Tagged(
+                    "!tag".to_string(),
+                    Box::new(Value::Null),
+                ),
+            );
+            let _ = fm.insert("weight".to_string(), Value::Float(1.0));
+
+            for format in [Format::Json5, Format::Ron] {
+                let raw = to_string(&fm, format).unwrap();
+                let parsed = parse(&raw, format).unwrap();
+                assert_eq!(
+                    parsed.0.keys().collect::<Vec<_>>(),
+                    fm.0.keys().collect::<Vec<_>>(),
+                    "{format}: {raw}"
+                );
+                assert_eq!(parsed.get("title"), fm.get("title"));
+                assert_eq!(parsed.get("array"), fm.get("array"));
+                assert_eq!(
+                    parsed.get("weight"),
+                    Some(&Value::Float(1.0)),
+                    "{format}: {raw}"
+                );
+                assert_eq!(
+                    parsed.get("date"),
+                    Some(&Value::from("2024-01-01")),
+                    "{format}: {raw}"
+                );
+            }
+        }
+    }
+
+    /// Tests for source key order preservation.
+    mod key_order_tests {
+        use super::*;
+
+        fn keys(fm: &Frontmatter) -> Vec<&str> {
+            fm.iter().map(|(k, _)| k.as_str()).collect()
+        }
+
+        #[test]
+        fn test_yaml_preserves_key_order() {
+            let yaml = "zeta: 1\nalpha: 2\nmid:\n  z: 1\n  a: 2\n";
+            let fm = parse(yaml, Format::Yaml).unwrap();
+            assert_eq!(keys(&fm), ["zeta", "alpha", "mid"]);
+            let nested = fm.get("mid").unwrap().as_object().unwrap();
+            assert_eq!(keys(nested), ["z", "a"]);
+
+            let out = to_string(&fm, Format::Yaml).unwrap();
+            let zeta = out.find("zeta").unwrap();
+            let alpha = out.find("alpha").unwrap();
+            assert!(zeta < alpha);
+        }
+
+        #[test]
+        fn test_toml_preserves_key_order() {
+            let toml =
+                "zeta = \"z\"\nalpha = \"a\"\n\n[mid]\nz = \"z\"\na = \"a\"\n";
+            let fm = parse(toml, Format::Toml).unwrap();
+            assert_eq!(keys(&fm), ["zeta", "alpha", "mid"]);
+            let nested = fm.get("mid").unwrap().as_object().unwrap();
+            assert_eq!(keys(nested), ["z", "a"]);
+
+            let out = to_string(&fm, Format::Toml).unwrap();
+            assert_eq!(out, toml);
+        }
+
+        #[test]
+        fn test_json_preserves_key_order() {
+            let json =
+                r#"{"zeta":"z","alpha":"a","mid":{"z":"z","a":"a"}}"#;
+            let fm = parse(json, Format::Json).unwrap();
+            assert_eq!(keys(&fm), ["zeta", "alpha", "mid"]);
+
+            let out = to_string(&fm, Format::Json).unwrap();
+            assert_eq!(out, json);
+        }
+    }
+
+    /// Tests for lossless integer, float and datetime conversion.
+    mod scalar_fidelity_tests {
+        use super::*;
+
+        const BIG: i64 = 9_007_199_254_740_993; // 2^53 + 1
+
+        #[test]
+        fn test_large_integers_are_preserved() {
+            let sources = [
+                (format!("id: {BIG}"), Format::Yaml),
+                (format!("id = {BIG}"), Format::Toml),
+                (format!(r#"{{"id": {BIG}}}"#), Format::Json),
+            ];
+            for (raw, format) in sources {
+                let fm = parse(&raw, format).unwrap();
+                assert_eq!(fm.get("id"), Some(&Value::Integer(BIG)));
+
+                for target in [Format::Yaml, Format::Toml, Format::Json]
+                {
+                    let out = to_string(&fm, target).unwrap();
+                    assert!(out.contains(&BIG.to_string()), "{out}");
+                    let back = parse(&out, target).unwrap();
+                    assert_eq!(back, fm);
+                }
+            }
+        }
+
+        #[test]
+        fn test_integers_beyond_i64_become_floats() {
+            let sources = [
+                ("id: 9223372036854775808", Format::Yaml),
+                (r#"{"id": 9223372036854775808}"#, Format::Json),
+            ];
+            for (raw, format) in sources {
+                let fm = parse(raw, format).unwrap();
+                assert_eq!(
+                    fm.get("id"),
+                    Some(&Value::Float(9_223_372_036_854_775_808.0))
+                );
+            }
+
+            assert!(parse("id = 9223372036854775808", Format::Toml)
+                .is_err());
+        }
+
+        #[test]
+        fn test_integral_floats_stay_floats() {
+            let fm =
+                parse("ratio = 1.0\ncount = 1", Format::Toml).unwrap();
+            assert_eq!(fm.get("ratio"), Some(&Value::Float(1.0)));
+            assert_eq!(fm.get("count"), Some(&Value::Integer(1)));
+
+            for target in [Format::Yaml, Format::Toml, Format::Json] {
+                let out = to_string(&fm, target).unwrap();
+                assert_eq!(parse(&out, target).unwrap(), fm);
+            }
+        }
+
+        #[test]
+        fn test_toml_datetime_round_trip() {
+            let raw =
+                "published = 1979-05-27T07:32:00Z\nday = 1979-05-27";
+            let fm = parse(raw, Format::Toml).unwrap();
+            let published = fm.get("published").unwrap();
+            assert_eq!(
+                published.as_datetime().map(ToString::to_string),
+                Some("1979-05-27T07:32:00Z".to_string())
+            );
+            assert!(fm.get("day").unwrap().is_datetime());
+
+            let toml = to_string(&fm, Format::Toml).unwrap();
+            assert!(toml.contains("published = 1979-05-27T07:32:00Z"));
+            assert_eq!(parse(&toml, Format::Toml).unwrap(), fm);
+
+            let yaml = to_string(&fm, Format::Yaml).unwrap();
+            assert!(yaml.contains("!!timestamp"));
+            assert_eq!(parse(&yaml, Format::Yaml).unwrap(), fm);
+        }
+
+        #[test]
+        fn test_yaml_timestamp_tag() {
+            let fm = parse(
+                "at: !!timestamp 2024-01-01T10:00:00+01:00\nplain: 2024-01-01",
+                Format::Yaml,
+            )
+            .unwrap();
+            assert!(fm.get("at").unwrap().is_datetime());
+            assert_eq!(
+                fm.get("plain"),
+                Some(&Value::String("2024-01-01".to_string()))
+            );
+        }
+
+        #[test]
+        fn test_datetime_serialises_as_json_string() {
+            let fm = parse("at = 2024-01-01T10:00:00Z", Format::Toml)
+                .unwrap();
+            let json = to_string(&fm, Format::Json).unwrap();
+            assert_eq!(json, r#"{"at":"2024-01-01T10:00:00Z"}"#);
         }
     }
 
@@ -0,0 +1,609 @@
+// path.rs
+
+//! # Path Module
+//!
+//! This module addresses values nested inside front matter with dotted
+//! paths, so that `seo.og.image` or `authors[0].email` can be read and
+//! written without chaining `as_object` and `as_array` calls.
+//!
+//! A path is a sequence of keys separated by `.`, where each key may be
+//! followed by one or more array indices in brackets. A key that itself
+//! contains `.`, `[`, `]` or `\` escapes them with a backslash, so the
+//! key `v1.2` is written `v1\.2`. [`escape_key`] produces that form.
+//!
+//! ## Usage Example
+//!
+//! ```rust
+//! use frontmatter_gen::{parser, Format, Value};
+//!
+//! let mut fm = parser::parse(
+//!     "authors:\n  - name: Jane\n    email: jane@example.com\n",
+//!     Format::Yaml,
+//! )?;
+//!
+//! assert_eq!(
+//!     fm.get_path("authors[0].email").and_then(Value::as_str),
+//!     Some("jane@example.com")
+//! );
+//!
+//! fm.set_path("seo.og.image", "cover.png".into())?;
+//! assert_eq!(
+//!     fm.get_path("seo.og.image").and_then(Value::as_str),
+//!     Some("cover.png")
+//! );
+//! # Ok::<(), frontmatter_gen::Error>(())
+//! ```
+
+use std::fmt;
+
+use crate::{
+    error::Error,
+    types::{Frontmatter, Value},
+};
+
+/// One step of a path.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Segment {
+    /// An object key.
+    Key(String),
+    /// An array index.
+    Index(usize),
+}
+
+impl fmt::Display for Segment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Segment::Key(key) => f.write_str(&escape_key(key)),
+            Segment::Index(index) => write!(f, "[{index}]"),
+        }
+    }
+}
+
+/// Escapes a key so that it is read back as a single path segment.
+///
+/// # Examples
+///
+/// ```rust
+/// use frontmatter_gen::path::escape_key;
+///
+/// assert_eq!(escape_key("v1.2"), r"v1\.2");
+/// assert_eq!(escape_key("title"), "title");
+/// ```
+#[must_use]
+pub fn escape_key(key: &str) -> String {
+    let mut escaped = String::with_capacity(key.len());
+    for c in key.chars() {
+        if matches!(c, '.' | '[' | ']' | '\\') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// Parses a path into its segments.
+///
+/// The first segment is always a key.
+///
+/// # Errors
+///
+/// Returns `Error::InvalidPath` if the path is empty, contains an empty
+/// key, an unknown escape, or an index that is not a non-negative
+/// integer.
+///
+/// # Examples
+///
+/// ```rust
+/// use frontmatter_gen::path::{parse, Segment};
+///
+/// assert_eq!(
+///     parse(r"authors[0].v1\.2")?,
+///     [
+///         Segment::Key("authors".to_string()),
+///         Segment::Index(0),
+///         Segment::Key("v1.2".to_string()),
+///     ]
+/// );
+/// # Ok::<(), frontmatter_gen::Error>(())
+/// ```
+pub fn parse(path: &str) -> Result<Vec<Segment>, Error> {
+    let invalid = |reason: &str| {
+        Error::InvalidPath(format!("`{path}`: {reason}"))
+    };
+
+    let mut segments = Vec::new();
+    let mut chars = path.chars().peekable();
+    loop {
+        let mut key = String::new();
+        while let Some(&c) = chars.peek() {
+            match c {
+                '.' | '[' => break,
+                ']' => return Err(invalid("unexpected `]`")),
+                '\\' => {
+                    let _ = chars.next();
+                    match chars.next() {
+                        Some(c @ ('.' | '[' | ']' | '\\')) => {
+                            key.push(c)
+                        }
+                        _ => return Err(invalid("invalid escape")),
+                    }
+                }
+                _ => {
+                    key.push(c);
+                    let _ = chars.next();
+                }
+            }
+        }
+        if key.is_empty() {
+            return Err(invalid("empty key"));
+        }
+        segments.push(Segment::Key(key));
+
+        while chars.next_if_eq(&'[').is_some() {
+            let mut digits = String::new();
+            while let Some(c) = chars.next_if(char::is_ascii_digit) {
+                digits.push(c);
+            }
+            if chars.next() != Some(']') {
+                return Err(invalid("expected `]` after an index"));
+            }
+            let index = digits
+                .parse()
+                .map_err(|_| invalid("expected an array index"))?;
+            segments.push(Segment::Index(index));
+        }
+
+        match chars.next() {
+            None => return Ok(segments),
+            Some('.') => {}
+            Some(c) => {
+                return Err(invalid(&format!("unexpected `{c}`")))
+            }
+        }
+    }
+}
+
+/// Formats segments back into a path.
+pub(crate) fn join(segments: &[Segment]) -> String {
+    let mut path = String::new();
+    for segment in segments {
+        if matches!(segment, Segment::Key(_)) && !path.is_empty() {
+            path.push('.');
+        }
+        path.push_str(&segment.to_string());
+    }
+    path
+}
+
+/// Looks through tags, which do not change how a value is addressed.
+fn untagged(value: &Value) -> &Value {
+    match value {
+        Value::Tagged(_, inner) => untagged(inner),
+        _ => value,
+    }
+}
+
+/// Looks through tags, which do not change how a value is addressed.
+fn untagged_mut(value: &mut Value) -> &mut Value {
+    match value {
+        Value::Tagged(_, inner) => untagged_mut(inner),
+        _ => value,
+    }
+}
+
+/// Returns the child of `value` named by `segment`.
+fn child<'a>(value: &'a Value, segment: &Segment) -> Option<&'a Value> {
+    match (segment, untagged(value)) {
+        (Segment::Key(key), Value::Object(obj)) => obj.get(key),
+        (Segment::Index(index), Value::Array(arr)) => arr.get(*index),
+        _ => None,
+    }
+}
+
+/// Returns the child of `value` named by `segment`, mutably.
+fn child_mut<'a>(
+    value: &'a mut Value,
+    segment: &Segment,
+) -> Option<&'a mut Value> {
+    match (segment, untagged_mut(value)) {
+        (Segment::Key(key), Value::Object(obj)) => obj.get_mut(key),
+        (Segment::Index(index), Value::Array(arr)) => {
+            arr.get_mut(*index)
+        }
+        _ => None,
+    }
+}
+
+/// Returns the child of `value` named by `segment`, creating it (and
+/// `value` itself, if it is null) when missing, and whether it was
+/// created.
+///
+/// `parent` is the path of `value`, used in error messages.
+fn child_or_insert<'a>(
+    value: &'a mut Value,
+    segment: &Segment,
+    parent: &[Segment],
+) -> Result<(&'a mut Value, bool), Error> {
+    let value = untagged_mut(value);
+    if value.is_null() {
+        *value = match segment {
+            Segment::Key(_) => Value::Object(Box::default()),
+            Segment::Index(_) => Value::Array(Vec::new()),
+        };
+    }
+
+    match (segment, value) {
+        (Segment::Key(key), Value::Object(obj)) => {
+            let created = !obj.contains_key(key);
+            Ok((
+                obj.0.entry(key.clone()).or_insert(Value::Null),
+                created,
+            ))
+        }
+        (Segment::Index(index), Value::Array(arr)) => {
+            // Writing one past the end appends.
+            let created = *index == arr.len();
+            if created {
+                arr.push(Value::Null);
+            }
+            let len = arr.len();
+            let child = arr.get_mut(*index).ok_or_else(|| {
+                Error::InvalidPath(format!(
+                    "`{}`: index {index} is out of bounds for an array of length {len}",
+                    join(parent)
+                ))
+            })?;
+            Ok((child, created))
+        }
+        (segment, value) => Err(Error::InvalidPath(format!(
+            "`{}` is {}, so it has no {}",
+            join(parent),
+            article(value.type_name()),
+            match segment {
+                Segment::Key(key) => format!("key `{key}`"),
+                Segment::Index(index) => format!("index {index}"),
+            }
+        ))),
+    }
+}
+
+/// Prefixes a type name with its indefinite article.
+fn article(type_name: &str) -> String {
+    if type_name.starts_with(['a', 'e', 'i', 'o', 'u']) {
+        format!("an {type_name}")
+    } else {
+        format!("a {type_name}")
+    }
+}
+
+impl Frontmatter {
+    /// Returns the value at a dotted path, such as `seo.og.image` or
+    /// `authors[0].email`.
+    ///
+    /// Tagged values are looked through. See the [`path`](crate::path)
+    /// module for the path syntax.
+    ///
+    /// # Returns
+    ///
+    /// `None` if the path is invalid or does not lead to a value.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use frontmatter_gen::{parser, Format, Value};
+    ///
+    /// let fm = parser::parse(r#"{"v1.2": {"tags": ["a", "b"]}}"#, Format::Json)?;
+    /// assert_eq!(fm.get_path(r"v1\.2.tags[1]"), Some(&Value::String("b".into())));
+    /// assert_eq!(fm.get_path(r"v1\.2.tags[2]"), None);
+    /// # Ok::<(), frontmatter_gen::Error>(())
+    /// ```
+    #[must_use]
+    pub fn get_path(&self, path: &str) -> Option<&Value> {
+        let segments = parse(path).ok()?;
+        let (Segment::Key(first), rest) = segments.split_first()?
+        else {
+            return None;
+        };
+        rest.iter().try_fold(self.get(first)?, |value, segment| {
+            child(value, segment)
+        })
+    }
+
+    /// Returns a mutable reference to the value at a dotted path.
+    ///
+    /// # Returns
+    ///
+    /// `None` if the path is invalid or does not lead to a value.
+    pub fn get_path_mut(&mut self, path: &str) -> Option<&mut Value> {
+        self.lookup_mut(&parse(path).ok()?)
+    }
+
+    /// Sets the value at a dotted path, creating intermediate objects
+    /// and arrays as needed.
+    ///
+    /// A missing or null intermediate becomes an object when followed by
+    /// a key and an array when followed by an index. An index equal to
+    /// the length of an array appends to it.
+    ///
+    /// # Returns
+    ///
+    /// The value previously at the path, if any.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InvalidPath` if the path is invalid, passes
+    /// through a value that is neither an object nor an array, or
+    /// indexes more than one past the end of an array. The front matter
+    /// is unchanged on error.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use frontmatter_gen::{Frontmatter, Value};
+    ///
+    /// let mut fm = Frontmatter::new();
+    /// fm.set_path("authors[0].name", "Jane".into())?;
+    /// assert_eq!(fm.to_string(), r#"{"authors": [{"name": "Jane"}]}"#);
+    ///
+    /// assert!(fm.set_path("authors[0].name.first", "J".into()).is_err());
+    /// # Ok::<(), frontmatter_gen::Error>(())
+    /// ```
+    pub fn set_path(
+        &mut self,
+        path: &str,
+        value: Value,
+    ) -> Result<Option<Value>, Error> {
+        let segments = parse(path)?;
+        let (Segment::Key(first), rest) = segments
+            .split_first()
+            .ok_or_else(|| Error::InvalidPath(path.to_string()))?
+        else {
+            return Err(Error::InvalidPath(path.to_string()));
+        };
+        if rest.is_empty() {
+            return Ok(self.insert(first.clone(), value));
+        }
+
+        // Work on a copy of the top-level entry, so that a failure part
+        // way down leaves the front matter untouched.
+        let mut top = self.get(first).cloned().unwrap_or_default();
+        let mut slot = &mut top;
+        let mut created = false;
+        for (depth, segment) in rest.iter().enumerate() {
+            (slot, created) =
+                child_or_insert(slot, segment, &segments[..=depth])?;
+        }
+        let previous = std::mem::replace(slot, value);
+        let _ = self.insert(first.clone(), top);
+        Ok((!created).then_some(previous))
+    }
+
+    /// Removes and returns the value at a dotted path.
+    ///
+    /// Removing an array element shifts the elements after it.
+    ///
+    /// # Returns
+    ///
+    /// `None` if the path is invalid or does not lead to a value.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use frontmatter_gen::{parser, Format, Value};
+    ///
+    /// let mut fm = parser::parse("tags: [a, b, c]", Format::Yaml)?;
+    /// assert_eq!(fm.remove_path("tags[0]"), Some(Value::String("a".into())));
+    /// assert_eq!(fm.get_path("tags[0]"), Some(&Value::String("b".into())));
+    /// # Ok::<(), frontmatter_gen::Error>(())
+    /// ```
+    pub fn remove_path(&mut self, path: &str) -> Option<Value> {
+        let segments = parse(path).ok()?;
+        let (last, parent) = segments.split_last()?;
+        if parent.is_empty() {
+            return match last {
+                Segment::Key(key) => self.remove(key),
+                Segment::Index(_) => None,
+            };
+        }
+
+        match (last, untagged_mut(self.lookup_mut(parent)?)) {
+            (Segment::Key(key), Value::Object(obj)) => obj.remove(key),
+            (Segment::Index(index), Value::Array(arr))
+                if *index < arr.len() =>
+            {
+                Some(arr.remove(*index))
+            }
+            _ => None,
+        }
+    }
+
+    /// Follows parsed segments to a value, mutably.
+    fn lookup_mut(
+        &mut self,
+        segments: &[Segment],
+    ) -> Option<&mut Value> {
+        let (Segment::Key(first), rest) = segments.split_first()?
+        else {
+            return None;
+        };
+        rest.iter()
+            .try_fold(self.get_mut(first)?, |value, segment| {
+                child_mut(value, segment)
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parser, types::Format};
+
+    fn sample() -> Frontmatter {
+        parser::parse(
+            r#"
+title: Post
+seo:
+  og:
+    image: cover.png
+authors:
+  - name: Jane
+    email: jane@example.com
+  - name: John
+"v1.2": released
+custom: !meta
+  kind: note
+"#,
+            Format::Yaml,
+        )
+        .unwrap()
+    }
+
+    mod parse_tests {
+        use super::*;
+
+        #[test]
+        fn test_parse_segments() {
+            assert_eq!(
+                parse(r"a.b[0][12].c\.d\[e\]\\").unwrap(),
+                [
+                    Segment::Key("a".into()),
+                    Segment::Key("b".into()),
+                    Segment::Index(0),
+                    Segment::Index(12),
+                    Segment::Key(r"c.d[e]\".into()),
+                ]
+            );
+        }
+
+        #[test]
+        fn test_parse_rejects_malformed_paths() {
+            for path in [
+                "", "a.", ".a", "a..b", "[0]", "a[x]", "a[1", "a]",
+                "a[0]b", r"a\n",
+            ] {
+                assert!(
+                    matches!(parse(path), Err(Error::InvalidPath(_))),
+                    "{path:?} was accepted"
+                );
+            }
+        }
+
+        #[test]
+        fn test_escape_key_round_trips() {
+            for key in ["plain", "v1.2", r"a\b", "x[0]"] {
+                assert_eq!(
+                    parse(&escape_key(key)).unwrap(),
+                    [Segment::Key(key.into())]
+                );
+            }
+        }
+    }
+
+    mod access_tests {
+        use super::*;
+
+        #[test]
+        fn test_get_path() {
+            let fm = sample();
+            let get = |path| fm.get_path(path).and_then(Value::as_str);
+            assert_eq!(get("seo.og.image"), Some("cover.png"));
+            assert_eq!(
+                get("authors[0].email"),
+                Some("jane@example.com")
+            );
+            assert_eq!(get("authors[1].name"), Some("John"));
+            assert_eq!(get(r"v1\.2"), Some("released"));
+            assert_eq!(get("custom.kind"), Some("note"));
+            assert_eq!(get("authors[2].name"), None);
+            assert_eq!(get("title.length"), None);
+            assert_eq!(get("seo..og"), None);
+        }
+
+        #[test]
+        fn test_get_path_mut() {
+            let mut fm = sample();
+            *fm.get_path_mut("authors[1].name").unwrap() = "Jo".into();
+            assert_eq!(
+                fm.get_path("authors[1].name"),
+                Some(&Value::String("Jo".into()))
+            );
+        }
+
+        #[test]
+        fn test_set_path_creates_intermediates() {
+            let mut fm = sample();
+            assert_eq!(
+                fm.set_path("seo.og.image", "new.png".into()).unwrap(),
+                Some(Value::String("cover.png".into()))
+            );
+            assert_eq!(
+                fm.set_path("seo.twitter.card", "summary".into())
+                    .unwrap(),
+                None
+            );
+            assert_eq!(
+                fm.set_path("authors[2].name", "Ann".into()).unwrap(),
+                None
+            );
+            assert_eq!(
+                fm.set_path("links[0][0]", Value::Integer(1)).unwrap(),
+                None
+            );
+
+            assert_eq!(
+                fm.get_path("seo.twitter.card"),
+                Some(&Value::String("summary".into()))
+            );
+            assert_eq!(
+                fm.get_path("authors[2].name").and_then(Value::as_str),
+                Some("Ann")
+            );
+            assert_eq!(
+                fm.get_path("links").unwrap().to_string(),
+                "[[1]]"
+            );
+        }
+
+        #[test]
+        fn test_set_path_errors_leave_front_matter_unchanged() {
+            let mut fm = sample();
+            let before = fm.clone();
+
+            let err = fm.set_path("seo.og.image.width", 1.into());
+            assert_eq!(
+                err.unwrap_err().to_string(),
+                "Invalid front matter path: `seo.og.image` is a string, so it has no key `width`"
+            );
+            let err = fm.set_path("authors[5]", Value::Null);
+            assert!(err.unwrap_err().to_string().contains(
+                "`authors`: index 5 is out of bounds for an array of length 2"
+            ));
+            assert!(fm.set_path("seo.new.x[", Value::Null).is_err());
+            assert_eq!(fm, before);
+        }
+
+        #[test]
+        fn test_remove_path() {
+            let mut fm = sample();
+            assert_eq!(
+                fm.remove_path("authors[0].email"),
+                Some(Value::String("jane@example.com".into()))
+            );
+            assert_eq!(
+                fm.remove_path("authors[0]").unwrap().to_string(),
+                r#"{"name": "Jane"}"#
+            );
+            assert_eq!(
+                fm.get_path("authors[0].name").and_then(Value::as_str),
+                Some("John")
+            );
+            assert_eq!(
+                fm.remove_path(r"v1\.2"),
+                Some(Value::String("released".into()))
+            );
+            assert_eq!(fm.remove_path("authors[3]"), None);
+            assert_eq!(fm.remove_path("missing.key"), None);
+            assert!(!fm.contains_key("v1.2"));
+        }
+    }
+}
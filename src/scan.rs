@@ -0,0 +1,699 @@
+// scan.rs
+
+//! # Key Scanning Module
+//!
+//! This module reads selected top-level keys from front matter without
+//! parsing the rest of it. The block is split into its top-level entries
+//! with a light scan that only tracks strings, comments and brackets;
+//! the entries whose keys are wanted are then parsed on their own, with
+//! the same converters as [`parser::parse`]. The body is never read
+//! beyond the closing fence.
+//!
+//! Entries that are not wanted are never parsed, so an error in one of
+//! them goes unreported. When the scan meets something it cannot split
+//! safely, such as a YAML merge key, a quoted key with escapes or an
+//! alias to a skipped entry, it falls back to parsing the whole block.
+//!
+//! ## Usage Example
+//!
+//! ```rust
+//! use frontmatter_gen::extract_keys;
+//!
+//! let content = "---\ntitle: My Post\ndraft: true\ntags:\n- rust\n- web\n---\nBody";
+//! let frontmatter = extract_keys(content, &["draft", "tags"])?;
+//!
+//! assert_eq!(frontmatter.len(), 2);
+//! assert_eq!(frontmatter.get("draft").and_then(|v| v.as_bool()), Some(true));
+//! assert!(frontmatter.get("title").is_none());
+//! # Ok::<(), frontmatter_gen::Error>(())
+//! ```
+
+use std::borrow::Cow;
+
+use crate::{
+    error::Error,
+    extractor::find_block,
+    parser,
+    types::{Format, Frontmatter},
+    validate_input, ParseOptions,
+};
+
+/// Extracts the given top-level keys from the front matter of a
+/// document.
+///
+/// The format comes from the fence, as in [`extract`](crate::extract).
+/// Keys that the front matter does not contain are left out of the
+/// result, and the input checks apply to the front matter block only.
+/// The security policy applies to the values returned, and to the YAML
+/// anchors and aliases of the whole block.
+///
+/// # Arguments
+///
+/// * `content` - The whole document.
+/// * `keys` - The top-level keys to read.
+///
+/// # Errors
+///
+/// Returns `Error` if:
+/// - The front matter block cannot be found or fails the input checks
+/// - A wanted entry cannot be parsed
+pub fn extract_keys(
+    content: &str,
+    keys: &[&str],
+) -> Result<Frontmatter, Error> {
+    extract_keys_with_options(content, keys, &ParseOptions::from_env())
+}
+
+/// Extracts the given top-level keys from the front matter of a
+/// document, under the limits and security policy of `options`.
+///
+/// This is [`extract_keys`] with `options` in place of those read from
+/// the environment.
+///
+/// # Errors
+///
+/// Returns the same errors as [`extract_keys`], against the limits of
+/// `options`.
+///
+/// # Examples
+///
+/// ```rust
+/// use frontmatter_gen::{extract_keys_with_options, Error, ParseOptions};
+///
+/// let options = ParseOptions::builder().max_size(8).build()?;
+/// let result = extract_keys_with_options(
+///     "---\ntitle: My Post\n---\nBody",
+///     &["title"],
+///     &options,
+/// );
+/// assert!(matches!(result, Err(Error::ContentTooLarge { .. })));
+/// # Ok::<(), frontmatter_gen::Error>(())
+/// ```
+pub fn extract_keys_with_options(
+    content: &str,
+    keys: &[&str],
+    options: &ParseOptions,
+) -> Result<Frontmatter, Error> {
+    let block = find_block(content)?;
+    validate_input(&content[..block.body.start], options)?;
+
+    let raw = &content[block.frontmatter];
+    let frontmatter =
+        parse_keys_with_options(raw, block.format, keys, options)
+            .map_err(|e| e.relocate(raw, content))?;
+    options.security.check(raw, block.format, &frontmatter)?;
+
+    Ok(frontmatter)
+}
+
+/// Parses the given top-level keys of raw front matter.
+///
+/// # Arguments
+///
+/// * `raw` - The front matter, without its fences.
+/// * `format` - The format of the front matter.
+/// * `keys` - The top-level keys to read.
+///
+/// # Errors
+///
+/// Returns `Error` if a wanted entry cannot be parsed, or if the whole
+/// block has to be parsed and that fails.
+///
+/// # Examples
+///
+/// ```rust
+/// use frontmatter_gen::{scan::parse_keys, Format};
+///
+/// let raw = "title = \"Post\"\n\n[extra]\nnotes = \"unused\"\n";
+/// let frontmatter = parse_keys(raw, Format::Toml, &["title"])?;
+/// assert_eq!(frontmatter.get("title").and_then(|v| v.as_str()), Some("Post"));
+/// assert_eq!(frontmatter.len(), 1);
+/// # Ok::<(), frontmatter_gen::Error>(())
+/// ```
+pub fn parse_keys(
+    raw: &str,
+    format: Format,
+    keys: &[&str],
+) -> Result<Frontmatter, Error> {
+    parse_keys_with_options(raw, format, keys, &ParseOptions::default())
+}
+
+/// Parses the given top-level keys of raw front matter under the limits
+/// of `options`.
+///
+/// # Errors
+///
+/// Returns the same errors as [`parse_keys`], against the limits of
+/// `options`.
+pub fn parse_keys_with_options(
+    raw: &str,
+    format: Format,
+    keys: &[&str],
+    options: &ParseOptions,
+) -> Result<Frontmatter, Error> {
+    let entries = match format {
+        Format::Yaml => yaml_entries(raw),
+        Format::Toml => toml_entries(raw),
+        Format::Json => json_entries(raw),
+        Format::Json5
+        | Format::Ron
+        | Format::Org
+        | Format::AsciiDoc
+        | Format::Unsupported => None,
+    };
+
+    if let Some(entries) = entries {
+        let wanted: Vec<&str> = entries
+            .iter()
+            .filter(|(key, _)| keys.contains(&key.as_ref()))
+            .map(|&(_, entry)| entry)
+            .collect();
+        if wanted.is_empty() {
+            return Ok(Frontmatter::new());
+        }
+
+        let selected = match format {
+            Format::Json => format!("{{{}}}", wanted.join(",")),
+            _ => wanted.iter().fold(String::new(), |mut doc, entry| {
+                doc.push_str(entry);
+                if !entry.ends_with('\n') {
+                    doc.push('\n');
+                }
+                doc
+            }),
+        };
+        if let Ok(frontmatter) = parser::parse_with_options(
+            &selected,
+            format,
+            Some(*options),
+        ) {
+            return Ok(frontmatter);
+        }
+    }
+
+    // The block could not be split, or the wanted entries depend on
+    // others: parse all of it.
+    let mut frontmatter =
+        parser::parse_with_options(raw, format, Some(*options))?;
+    frontmatter.0.retain(|key, _| keys.contains(&key.as_str()));
+    Ok(frontmatter)
+}
+
+/// A top-level key and the source text of its entry.
+type Entry<'a> = (Cow<'a, str>, &'a str);
+
+/// Splits YAML into its top-level entries, or returns `None` if the
+/// block uses a construct the scan does not follow.
+fn yaml_entries(raw: &str) -> Option<Vec<Entry<'_>>> {
+    let mut entries: Vec<(Cow<'_, str>, usize)> = Vec::new();
+    let mut offset = 0;
+
+    for line in raw.split_inclusive('\n') {
+        let start = offset;
+        offset += line.len();
+
+        let text = line.trim_end_matches(['\n', '\r']);
+        let continues = text.is_empty()
+            || text.starts_with([' ', '\t', '#'])
+            || text == "-"
+            || text.starts_with("- ");
+        if continues {
+            // A sequence item needs an entry to belong to.
+            if entries.is_empty()
+                && !text.trim_start().is_empty()
+                && !text.trim_start().starts_with('#')
+            {
+                return None;
+            }
+            continue;
+        }
+
+        let key = yaml_key(text)?;
+        if key == "<<" {
+            return None;
+        }
+        entries.push((key, start));
+    }
+
+    Some(spans(raw, entries))
+}
+
+/// Reads the key of a YAML line that starts a top-level entry.
+fn yaml_key(text: &str) -> Option<Cow<'_, str>> {
+    let (key, rest) = match text.chars().next()? {
+        '"' => {
+            let end = text[1..].find('"')? + 1;
+            let key = &text[1..end];
+            if key.contains('\\') {
+                return None;
+            }
+            (Cow::Borrowed(key), &text[end + 1..])
+        }
+        '\'' => {
+            let mut end = 1;
+            loop {
+                end += text[end..].find('\'')?;
+                if text[end + 1..].starts_with('\'') {
+                    end += 2;
+                } else {
+                    break;
+                }
+            }
+            (
+                Cow::Owned(text[1..end].replace("''", "'")),
+                &text[end + 1..],
+            )
+        }
+        // Directives, document markers, flow collections, anchors,
+        // tags, aliases, complex keys and block scalars.
+        '%' | '-' | '.' | '{' | '[' | '&' | '!' | '*' | '?' | '|'
+        | '>' | '}' | ']' | ',' | '@' | '`' => return None,
+        _ => {
+            let colon =
+                text.match_indices(':').map(|(i, _)| i).find(|&i| {
+                    text[i + 1..].is_empty()
+                        || text[i + 1..].starts_with([' ', '\t'])
+                })?;
+            (Cow::Borrowed(text[..colon].trim_end()), &text[colon..])
+        }
+    };
+
+    let rest = rest.trim_start_matches([' ', '\t']);
+    rest.starts_with(':').then_some(key)
+}
+
+/// Splits TOML into its top-level entries, or returns `None` if the
+/// block uses a construct the scan does not follow.
+///
+/// A key-value pair before the first table is an entry of its own, and
+/// a table header starts an entry that runs to the next header, so
+/// `[author.links]` belongs to the key `author`.
+fn toml_entries(raw: &str) -> Option<Vec<Entry<'_>>> {
+    let mut entries: Vec<(Cow<'_, str>, usize)> = Vec::new();
+    let mut lexer = TomlLexer::default();
+    let mut in_table = false;
+    let mut offset = 0;
+
+    for line in raw.split_inclusive('\n') {
+        let start = offset;
+        offset += line.len();
+
+        if lexer.is_neutral() {
+            let text = line.trim_start();
+            if text.is_empty() || text.starts_with('#') {
+                continue;
+            }
+            if let Some(header) = text.strip_prefix('[') {
+                in_table = true;
+                let key = toml_key(header.trim_start_matches('['))?;
+                entries.push((key, start));
+                continue;
+            }
+            if !in_table {
+                entries.push((toml_key(text)?, start));
+            }
+        }
+        lexer.line(line)?;
+    }
+
+    lexer.is_neutral().then(|| spans(raw, entries))
+}
+
+/// Reads the first segment of a TOML key.
+fn toml_key(text: &str) -> Option<Cow<'_, str>> {
+    let text = text.trim_start();
+    let key = match text.chars().next()? {
+        quote @ ('"' | '\'') => {
+            let end = text[1..].find(quote)? + 1;
+            let key = &text[1..end];
+            if quote == '"' && key.contains('\\') {
+                return None;
+            }
+            key
+        }
+        _ => {
+            let end = text
+                .find(|c: char| {
+                    !(c.is_ascii_alphanumeric() || c == '_' || c == '-')
+                })
+                .unwrap_or(text.len());
+            &text[..end]
+        }
+    };
+    (!key.is_empty()).then_some(Cow::Borrowed(key))
+}
+
+/// Where a TOML line ends up: inside a multi-line string, inside
+/// brackets, or back at the top level.
+#[derive(Default)]
+struct TomlLexer {
+    /// The delimiter of the multi-line string being read, if any.
+    multiline: Option<&'static str>,
+    /// How many arrays and inline tables are open.
+    depth: usize,
+}
+
+impl TomlLexer {
+    /// Returns `true` if the next line starts a new statement.
+    const fn is_neutral(&self) -> bool {
+        self.multiline.is_none() && self.depth == 0
+    }
+
+    /// Follows one line, returning `None` if it does not lex.
+    fn line(&mut self, line: &str) -> Option<()> {
+        let mut rest = line;
+        while !rest.is_empty() {
+            if let Some(delimiter) = self.multiline {
+                // Literal strings have no escapes.
+                let end = if delimiter == "'''" {
+                    rest.find(delimiter)
+                } else {
+                    find_unescaped(rest, delimiter)?
+                };
+                match end {
+                    Some(end) => {
+                        self.multiline = None;
+                        rest = &rest[end + delimiter.len()..];
+                    }
+                    None => return Some(()),
+                }
+                continue;
+            }
+
+            let Some(i) =
+                rest.find(['#', '"', '\'', '[', '{', ']', '}'])
+            else {
+                return Some(());
+            };
+            let token = &rest[i..];
+            rest = match token.as_bytes()[0] {
+                b'#' => return Some(()),
+                b'"' | b'\'' => {
+                    let (quote, triple) = if token.starts_with('"') {
+                        ("\"", "\"\"\"")
+                    } else {
+                        ("'", "'''")
+                    };
+                    if token.starts_with(triple) {
+                        self.multiline = Some(triple);
+                        &token[3..]
+                    } else {
+                        let body = &token[1..];
+                        let end = if quote == "\"" {
+                            find_unescaped(body, quote)??
+                        } else {
+                            body.find(quote)?
+                        };
+                        &body[end + 1..]
+                    }
+                }
+                b'[' | b'{' => {
+                    self.depth += 1;
+                    &token[1..]
+                }
+                _ => {
+                    self.depth = self.depth.checked_sub(1)?;
+                    &token[1..]
+                }
+            };
+        }
+        Some(())
+    }
+}
+
+/// Finds `delimiter` in `text`, skipping backslash escapes. Returns
+/// `Some(None)` if it is not there and `None` if `text` ends in the
+/// middle of an escape.
+fn find_unescaped(
+    text: &str,
+    delimiter: &str,
+) -> Option<Option<usize>> {
+    let mut chars = text.char_indices();
+    while let Some((i, c)) = chars.next() {
+        if c == '\\' {
+            let _ = chars.next();
+        } else if text[i..].starts_with(delimiter) {
+            return Some(Some(i));
+        }
+    }
+    Some(None)
+}
+
+/// Splits a JSON object into its members, or returns `None` if it is
+/// not a well-formed object.
+fn json_entries(raw: &str) -> Option<Vec<Entry<'_>>> {
+    let bytes = raw.as_bytes();
+    let skip_ws = |mut i: usize| {
+        while bytes.get(i).is_some_and(u8::is_ascii_whitespace) {
+            i += 1;
+        }
+        i
+    };
+
+    let mut entries = Vec::new();
+    let mut i = skip_ws(0);
+    if bytes.get(i) != Some(&b'{') {
+        return None;
+    }
+    i = skip_ws(i + 1);
+    if bytes.get(i) == Some(&b'}') {
+        return Some(entries);
+    }
+
+    loop {
+        let key_start = i;
+        if bytes.get(i) != Some(&b'"') {
+            return None;
+        }
+        i = json_string_end(bytes, i)?;
+        let quoted = &raw[key_start..i];
+        let key = if quoted.contains('\\') {
+            Cow::Owned(serde_json::from_str::<String>(quoted).ok()?)
+        } else {
+            Cow::Borrowed(&quoted[1..quoted.len() - 1])
+        };
+
+        i = skip_ws(i);
+        if bytes.get(i) != Some(&b':') {
+            return None;
+        }
+        i = json_value_end(bytes, skip_ws(i + 1))?;
+        entries.push((key, raw[key_start..i].trim_end()));
+
+        match bytes.get(i) {
+            Some(b',') => i = skip_ws(i + 1),
+            Some(b'}') => return Some(entries),
+            _ => return None,
+        }
+    }
+}
+
+/// Returns the index after the string that opens at `start`.
+fn json_string_end(bytes: &[u8], start: usize) -> Option<usize> {
+    let mut i = start + 1;
+    loop {
+        match bytes.get(i)? {
+            b'\\' => i += 2,
+            b'"' => return Some(i + 1),
+            _ => i += 1,
+        }
+    }
+}
+
+/// Returns the index of the `,` or `}` that ends the value starting at
+/// `start`.
+fn json_value_end(bytes: &[u8], start: usize) -> Option<usize> {
+    let mut depth = 0_usize;
+    let mut i = start;
+    loop {
+        match bytes.get(i)? {
+            b'"' => {
+                i = json_string_end(bytes, i)?;
+                continue;
+            }
+            b'[' | b'{' => depth += 1,
+            b']' | b'}' if depth > 0 => depth -= 1,
+            b',' | b'}' if depth == 0 => return Some(i),
+            _ => {}
+        }
+        i += 1;
+    }
+}
+
+/// Turns entry start offsets into entries that run to the next start.
+fn spans<'a>(
+    raw: &'a str,
+    starts: Vec<(Cow<'a, str>, usize)>,
+) -> Vec<Entry<'a>> {
+    let ends: Vec<usize> = starts
+        .iter()
+        .skip(1)
+        .map(|&(_, start)| start)
+        .chain(std::iter::once(raw.len()))
+        .collect();
+    starts
+        .into_iter()
+        .zip(ends)
+        .map(|((key, start), end)| (key, &raw[start..end]))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Value;
+
+    fn keys(raw: &str, format: Format, wanted: &[&str]) -> Frontmatter {
+        parse_keys(raw, format, wanted).unwrap()
+    }
+
+    mod split_tests {
+        use super::*;
+
+        fn entry_keys(entries: Option<Vec<Entry<'_>>>) -> Vec<String> {
+            entries
+                .unwrap()
+                .into_iter()
+                .map(|(key, _)| key.into_owned())
+                .collect()
+        }
+
+        #[test]
+        fn test_yaml_entries() {
+            let raw = "# About\ntitle: \"A: B\"\n'it''s': 1\ntags:\n- a\n  # note\nauthor:\n  name: Jane\n\"quoted\" : x\n";
+            let entries = yaml_entries(raw).unwrap();
+            assert_eq!(entries[2].1, "tags:\n- a\n  # note\n");
+            assert_eq!(
+                entry_keys(Some(entries)),
+                ["title", "it's", "tags", "author", "quoted"]
+            );
+
+            for raw in [
+                "<<: *base\ntitle: A\n",
+                "- a\n- b\n",
+                "{title: A}\n",
+                "title: [a,\nb]\n",
+                "\"a\\tb\": 1\n",
+                "%YAML 1.2\n---\ntitle: A\n",
+            ] {
+                assert!(yaml_entries(raw).is_none(), "{raw}");
+            }
+        }
+
+        #[test]
+        fn test_toml_entries() {
+            let raw = "title = \"a = [\"\nnotes = \"\"\"\nfake = 1\n[x]\n\"\"\"\ntags = [\n  \"a\",\n]\n\"dotted.key\".b = 'c' # ]\n\n[author]\nname = 'Jane'\n[[links]]\nurl = \"u\"\n[author.extra]\ny = 1\n";
+            let entries = toml_entries(raw).unwrap();
+            assert_eq!(entries[2].1, "tags = [\n  \"a\",\n]\n");
+            assert_eq!(
+                entry_keys(Some(entries)),
+                [
+                    "title",
+                    "notes",
+                    "tags",
+                    "dotted.key",
+                    "author",
+                    "links",
+                    "author"
+                ]
+            );
+
+            assert!(toml_entries("a = [1,\n").is_none());
+            assert!(toml_entries("\"a\\u0041\" = 1\n").is_none());
+        }
+
+        #[test]
+        fn test_json_entries() {
+            let raw = r#" { "a": {"b": [1, "}", "]"]}, "c\"d" : "x,y" ,"e":null } "#;
+            let entries = json_entries(raw).unwrap();
+            assert_eq!(entries[0].1, r#""a": {"b": [1, "}", "]"]}"#);
+            assert_eq!(entry_keys(Some(entries)), ["a", "c\"d", "e"]);
+
+            assert_eq!(json_entries("{}").unwrap().len(), 0);
+            for raw in ["[1]", "{\"a\": 1", "{\"a\" 1}", "{\"a\": [1}"]
+            {
+                assert!(json_entries(raw).is_none(), "{raw}");
+            }
+        }
+    }
+
+    mod parse_keys_tests {
+        use super::*;
+
+        #[test]
+        fn test_only_wanted_keys_are_parsed() {
+            // The unwanted entries are not valid, and are never parsed.
+            let yaml = "title: Post\nbroken: [unclosed\ndraft: true\n";
+            let fm = keys(yaml, Format::Yaml, &["draft", "missing"]);
+            assert_eq!(fm.len(), 1);
+            assert_eq!(fm.get("draft"), Some(&Value::Boolean(true)));
+
+            let toml =
+                "draft = true\nbroken = [\n[author]\nname = 'J'\n";
+            assert!(toml_entries(toml).is_none());
+            let toml = "draft = true\nbroken = nope\n[author]\nname = 'J'\n[author.links]\nsite = 's'\n";
+            let fm = keys(toml, Format::Toml, &["draft", "author"]);
+            assert_eq!(fm.len(), 2);
+            assert_eq!(
+                fm.get_path("author.links.site")
+                    .and_then(Value::as_str),
+                Some("s")
+            );
+
+            let json =
+                r#"{"title": "Post", "broken": tru, "tags": ["a"]}"#;
+            let fm = keys(json, Format::Json, &["tags"]);
+            assert_eq!(
+                fm.get("tags").and_then(Value::array_len),
+                Some(1)
+            );
+        }
+
+        #[test]
+        fn test_matches_a_full_parse() {
+            let yaml = "title: 'A: B'\ndate: 2024-01-02T03:04:05Z\nlist:\n- 1\n- two\nnested:\n  a: !tag x\n";
+            let full = parser::parse(yaml, Format::Yaml).unwrap();
+            let wanted = ["list", "nested", "date"];
+            let mut expected = full.clone();
+            expected.0.retain(|key, _| wanted.contains(&key.as_str()));
+            assert_eq!(keys(yaml, Format::Yaml, &wanted), expected);
+        }
+
+        #[test]
+        fn test_falls_back_to_a_full_parse() {
+            let yaml = "base: &b\n  draft: true\npost: *b\n";
+            let fm = keys(yaml, Format::Yaml, &["post"]);
+            assert_eq!(
+                fm.get_path("post.draft"),
+                Some(&Value::Boolean(true))
+            );
+
+            let yaml =
+                "defaults: &d { draft: true }\n<<: *d\ntitle: A\n";
+            let fm = keys(yaml, Format::Yaml, &["draft"]);
+            assert_eq!(fm.get("draft"), Some(&Value::Boolean(true)));
+
+            assert!(parse_keys("title: [", Format::Yaml, &["title"])
+                .is_err());
+        }
+    }
+
+    mod extract_keys_tests {
+        use super::*;
+
+        #[test]
+        fn test_body_is_not_checked() {
+            let content = "+++\ndraft = false\n+++\nSee ../other.md\0";
+            let fm = extract_keys(content, &["draft"]).unwrap();
+            assert_eq!(fm.get("draft"), Some(&Value::Boolean(false)));
+            assert!(crate::extract(content).is_ok());
+        }
+
+        #[test]
+        fn test_errors_point_into_the_document() {
+            let content = "---\ntitle: A\ndraft: [\n---\n";
+            let err = extract_keys(content, &["draft"]).unwrap_err();
+            assert_eq!(err.context().and_then(|c| c.line), Some(3));
+        }
+    }
+}
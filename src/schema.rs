@@ -0,0 +1,1013 @@
+//! # Front Matter Schema Module
+//!
+//! This module declares the expected shape of a front matter block and
+//! validates parsed `Frontmatter` against it. A schema describes each
+//! field by:
+//!
+//! - its type, whether it may be null, and whether it is required,
+//! - the values it may take (`enum`),
+//! - a regular expression its strings must match,
+//! - string length and numeric ranges,
+//! - the schema of array items and of nested object fields.
+//!
+//! Validation does not stop at the first problem: every violation is
+//! returned together with the path of the offending key, such as
+//! `author.name` or `tags[2]`.
+//!
+//! Schemas can be built in code or loaded from TOML, YAML or JSON files.
+//!
+//! ## Usage Example
+//!
+//! ```rust
+//! use frontmatter_gen::{extract, schema::Schema, Format};
+//!
+//! let schema = Schema::parse(
+//!     r#"
+//! [fields.title]
+//! type = "string"
+//! required = true
+//! max_length = 64
+//!
+//! [fields.tags]
+//! type = "array"
+//! items = { type = "string", pattern = "^[a-z-]+$" }
+//! "#,
+//!     Format::Toml,
+//! )?;
+//!
+//! let (frontmatter, _) = extract("---\ntags: [rust, Serde]\n---\n")?;
+//! let violations = schema.validate(&frontmatter);
+//!
+//! assert_eq!(violations.len(), 2);
+//! assert_eq!(violations[0].path, "title");
+//! assert_eq!(violations[1].path, "tags[1]");
+//! # Ok::<(), frontmatter_gen::Error>(())
+//! ```
+
+use indexmap::IndexMap;
+use regex::Regex;
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use std::{fmt, path::Path, sync::Arc};
+
+use crate::{
+    error::Error,
+    parser::{yaml_config, yaml_error},
+    types::{Datetime, Format, Frontmatter, Value},
+    validate_input, ParseOptions,
+};
+
+/// A schema describing the fields of a front matter block.
+///
+/// Fields that are present in the front matter but not declared in the
+/// schema are not checked.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Schema {
+    /// Field schemas, keyed by field name.
+    #[serde(default)]
+    pub fields: IndexMap<String, FieldSchema>,
+}
+
+/// The constraints placed on a single field.
+///
+/// Every constraint is optional. Length and range constraints only apply
+/// to values of a matching type, so a `min_length` on a field that holds
+/// a number is ignored unless `type` rules the number out.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FieldSchema {
+    /// The expected type. Any type is accepted when `None`.
+    #[serde(rename = "type", default)]
+    pub field_type: Option<FieldType>,
+    /// Whether `null` is accepted as well as values of the type.
+    #[serde(default)]
+    pub nullable: bool,
+    /// Whether the field must be present.
+    #[serde(default)]
+    pub required: bool,
+    /// The only values the field may take.
+    #[serde(rename = "enum", default)]
+    pub allowed: Option<Vec<Value>>,
+    /// A regular expression that string values must match.
+    #[serde(default)]
+    pub pattern: Option<Pattern>,
+    /// Minimum number of characters in a string, or items in an array.
+    #[serde(default)]
+    pub min_length: Option<usize>,
+    /// Maximum number of characters in a string, or items in an array.
+    #[serde(default)]
+    pub max_length: Option<usize>,
+    /// Inclusive lower bound for numeric values.
+    #[serde(default)]
+    pub minimum: Option<f64>,
+    /// Inclusive upper bound for numeric values.
+    #[serde(default)]
+    pub maximum: Option<f64>,
+    /// The schema every array item must satisfy.
+    #[serde(default)]
+    pub items: Option<Box<FieldSchema>>,
+    /// The schemas of the fields of a nested object.
+    #[serde(default)]
+    pub fields: Option<IndexMap<String, FieldSchema>>,
+}
+
+/// The types a field can be declared with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FieldType {
+    /// A string.
+    String,
+    /// An integer.
+    Integer,
+    /// An integer or a floating-point number.
+    Number,
+    /// A boolean.
+    Boolean,
+    /// An RFC 3339 date, time or date-time, either as a native value or
+    /// as a string that parses as one.
+    DateTime,
+    /// An array.
+    Array,
+    /// A nested object.
+    Object,
+}
+
+impl FieldType {
+    /// Returns `true` if `value` is of this type.
+    #[must_use]
+    pub fn matches(self, value: &Value) -> bool {
+        match self {
+            Self::String => value.is_string(),
+            Self::Integer => value.is_integer(),
+            Self::Number => value.is_number(),
+            Self::Boolean => value.is_boolean(),
+            Self::DateTime => {
+                value.is_datetime()
+                    || value
+                        .as_str()
+                        .is_some_and(|s| s.parse::<Datetime>().is_ok())
+            }
+            Self::Array => value.is_array(),
+            Self::Object => value.is_object(),
+        }
+    }
+}
+
+impl fmt::Display for FieldType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::String => "string",
+            Self::Integer => "integer",
+            Self::Number => "number",
+            Self::Boolean => "boolean",
+            Self::DateTime => "datetime",
+            Self::Array => "array",
+            Self::Object => "object",
+        };
+        f.write_str(name)
+    }
+}
+
+/// A compiled regular expression used by [`FieldSchema::pattern`].
+///
+/// Patterns are unanchored: use `^` and `$` to match a whole string.
+/// They serialise as their source text.
+#[derive(Debug, Clone)]
+pub struct Pattern(Regex);
+
+impl Pattern {
+    /// Compiles a pattern.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ValidationError` if the pattern is not a valid
+    /// regular expression.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use frontmatter_gen::schema::Pattern;
+    ///
+    /// let pattern = Pattern::new("^[a-z]+$")?;
+    /// assert!(pattern.is_match("rust"));
+    /// assert!(!pattern.is_match("Rust"));
+    /// # Ok::<(), frontmatter_gen::Error>(())
+    /// ```
+    pub fn new(pattern: &str) -> Result<Self, Error> {
+        Regex::new(pattern).map(Self).map_err(|e| {
+            Error::ValidationError(format!("Invalid pattern: {e}"))
+        })
+    }
+
+    /// Returns the source text of the pattern.
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        self.0.as_str()
+    }
+
+    /// Returns `true` if the pattern matches anywhere in `text`.
+    #[must_use]
+    pub fn is_match(&self, text: &str) -> bool {
+        self.0.is_match(text)
+    }
+}
+
+impl PartialEq for Pattern {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_str() == other.as_str()
+    }
+}
+
+impl Serialize for Pattern {
+    fn serialize<S: Serializer>(
+        &self,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for Pattern {
+    fn deserialize<D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Self, D::Error> {
+        let source = String::deserialize(deserializer)?;
+        Regex::new(&source).map(Self).map_err(de::Error::custom)
+    }
+}
+
+/// The rule a [`Violation`] breaks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rule {
+    /// A required field is missing.
+    Required,
+    /// A value has the wrong type.
+    Type,
+    /// A value is not one of the allowed values.
+    Enum,
+    /// A string does not match the pattern.
+    Pattern,
+    /// A string or array is too short or too long.
+    Length,
+    /// A number is outside the allowed range.
+    Range,
+}
+
+/// A single schema violation.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Violation {
+    /// The path of the offending key, such as `author.name` or `tags[2]`.
+    pub path: String,
+    /// The rule that was broken.
+    pub rule: Rule,
+    /// A human-readable description of the problem.
+    pub message: String,
+}
+
+impl Violation {
+    fn new(path: &str, rule: Rule, message: String) -> Self {
+        Self {
+            path: path.to_string(),
+            rule,
+            message,
+        }
+    }
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.path, self.message)
+    }
+}
+
+impl Schema {
+    /// Parses a schema written in the given format.
+    ///
+    /// # Arguments
+    ///
+    /// * `content` - The schema source.
+    /// * `format` - The format the schema is written in.
+    ///
+    /// # Errors
+    ///
+    /// Returns a parse error for the given format if the schema is
+    /// malformed, declares unknown constraints or contains an invalid
+    /// pattern.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use frontmatter_gen::{schema::{FieldType, Schema}, Format};
+    ///
+    /// let schema = Schema::parse(
+    ///     r#"{"fields": {"draft": {"type": "boolean"}}}"#,
+    ///     Format::Json,
+    /// )?;
+    /// assert_eq!(schema.fields["draft"].field_type, Some(FieldType::Boolean));
+    /// # Ok::<(), frontmatter_gen::Error>(())
+    /// ```
+    pub fn parse(content: &str, format: Format) -> Result<Self, Error> {
+        Self::parse_with_options(
+            content,
+            format,
+            &ParseOptions::default(),
+        )
+    }
+
+    /// Parses a schema under the size and YAML expansion limits of
+    /// `options`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ContentTooLarge` if the schema is larger than
+    /// `options.max_size`, `Error::ExpansionLimitExceeded` if its YAML
+    /// aliases expand past a limit, and otherwise the errors of
+    /// [`Schema::parse`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use frontmatter_gen::{schema::Schema, Error, Format, ParseOptions};
+    ///
+    /// let options = ParseOptions::builder().max_size(16).build()?;
+    /// let result = Schema::parse_with_options(
+    ///     "fields:\n  title:\n    type: string\n",
+    ///     Format::Yaml,
+    ///     &options,
+    /// );
+    /// assert!(matches!(result, Err(Error::ContentTooLarge { .. })));
+    /// # Ok::<(), frontmatter_gen::Error>(())
+    /// ```
+    pub fn parse_with_options(
+        content: &str,
+        format: Format,
+        options: &ParseOptions,
+    ) -> Result<Self, Error> {
+        validate_input(content, options)?;
+
+        match format {
+            Format::Yaml => noyalib::from_str_with_config(
+                content,
+                &yaml_config(options),
+            )
+            .map_err(|e| yaml_error(e, options)),
+            Format::Toml => Ok(toml::from_str(content)?),
+            Format::Json => serde_json::from_str(content)
+                .map_err(|e| Error::JsonParseError(Arc::new(e))),
+            Format::Json5 => json5::from_str(content)
+                .map_err(|e| Error::Json5ParseError(Arc::new(e))),
+            Format::Ron => ron::from_str(content)
+                .map_err(|e| Error::RonParseError(Arc::new(e))),
+            // Header values are flat text, which cannot describe fields.
+            Format::Org | Format::AsciiDoc | Format::Unsupported => {
+                Err(Error::InvalidFormat)
+            }
+        }
+    }
+
+    /// Loads a schema from a file, choosing the format from its
+    /// extension (`.toml`, `.yaml`, `.yml`, `.json`, `.json5` or `.ron`).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read, has an unrecognised
+    /// extension or does not parse as a schema.
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, Error> {
+        Self::from_file_with_options(path, &ParseOptions::default())
+    }
+
+    /// Loads a schema from a file under the limits of `options`, as
+    /// [`Schema::parse_with_options`] parses it.
+    ///
+    /// # Errors
+    ///
+    /// Returns the errors of [`Schema::from_file`], and those of
+    /// [`Schema::parse_with_options`] against the limits of `options`.
+    pub fn from_file_with_options(
+        path: impl AsRef<Path>,
+        options: &ParseOptions,
+    ) -> Result<Self, Error> {
+        let path = path.as_ref();
+        let format = match path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .map(str::to_ascii_lowercase)
+            .as_deref()
+        {
+            Some("toml") => Format::Toml,
+            Some("yaml" | "yml") => Format::Yaml,
+            Some("json") => Format::Json,
+            Some("json5") => Format::Json5,
+            Some("ron") => Format::Ron,
+            _ => return Err(Error::InvalidFormat),
+        };
+        let content = std::fs::read_to_string(path)?;
+        Self::parse_with_options(&content, format, options)
+    }
+
+    /// Validates front matter against the schema.
+    ///
+    /// # Returns
+    ///
+    /// Every violation found, in schema order. An empty vector means the
+    /// front matter is valid.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use frontmatter_gen::{
+    ///     schema::{FieldSchema, FieldType, Rule, Schema},
+    ///     Frontmatter, Value,
+    /// };
+    ///
+    /// let mut schema = Schema::default();
+    /// schema.fields.insert(
+    ///     "weight".to_string(),
+    ///     FieldSchema {
+    ///         field_type: Some(FieldType::Integer),
+    ///         minimum: Some(0.0),
+    ///         ..FieldSchema::default()
+    ///     },
+    /// );
+    ///
+    /// let mut frontmatter = Frontmatter::new();
+    /// frontmatter.insert("weight".to_string(), Value::Integer(-1));
+    ///
+    /// let violations = schema.validate(&frontmatter);
+    /// assert_eq!(violations[0].rule, Rule::Range);
+    /// ```
+    #[must_use]
+    pub fn validate(
+        &self,
+        frontmatter: &Frontmatter,
+    ) -> Vec<Violation> {
+        let mut violations = Vec::new();
+        validate_fields(&self.fields, frontmatter, "", &mut violations);
+        violations
+    }
+
+    /// Infers a schema from a corpus of front matter documents.
+    ///
+    /// Every key seen in any document becomes a field. A field is
+    /// required when it appears in every document, and typed when all of
+    /// its non-null values share a type; a mix of integers and floats is
+    /// typed as `number`. A typed field that is null in any document is
+    /// nullable. Array items and nested objects are inferred the
+    /// same way. No enum, pattern, length or range constraints are
+    /// inferred.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use frontmatter_gen::{parser, schema::{FieldType, Schema}, Format};
+    ///
+    /// let docs = [
+    ///     parser::parse("title: One\nweight: 1", Format::Yaml)?,
+    ///     parser::parse("title: Two\nweight: 2.5\ndraft: true", Format::Yaml)?,
+    /// ];
+    /// let schema = Schema::infer(&docs);
+    ///
+    /// assert!(schema.fields["title"].required);
+    /// assert!(!schema.fields["draft"].required);
+    /// assert_eq!(schema.fields["weight"].field_type, Some(FieldType::Number));
+    /// # Ok::<(), frontmatter_gen::Error>(())
+    /// ```
+    #[must_use]
+    pub fn infer(documents: &[Frontmatter]) -> Self {
+        let documents: Vec<&Frontmatter> = documents.iter().collect();
+        Self {
+            fields: infer_fields(&documents),
+        }
+    }
+}
+
+/// Infers the field schemas of a set of objects.
+fn infer_fields(
+    objects: &[&Frontmatter],
+) -> IndexMap<String, FieldSchema> {
+    let mut seen: IndexMap<&str, Vec<&Value>> = IndexMap::new();
+    for object in objects {
+        for (key, value) in object.iter() {
+            seen.entry(key.as_str()).or_default().push(value);
+        }
+    }
+
+    seen.into_iter()
+        .map(|(key, values)| {
+            let field = FieldSchema {
+                required: values.len() == objects.len(),
+                ..infer_field(&values)
+            };
+            (key.to_string(), field)
+        })
+        .collect()
+}
+
+/// Infers the schema of a field from the values it takes.
+fn infer_field(values: &[&Value]) -> FieldSchema {
+    let values: Vec<&Value> = values
+        .iter()
+        .map(|value| match value {
+            Value::Tagged(_, inner) => inner.as_ref(),
+            _ => *value,
+        })
+        .collect();
+    let has_null = values.iter().any(|value| value.is_null());
+    let values: Vec<&Value> = values
+        .into_iter()
+        .filter(|value| !value.is_null())
+        .collect();
+
+    let field_type = values
+        .iter()
+        .map(|value| value_type(value))
+        .reduce(|a, b| match (a, b) {
+            _ if a == b => a,
+            (
+                Some(FieldType::Integer | FieldType::Number),
+                Some(FieldType::Integer | FieldType::Number),
+            ) => Some(FieldType::Number),
+            _ => None,
+        })
+        .flatten();
+
+    let mut field = FieldSchema {
+        field_type,
+        nullable: has_null && field_type.is_some(),
+        ..FieldSchema::default()
+    };
+    match field_type {
+        Some(FieldType::Array) => {
+            let items: Vec<&Value> = values
+                .iter()
+                .filter_map(|value| value.as_array())
+                .flatten()
+                .collect();
+            if !items.is_empty() {
+                field.items = Some(Box::new(infer_field(&items)));
+            }
+        }
+        Some(FieldType::Object) => {
+            let objects: Vec<&Frontmatter> = values
+                .iter()
+                .filter_map(|value| value.as_object())
+                .collect();
+            field.fields = Some(infer_fields(&objects));
+        }
+        _ => {}
+    }
+    field
+}
+
+/// Returns the field type a value belongs to, if any.
+const fn value_type(value: &Value) -> Option<FieldType> {
+    match value {
+        Value::String(_) => Some(FieldType::String),
+        Value::Integer(_) => Some(FieldType::Integer),
+        Value::Float(_) => Some(FieldType::Number),
+        Value::DateTime(_) => Some(FieldType::DateTime),
+        Value::Boolean(_) => Some(FieldType::Boolean),
+        Value::Array(_) => Some(FieldType::Array),
+        Value::Object(_) => Some(FieldType::Object),
+        Value::Null | Value::Tagged(_, _) => None,
+    }
+}
+
+/// Validates the declared fields of an object.
+fn validate_fields(
+    fields: &IndexMap<String, FieldSchema>,
+    object: &Frontmatter,
+    prefix: &str,
+    violations: &mut Vec<Violation>,
+) {
+    for (key, field) in fields {
+        let path = if prefix.is_empty() {
+            key.clone()
+        } else {
+            format!("{prefix}.{key}")
+        };
+        match object.get(key) {
+            Some(value) => {
+                validate_value(field, value, &path, violations);
+            }
+            None if field.required => violations.push(Violation::new(
+                &path,
+                Rule::Required,
+                "missing required field".to_string(),
+            )),
+            None => {}
+        }
+    }
+}
+
+/// Validates a single value against its field schema.
+fn validate_value(
+    field: &FieldSchema,
+    value: &Value,
+    path: &str,
+    violations: &mut Vec<Violation>,
+) {
+    // Tags carry no meaning for validation; check what they wrap.
+    let value = match value {
+        Value::Tagged(_, inner) => inner.as_ref(),
+        _ => value,
+    };
+    if field.nullable && value.is_null() {
+        return;
+    }
+
+    if let Some(field_type) = field.field_type {
+        if !field_type.matches(value) {
+            violations.push(Violation::new(
+                path,
+                Rule::Type,
+                format!(
+                    "expected {field_type}, found {}",
+                    value.type_name()
+                ),
+            ));
+            return;
+        }
+    }
+
+    if let Some(allowed) = &field.allowed {
+        if !allowed.contains(value) {
+            let allowed = allowed
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join(", ");
+            violations.push(Violation::new(
+                path,
+                Rule::Enum,
+                format!("{value} is not one of: {allowed}"),
+            ));
+        }
+    }
+
+    match value {
+        Value::String(s) => {
+            if let Some(pattern) = &field.pattern {
+                if !pattern.is_match(s) {
+                    violations.push(Violation::new(
+                        path,
+                        Rule::Pattern,
+                        format!(
+                            "{value} does not match pattern `{}`",
+                            pattern.as_str()
+                        ),
+                    ));
+                }
+            }
+            if let Some(message) =
+                check_length(field, s.chars().count(), "characters")
+            {
+                violations.push(Violation::new(
+                    path,
+                    Rule::Length,
+                    message,
+                ));
+            }
+        }
+        Value::Integer(_) | Value::Float(_) => {
+            let number = value.as_f64().unwrap_or_default();
+            if let Some(min) = field.minimum.filter(|min| number < *min)
+            {
+                violations.push(Violation::new(
+                    path,
+                    Rule::Range,
+                    format!(
+                        "{value} is less than the minimum of {min}"
+                    ),
+                ));
+            }
+            if let Some(max) = field.maximum.filter(|max| number > *max)
+            {
+                violations.push(Violation::new(
+                    path,
+                    Rule::Range,
+                    format!(
+                        "{value} is greater than the maximum of {max}"
+                    ),
+                ));
+            }
+        }
+        Value::Array(items) => {
+            if let Some(message) =
+                check_length(field, items.len(), "items")
+            {
+                violations.push(Violation::new(
+                    path,
+                    Rule::Length,
+                    message,
+                ));
+            }
+            if let Some(item_schema) = &field.items {
+                for (index, item) in items.iter().enumerate() {
+                    validate_value(
+                        item_schema,
+                        item,
+                        &format!("{path}[{index}]"),
+                        violations,
+                    );
+                }
+            }
+        }
+        Value::Object(object) => {
+            if let Some(fields) = &field.fields {
+                validate_fields(fields, object, path, violations);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Checks a string or array length against the field's bounds.
+fn check_length(
+    field: &FieldSchema,
+    length: usize,
+    unit: &str,
+) -> Option<String> {
+    if let Some(min) = field.min_length.filter(|min| length < *min) {
+        return Some(format!(
+            "has {length} {unit}, fewer than the minimum of {min}"
+        ));
+    }
+    if let Some(max) = field.max_length.filter(|max| length > *max) {
+        return Some(format!(
+            "has {length} {unit}, more than the maximum of {max}"
+        ));
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser;
+
+    const SCHEMA: &str = r#"
+[fields.title]
+type = "string"
+required = true
+min_length = 3
+max_length = 10
+
+[fields.status]
+enum = ["draft", "published"]
+
+[fields.weight]
+type = "number"
+minimum = 0
+maximum = 100
+
+[fields.tags]
+type = "array"
+max_length = 3
+items = { type = "string", pattern = "^[a-z]+$" }
+
+[fields.author]
+type = "object"
+
+[fields.author.fields.name]
+type = "string"
+required = true
+
+[fields.author.fields.email]
+type = "string"
+pattern = "@"
+"#;
+
+    fn schema() -> Schema {
+        Schema::parse(SCHEMA, Format::Toml).unwrap()
+    }
+
+    fn frontmatter(yaml: &str) -> Frontmatter {
+        parser::parse(yaml, Format::Yaml).unwrap()
+    }
+
+    fn paths(violations: &[Violation]) -> Vec<(&str, Rule)> {
+        violations
+            .iter()
+            .map(|v| (v.path.as_str(), v.rule))
+            .collect()
+    }
+
+    mod loading_tests {
+        use super::*;
+
+        #[test]
+        fn test_parse_toml_schema() {
+            let schema = schema();
+            let title = &schema.fields["title"];
+            assert_eq!(title.field_type, Some(FieldType::String));
+            assert!(title.required);
+            assert_eq!(title.max_length, Some(10));
+            let tags = schema.fields["tags"].items.as_ref().unwrap();
+            assert_eq!(
+                tags.pattern.as_ref().unwrap().as_str(),
+                "^[a-z]+$"
+            );
+        }
+
+        #[test]
+        fn test_parse_yaml_and_json_schema() {
+            let yaml = "fields:\n  title:\n    type: string\n    required: true\n";
+            let json = r#"{"fields": {"title": {"type": "string", "required": true}}}"#;
+            assert_eq!(
+                Schema::parse(yaml, Format::Yaml).unwrap(),
+                Schema::parse(json, Format::Json).unwrap()
+            );
+        }
+
+        #[test]
+        fn test_invalid_pattern_is_rejected() {
+            let result = Schema::parse(
+                "[fields.title]\npattern = \"(\"\n",
+                Format::Toml,
+            );
+            assert!(matches!(result, Err(Error::TomlParseError(_))));
+        }
+
+        #[test]
+        fn test_unknown_constraint_is_rejected() {
+            let result = Schema::parse(
+                "[fields.title]\nmax_len = 3\n",
+                Format::Toml,
+            );
+            assert!(result.is_err());
+        }
+
+        #[test]
+        fn test_from_file_uses_extension() {
+            let dir = tempfile::tempdir().unwrap();
+            let path = dir.path().join("schema.toml");
+            std::fs::write(&path, SCHEMA).unwrap();
+            assert_eq!(Schema::from_file(&path).unwrap(), schema());
+
+            let unknown = dir.path().join("schema.txt");
+            std::fs::write(&unknown, SCHEMA).unwrap();
+            assert!(matches!(
+                Schema::from_file(&unknown),
+                Err(Error::InvalidFormat)
+            ));
+
+            let options =
+                ParseOptions::builder().max_size(16).build().unwrap();
+            assert!(matches!(
+                Schema::from_file_with_options(&path, &options),
+                Err(Error::ContentTooLarge { .. })
+            ));
+        }
+    }
+
+    mod validation_tests {
+        use super::*;
+
+        #[test]
+        fn test_valid_frontmatter() {
+            let fm = frontmatter(
+                "title: Hello\nstatus: draft\nweight: 2.5\ntags: [rust]\nauthor:\n  name: Jane\n  email: jane@example.com\n",
+            );
+            assert!(schema().validate(&fm).is_empty());
+        }
+
+        #[test]
+        fn test_reports_every_violation_with_its_path() {
+            let fm = frontmatter(
+                "status: archived\nweight: 101\ntags: [rust, Serde, web, cli]\nauthor:\n  email: jane\n",
+            );
+            let violations = schema().validate(&fm);
+            assert_eq!(
+                paths(&violations),
+                vec![
+                    ("title", Rule::Required),
+                    ("status", Rule::Enum),
+                    ("weight", Rule::Range),
+                    ("tags", Rule::Length),
+                    ("tags[1]", Rule::Pattern),
+                    ("author.name", Rule::Required),
+                    ("author.email", Rule::Pattern),
+                ]
+            );
+        }
+
+        #[test]
+        fn test_type_mismatch_skips_other_checks() {
+            let fm = frontmatter("title: 42\nauthor: Jane\n");
+            let violations = schema().validate(&fm);
+            assert_eq!(
+                paths(&violations),
+                vec![("title", Rule::Type), ("author", Rule::Type)]
+            );
+            assert_eq!(
+                violations[0].to_string(),
+                "title: expected string, found integer"
+            );
+        }
+
+        #[test]
+        fn test_string_length_counts_characters() {
+            let fm = frontmatter("title: \"日本語\"\n");
+            assert!(schema().validate(&fm).is_empty());
+
+            let fm = frontmatter("title: \"日本\"\n");
+            assert_eq!(
+                paths(&schema().validate(&fm)),
+                vec![("title", Rule::Length)]
+            );
+        }
+
+        #[test]
+        fn test_datetime_type_accepts_rfc3339_strings() {
+            let field = FieldSchema {
+                field_type: Some(FieldType::DateTime),
+                ..FieldSchema::default()
+            };
+            let fm = frontmatter("a: 2024-01-01\nb: yesterday\n");
+            let mut violations = Vec::new();
+            validate_value(&field, &fm.0["a"], "a", &mut violations);
+            validate_value(&field, &fm.0["b"], "b", &mut violations);
+            assert_eq!(paths(&violations), vec![("b", Rule::Type)]);
+        }
+
+        #[test]
+        fn test_integer_type_rejects_float() {
+            let field = FieldSchema {
+                field_type: Some(FieldType::Integer),
+                ..FieldSchema::default()
+            };
+            let mut violations = Vec::new();
+            validate_value(
+                &field,
+                &Value::Float(1.5),
+                "n",
+                &mut violations,
+            );
+            assert_eq!(paths(&violations), vec![("n", Rule::Type)]);
+        }
+    }
+
+    mod inference_tests {
+        use super::*;
+
+        #[test]
+        fn test_infer_nested_structure() {
+            let docs = [
+                frontmatter("title: A\ntags: [x, y]\nauthor:\n  name: Jane\n  email: j@x\n"),
+                frontmatter("title: B\ntags: []\nauthor:\n  name: John\nextra: ~\n"),
+            ];
+            let schema = Schema::infer(&docs);
+
+            let tags = &schema.fields["tags"];
+            assert_eq!(tags.field_type, Some(FieldType::Array));
+            assert_eq!(
+                tags.items.as_ref().unwrap().field_type,
+                Some(FieldType::String)
+            );
+
+            let author =
+                schema.fields["author"].fields.as_ref().unwrap();
+            assert!(author["name"].required);
+            assert!(!author["email"].required);
+
+            let extra = &schema.fields["extra"];
+            assert_eq!(extra.field_type, None);
+            assert!(!extra.required);
+
+            // The corpus validates against the schema inferred from it
+            assert!(docs
+                .iter()
+                .all(|fm| schema.validate(fm).is_empty()));
+        }
+
+        #[test]
+        fn test_null_values_make_a_field_nullable() {
+            let docs =
+                [frontmatter("sub: a\n"), frontmatter("sub: ~\n")];
+            let schema = Schema::infer(&docs);
+
+            let sub = &schema.fields["sub"];
+            assert_eq!(sub.field_type, Some(FieldType::String));
+            assert!(sub.nullable);
+            assert!(sub.required);
+            assert!(docs
+                .iter()
+                .all(|fm| schema.validate(fm).is_empty()));
+            assert_eq!(
+                schema.validate(&frontmatter("sub: 1\n"))[0].rule,
+                Rule::Type
+            );
+        }
+
+        #[test]
+        fn test_conflicting_types_are_left_open() {
+            let docs =
+                [frontmatter("id: 1\n"), frontmatter("id: one\n")];
+            assert_eq!(
+                Schema::infer(&docs).fields["id"].field_type,
+                None
+            );
+        }
+    }
+}
@@ -0,0 +1,925 @@
+// security.rs
+
+//! # Security Module
+//!
+//! This module checks the values of parsed front matter against a
+//! [`SecurityPolicy`]. Every rule is opt-in and looks only at the front
+//! matter, never at the body, so a Markdown link such as
+//! `[x](../other.md)` is never a problem.
+//!
+//! The rules are:
+//!
+//! - `path-traversal`: no `..` path component in the values of
+//!   path-like keys, such as `image`, `template` or `cover_path`
+//! - `null-byte`: no null byte in any key or string value
+//! - `max-string-length=N`: no string value longer than `N` bytes
+//! - `yaml-tag`: no tagged YAML value, such as `!include file.yml`
+//! - `yaml-anchor`: no YAML anchor (`&name`) or alias (`*name`)
+//!
+//! Each rejection names the key and the rule that triggered it.
+//!
+//! ## Usage Example
+//!
+//! ```rust
+//! use frontmatter_gen::{security::{PolicyRule, SecurityPolicy}, Error, Format, parser};
+//!
+//! let raw = "title: Post\nimage: ../../etc/passwd\n";
+//! let frontmatter = parser::parse(raw, Format::Yaml)?;
+//!
+//! let policy: SecurityPolicy = "path-traversal,null-byte".parse()?;
+//! match policy.check(raw, Format::Yaml, &frontmatter) {
+//!     Err(Error::PolicyViolation { key, rule, .. }) => {
+//!         assert_eq!(key, "image");
+//!         assert_eq!(rule, PolicyRule::PathTraversal);
+//!     }
+//!     other => panic!("expected a violation, got {other:?}"),
+//! }
+//! # Ok::<(), frontmatter_gen::Error>(())
+//! ```
+
+use std::{
+    cell::OnceCell, fmt, num::NonZeroUsize, ops::Range, str::FromStr,
+};
+
+use noyalib::cst::{parse_document, Document as YamlDocument};
+use toml_edit::Document as TomlDocument;
+
+use crate::{
+    error::Error,
+    path::{self, Segment},
+    types::{Format, Frontmatter, Value},
+};
+
+/// Key names, or endings of key names, whose values are paths.
+///
+/// A key is path-like if its lowercase name, without a plural `s`, ends
+/// with one of these, so `image`, `cover_image` and `imagePaths` all
+/// are.
+pub const PATH_LIKE_KEYS: &[&str] = &[
+    "path",
+    "file",
+    "dir",
+    "directory",
+    "folder",
+    "src",
+    "href",
+    "url",
+    "uri",
+    "link",
+    "image",
+    "img",
+    "icon",
+    "logo",
+    "cover",
+    "thumbnail",
+    "template",
+    "layout",
+    "include",
+    "permalink",
+    "asset",
+];
+
+/// The longest string value [`SecurityPolicy::strict`] allows, in bytes.
+pub const DEFAULT_MAX_STRING_LENGTH: NonZeroUsize =
+    match NonZeroUsize::new(16 * 1024) {
+        Some(length) => length,
+        None => unreachable!(),
+    };
+
+/// A rule of a [`SecurityPolicy`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum PolicyRule {
+    /// A path-like key holds a path that climbs out with `..`
+    /// (`path-traversal`).
+    PathTraversal,
+    /// A key or string value contains a null byte (`null-byte`).
+    NullByte,
+    /// A string value is longer than the limit (`max-string-length`).
+    MaxStringLength,
+    /// A YAML value carries a tag (`yaml-tag`).
+    YamlTag,
+    /// The YAML defines an anchor or uses an alias (`yaml-anchor`).
+    YamlAnchor,
+}
+
+impl PolicyRule {
+    /// Returns the name of the rule, as accepted by
+    /// [`SecurityPolicy::from_str`].
+    #[must_use]
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            PolicyRule::PathTraversal => "path-traversal",
+            PolicyRule::NullByte => "null-byte",
+            PolicyRule::MaxStringLength => "max-string-length",
+            PolicyRule::YamlTag => "yaml-tag",
+            PolicyRule::YamlAnchor => "yaml-anchor",
+        }
+    }
+}
+
+impl fmt::Display for PolicyRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Rules that front matter values must follow.
+///
+/// The default policy has every rule turned off. Turn rules on one by
+/// one, start from [`SecurityPolicy::strict`], or parse a
+/// comma-separated list of rule names such as
+/// `"path-traversal,max-string-length=4096"`; `strict` and `none` are
+/// also accepted.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SecurityPolicy {
+    /// Reject `..` path components in the values of path-like keys
+    pub deny_path_traversal: bool,
+    /// Reject null bytes in keys and string values
+    pub deny_null_bytes: bool,
+    /// Reject string values longer than this many bytes
+    pub max_string_length: Option<NonZeroUsize>,
+    /// Reject tagged YAML values
+    pub deny_yaml_tags: bool,
+    /// Reject YAML anchors and aliases
+    pub deny_yaml_anchors: bool,
+}
+
+/// A value that broke a rule of a [`SecurityPolicy`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Violation {
+    /// The path of the offending key, such as `image` or `assets[0]`,
+    /// or `.` for the front matter itself
+    pub key: String,
+    /// The rule that was broken
+    pub rule: PolicyRule,
+    /// What was wrong with the value
+    pub message: String,
+    /// The byte offset of the problem in the raw front matter, when it
+    /// can be found
+    pub offset: Option<usize>,
+}
+
+impl From<Violation> for Error {
+    fn from(violation: Violation) -> Self {
+        Error::PolicyViolation {
+            key: violation.key,
+            rule: violation.rule,
+            message: violation.message,
+        }
+    }
+}
+
+impl SecurityPolicy {
+    /// Returns a policy with every rule turned on, and string values
+    /// limited to [`DEFAULT_MAX_STRING_LENGTH`] bytes.
+    #[must_use]
+    pub const fn strict() -> Self {
+        Self {
+            deny_path_traversal: true,
+            deny_null_bytes: true,
+            max_string_length: Some(DEFAULT_MAX_STRING_LENGTH),
+            deny_yaml_tags: true,
+            deny_yaml_anchors: true,
+        }
+    }
+
+    /// Returns whether no rule is turned on.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Checks parsed front matter, stopping at the first violation.
+    ///
+    /// # Arguments
+    ///
+    /// * `raw` - The raw front matter `frontmatter` was parsed from,
+    ///   used to find YAML anchors and aliases.
+    /// * `format` - The format of `raw`.
+    /// * `frontmatter` - The parsed front matter.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::PolicyViolation` naming the key and rule of the
+    /// first violation.
+    pub fn check(
+        &self,
+        raw: &str,
+        format: Format,
+        frontmatter: &Frontmatter,
+    ) -> Result<(), Error> {
+        match self
+            .violations(raw, format, frontmatter)
+            .into_iter()
+            .next()
+        {
+            Some(violation) => {
+                log::warn!(
+                    "Security policy rejected `{}`: {}",
+                    violation.key,
+                    violation.message
+                );
+                Err(violation.into())
+            }
+            None => Ok(()),
+        }
+    }
+
+    /// Returns every violation in parsed front matter, values first in
+    /// document order, then YAML anchors and aliases.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use frontmatter_gen::{parser, security::{PolicyRule, SecurityPolicy}, Format};
+    ///
+    /// let raw = "a: &x 1\nb: *x\nc: !secret 2\n";
+    /// let frontmatter = parser::parse(raw, Format::Yaml)?;
+    ///
+    /// let violations = SecurityPolicy::strict().violations(raw, Format::Yaml, &frontmatter);
+    /// let found: Vec<_> = violations.iter().map(|v| (v.key.as_str(), v.rule)).collect();
+    /// assert_eq!(found, [
+    ///     ("c", PolicyRule::YamlTag),
+    ///     ("a", PolicyRule::YamlAnchor),
+    ///     ("b", PolicyRule::YamlAnchor),
+    /// ]);
+    /// # Ok::<(), frontmatter_gen::Error>(())
+    /// ```
+    #[must_use]
+    pub fn violations(
+        &self,
+        raw: &str,
+        format: Format,
+        frontmatter: &Frontmatter,
+    ) -> Vec<Violation> {
+        let mut violations = Vec::new();
+        if self.is_empty() {
+            return violations;
+        }
+
+        let source = Source::new(raw, format);
+        for (key, value) in frontmatter.iter() {
+            self.check_key(&source, key, key, &mut violations);
+            self.check_value(
+                &source,
+                value,
+                key,
+                is_path_like(key),
+                &mut violations,
+            );
+        }
+        if self.deny_yaml_anchors && format == Format::Yaml {
+            anchor_violations(&source, frontmatter, &mut violations);
+        }
+
+        violations
+    }
+
+    /// Checks the name of a key.
+    fn check_key(
+        &self,
+        source: &Source<'_>,
+        key: &str,
+        path: &str,
+        violations: &mut Vec<Violation>,
+    ) {
+        if self.deny_null_bytes && key.contains('\0') {
+            violations.push(Violation {
+                key: path.to_string(),
+                rule: PolicyRule::NullByte,
+                message: "Key contains a null byte".to_string(),
+                offset: source.find(path, "\0"),
+            });
+        }
+    }
+
+    /// Checks a value and everything nested in it.
+    fn check_value(
+        &self,
+        source: &Source<'_>,
+        value: &Value,
+        path: &str,
+        path_like: bool,
+        violations: &mut Vec<Violation>,
+    ) {
+        match value {
+            Value::String(s) => {
+                self.check_string(
+                    source, s, path, path_like, violations,
+                );
+            }
+            Value::Array(items) => {
+                for (index, item) in items.iter().enumerate() {
+                    self.check_value(
+                        source,
+                        item,
+                        &format!("{path}[{index}]"),
+                        path_like,
+                        violations,
+                    );
+                }
+            }
+            Value::Object(object) => {
+                for (key, item) in object.iter() {
+                    let item_path = format!("{path}.{key}");
+                    self.check_key(source, key, &item_path, violations);
+                    self.check_value(
+                        source,
+                        item,
+                        &item_path,
+                        is_path_like(key),
+                        violations,
+                    );
+                }
+            }
+            Value::Tagged(tag, inner) => {
+                if self.deny_yaml_tags {
+                    violations.push(Violation {
+                        key: path.to_string(),
+                        rule: PolicyRule::YamlTag,
+                        message: format!("Value has the tag `{tag}`"),
+                        offset: source.find(path, tag),
+                    });
+                }
+                self.check_value(
+                    source, inner, path, path_like, violations,
+                );
+            }
+            _ => {}
+        }
+    }
+
+    /// Checks a string value.
+    fn check_string(
+        &self,
+        source: &Source<'_>,
+        s: &str,
+        path: &str,
+        path_like: bool,
+        violations: &mut Vec<Violation>,
+    ) {
+        if self.deny_path_traversal && path_like && is_traversal(s) {
+            violations.push(Violation {
+                key: path.to_string(),
+                rule: PolicyRule::PathTraversal,
+                message: format!("Path `{s}` climbs out with `..`"),
+                offset: source.find(path, s),
+            });
+        }
+        if self.deny_null_bytes && s.contains('\0') {
+            violations.push(Violation {
+                key: path.to_string(),
+                rule: PolicyRule::NullByte,
+                message: "Value contains a null byte".to_string(),
+                offset: source.find(path, "\0"),
+            });
+        }
+        if let Some(max) = self.max_string_length {
+            if s.len() > max.get() {
+                violations.push(Violation {
+                    key: path.to_string(),
+                    rule: PolicyRule::MaxStringLength,
+                    message: format!(
+                        "Value is {} bytes. The maximum allowed is {max}.",
+                        s.len()
+                    ),
+                    offset: None,
+                });
+            }
+        }
+    }
+}
+
+impl FromStr for SecurityPolicy {
+    type Err = Error;
+
+    /// Parses a comma-separated list of rule names, such as
+    /// `path-traversal,null-byte,max-string-length=4096`.
+    ///
+    /// `strict` turns every rule on and `none` turns them all off;
+    /// later names add to earlier ones.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut policy = Self::default();
+        for name in
+            s.split(',').map(str::trim).filter(|n| !n.is_empty())
+        {
+            match name.split_once('=') {
+                Some(("max-string-length", length)) => {
+                    let length = length
+                        .trim()
+                        .parse::<NonZeroUsize>()
+                        .map_err(|e| {
+                            Error::ValidationError(format!(
+                                "Invalid `max-string-length` of `{length}`: {e}"
+                            ))
+                        })?;
+                    policy.max_string_length = Some(length);
+                }
+                Some(_) => {
+                    return Err(unknown_rule(name));
+                }
+                None => match name {
+                    "strict" => policy = Self::strict(),
+                    "none" => policy = Self::default(),
+                    "path-traversal" => {
+                        policy.deny_path_traversal = true;
+                    }
+                    "null-byte" => policy.deny_null_bytes = true,
+                    "max-string-length" => {
+                        policy.max_string_length =
+                            Some(DEFAULT_MAX_STRING_LENGTH);
+                    }
+                    "yaml-tag" => policy.deny_yaml_tags = true,
+                    "yaml-anchor" => policy.deny_yaml_anchors = true,
+                    _ => return Err(unknown_rule(name)),
+                },
+            }
+        }
+        Ok(policy)
+    }
+}
+
+/// The error for a rule name [`SecurityPolicy::from_str`] does not
+/// know.
+fn unknown_rule(name: &str) -> Error {
+    Error::ValidationError(format!(
+        "Unknown security policy rule `{name}`. Expected `strict`, `none`, \
+         `path-traversal`, `null-byte`, `max-string-length[=N]`, \
+         `yaml-tag` or `yaml-anchor`."
+    ))
+}
+
+/// Returns whether `key` names a path, according to
+/// [`PATH_LIKE_KEYS`].
+fn is_path_like(key: &str) -> bool {
+    let key = key.to_lowercase();
+    let key = key.strip_suffix('s').unwrap_or(&key);
+    PATH_LIKE_KEYS.iter().any(|name| key.ends_with(name))
+}
+
+/// Returns whether a path has a `..` component.
+fn is_traversal(path: &str) -> bool {
+    path.split(['/', '\\']).any(|component| component == "..")
+}
+
+/// The raw front matter, with the spans its parser reports for YAML
+/// and TOML. The documents are parsed the first time they are needed.
+struct Source<'a> {
+    raw: &'a str,
+    format: Format,
+    yaml: OnceCell<Option<YamlDocument>>,
+    toml: OnceCell<Option<TomlDocument<&'a str>>>,
+}
+
+impl<'a> Source<'a> {
+    fn new(raw: &'a str, format: Format) -> Self {
+        Self {
+            raw,
+            format,
+            yaml: OnceCell::new(),
+            toml: OnceCell::new(),
+        }
+    }
+
+    fn yaml(&self) -> Option<&YamlDocument> {
+        if self.format != Format::Yaml {
+            return None;
+        }
+        self.yaml
+            .get_or_init(|| parse_document(self.raw).ok())
+            .as_ref()
+    }
+
+    fn toml(&self) -> Option<&TomlDocument<&'a str>> {
+        if self.format != Format::Toml {
+            return None;
+        }
+        self.toml
+            .get_or_init(|| TomlDocument::parse(self.raw).ok())
+            .as_ref()
+    }
+
+    /// Returns the offset of the first `needle` in the entry at `path`,
+    /// from its key to the end of its value.
+    fn find(&self, path: &str, needle: &str) -> Option<usize> {
+        let range = self
+            .entry(path)
+            .unwrap_or_else(|| self.after_keys(path)..self.raw.len());
+        let start = range.start;
+        self.raw.get(range)?.find(needle).map(|i| start + i)
+    }
+
+    /// Returns the span of the entry at `path` as its parser reports
+    /// it.
+    fn entry(&self, path: &str) -> Option<Range<usize>> {
+        if let Some(doc) = self.yaml() {
+            let (start, end) = doc.span_at(path)?;
+            let start =
+                doc.key_span(path).map_or(start, |(key, _)| key);
+            return Some(start..end);
+        }
+
+        let mut item = self.toml()?.as_item();
+        let mut key_start = None;
+        for segment in path::parse(path).ok()? {
+            item = match segment {
+                Segment::Key(key) => {
+                    let (key, item) =
+                        item.as_table_like()?.get_key_value(&key)?;
+                    key_start = key.span().map(|span| span.start);
+                    item
+                }
+                Segment::Index(index) => {
+                    key_start = None;
+                    item.get(index)?
+                }
+            };
+        }
+        let value = item.span();
+        let start = key_start
+            .or_else(|| value.as_ref().map(|span| span.start))?;
+        Some(start..value.map_or(self.raw.len(), |span| span.end))
+    }
+
+    /// Returns where the entry at `path` can start in formats without
+    /// spans: just after the last of its keys, each found after the one
+    /// before.
+    fn after_keys(&self, path: &str) -> usize {
+        let Ok(segments) = path::parse(path) else {
+            return 0;
+        };
+        let mut at = 0;
+        for segment in segments {
+            if let Segment::Key(key) = segment {
+                if let Some(end) = find_key(&self.raw[at..], &key) {
+                    at += end;
+                }
+            }
+        }
+        at
+    }
+}
+
+/// Returns the end of the first occurrence of `key` in `text` that is
+/// followed by a `:` or `=`, allowing for a closing quote.
+fn find_key(text: &str, key: &str) -> Option<usize> {
+    text.match_indices(key)
+        .map(|(i, _)| i + key.len())
+        .find(|&end| {
+            text[end..]
+                .trim_start_matches(['"', '\'', ' ', '\t'])
+                .starts_with([':', '='])
+        })
+}
+
+/// Reports every YAML anchor and alias in `raw`, under the key it
+/// belongs to.
+fn anchor_violations(
+    source: &Source<'_>,
+    frontmatter: &Frontmatter,
+    violations: &mut Vec<Violation>,
+) {
+    if !source.raw.contains(['&', '*']) {
+        return;
+    }
+    let Some(doc) = source.yaml() else {
+        return;
+    };
+
+    let anchors = doc.anchors().into_iter().map(|anchor| {
+        (anchor.mark_span.0, format!("Anchor `&{}`", anchor.name))
+    });
+    let aliases = doc.aliases().into_iter().map(|alias| {
+        (alias.mark_span.0, format!("Alias `*{}`", alias.name))
+    });
+    let mut marks: Vec<_> = anchors.chain(aliases).collect();
+    marks.sort_by_key(|&(offset, _)| offset);
+    if marks.is_empty() {
+        return;
+    }
+
+    let mut paths = Vec::new();
+    for (key, value) in frontmatter.iter() {
+        collect_paths(value, key.clone(), &mut paths);
+    }
+    for (offset, what) in marks {
+        violations.push(Violation {
+            key: owner(doc, &paths, offset),
+            rule: PolicyRule::YamlAnchor,
+            message: format!("{what} is not allowed"),
+            offset: Some(offset),
+        });
+    }
+}
+
+/// Lists the path of `value` and every value nested in it, parents
+/// first.
+fn collect_paths(value: &Value, path: String, paths: &mut Vec<String>) {
+    match value {
+        Value::Array(items) => {
+            paths.push(path.clone());
+            for (index, item) in items.iter().enumerate() {
+                collect_paths(item, format!("{path}[{index}]"), paths);
+            }
+        }
+        Value::Object(object) => {
+            paths.push(path.clone());
+            for (key, item) in object.iter() {
+                collect_paths(item, format!("{path}.{key}"), paths);
+            }
+        }
+        Value::Tagged(_, inner) => collect_paths(inner, path, paths),
+        _ => paths.push(path),
+    }
+}
+
+/// Returns the path of the key an anchor or alias at `offset` belongs
+/// to: the deepest value whose text holds it, or else the last key
+/// before it.
+///
+/// Aliases are not part of the text of the value they stand for, so
+/// they fall back to the key they follow.
+fn owner(
+    doc: &YamlDocument,
+    paths: &[String],
+    offset: usize,
+) -> String {
+    let mut holder = None;
+    let mut before: Option<(usize, &String)> = None;
+
+    for path in paths {
+        if doc
+            .span_at(path)
+            .is_some_and(|(start, end)| start <= offset && offset < end)
+        {
+            holder = Some(path);
+        }
+        if let Some((start, _)) = doc.key_span(path) {
+            if start < offset
+                && before.is_none_or(|(latest, _)| start > latest)
+            {
+                before = Some((start, path));
+            }
+        }
+    }
+
+    holder
+        .or(before.map(|(_, path)| path))
+        .map_or_else(|| ".".to_string(), Clone::clone)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser;
+
+    /// Returns the key and rule of every violation of `policy` in
+    /// `raw`.
+    fn violations(
+        policy: SecurityPolicy,
+        raw: &str,
+        format: Format,
+    ) -> Vec<(String, PolicyRule)> {
+        let frontmatter = parser::parse(raw, format).unwrap();
+        policy
+            .violations(raw, format, &frontmatter)
+            .into_iter()
+            .map(|violation| (violation.key, violation.rule))
+            .collect()
+    }
+
+    mod rule_tests {
+        use super::*;
+
+        #[test]
+        fn test_default_policy_allows_everything() {
+            let raw = "image: ../a.png\nbase: &b 1\nuse: *b\nt: !x 1\n";
+            assert!(violations(
+                SecurityPolicy::default(),
+                raw,
+                Format::Yaml
+            )
+            .is_empty());
+        }
+
+        #[test]
+        fn test_path_traversal_only_in_path_like_keys() {
+            let policy = SecurityPolicy {
+                deny_path_traversal: true,
+                ..SecurityPolicy::default()
+            };
+            let raw = r#"{
+                "title": "../not a path",
+                "image": "../a.png",
+                "seo": {"coverImage": "img/../../b.png"},
+                "includes": ["ok.md", "..\\up.md"],
+                "layout": "post..html"
+            }"#;
+
+            assert_eq!(
+                violations(policy, raw, Format::Json),
+                [
+                    ("image".to_string(), PolicyRule::PathTraversal),
+                    (
+                        "seo.coverImage".to_string(),
+                        PolicyRule::PathTraversal
+                    ),
+                    (
+                        "includes[1]".to_string(),
+                        PolicyRule::PathTraversal
+                    ),
+                ]
+            );
+        }
+
+        #[test]
+        fn test_null_bytes_in_keys_and_values() {
+            let policy = SecurityPolicy {
+                deny_null_bytes: true,
+                ..SecurityPolicy::default()
+            };
+            let raw =
+                "{\"a\": \"x\\u0000y\", \"b\": {\"k\\u0000\": 1}}";
+
+            assert_eq!(
+                violations(policy, raw, Format::Json),
+                [
+                    ("a".to_string(), PolicyRule::NullByte),
+                    ("b.k\0".to_string(), PolicyRule::NullByte),
+                ]
+            );
+        }
+
+        #[test]
+        fn test_max_string_length() {
+            let policy = SecurityPolicy {
+                max_string_length: NonZeroUsize::new(5),
+                ..SecurityPolicy::default()
+            };
+            let raw = "short = \"12345\"\nlong = [\"123456\"]\n";
+
+            assert_eq!(
+                violations(policy, raw, Format::Toml),
+                [("long[0]".to_string(), PolicyRule::MaxStringLength)]
+            );
+        }
+
+        #[test]
+        fn test_yaml_tags() {
+            let policy = SecurityPolicy {
+                deny_yaml_tags: true,
+                ..SecurityPolicy::default()
+            };
+            let raw = "a: !include other.yml\nb:\n  c: !!str 1\n";
+
+            assert_eq!(
+                violations(policy, raw, Format::Yaml),
+                [("a".to_string(), PolicyRule::YamlTag)]
+            );
+        }
+
+        #[test]
+        fn test_yaml_anchors_name_their_keys() {
+            let policy = SecurityPolicy {
+                deny_yaml_anchors: true,
+                ..SecurityPolicy::default()
+            };
+            let raw = "a: 1\nb:\n  c: &x 1\n  d: [1, &y 2]\nv: *x\nw: &m\n  k: 1\n";
+
+            assert_eq!(
+                violations(policy, raw, Format::Yaml),
+                [
+                    ("b.c".to_string(), PolicyRule::YamlAnchor),
+                    ("b.d".to_string(), PolicyRule::YamlAnchor),
+                    ("v".to_string(), PolicyRule::YamlAnchor),
+                    ("w".to_string(), PolicyRule::YamlAnchor),
+                ]
+            );
+        }
+
+        #[test]
+        fn test_yaml_anchor_rule_ignores_other_formats() {
+            let policy = SecurityPolicy {
+                deny_yaml_anchors: true,
+                ..SecurityPolicy::default()
+            };
+            assert!(violations(
+                policy,
+                "{\"a\": \"&x *y\"}",
+                Format::Json
+            )
+            .is_empty());
+        }
+
+        #[test]
+        fn test_offsets_point_into_the_offending_entry() {
+            let policy = SecurityPolicy::strict();
+            let cases = [
+                (
+                    "note: ../a.png\nimage: ../a.png\nt: !x 1\nu: !x 2\n",
+                    Format::Yaml,
+                ),
+                (
+                    "note = \"../a.png\"\nimage = \"../a.png\"\n",
+                    Format::Toml,
+                ),
+                (
+                    "{\"note\": \"../a.png\", \"image\": \"../a.png\"}",
+                    Format::Json,
+                ),
+            ];
+            for (raw, format) in cases {
+                let frontmatter = parser::parse(raw, format).unwrap();
+                let violations =
+                    policy.violations(raw, format, &frontmatter);
+                let offsets: Vec<_> = violations
+                    .iter()
+                    .map(|violation| {
+                        (violation.key.as_str(), violation.offset)
+                    })
+                    .collect();
+                let second = raw.rfind("../a.png");
+                match format {
+                    Format::Yaml => assert_eq!(
+                        offsets,
+                        [
+                            ("image", second),
+                            ("t", raw.find("!x")),
+                            ("u", raw.rfind("!x")),
+                        ]
+                    ),
+                    _ => assert_eq!(offsets, [("image", second)]),
+                }
+            }
+        }
+
+        #[test]
+        fn test_check_returns_first_violation() {
+            let raw = "image: ../a.png\nlogo: ../b.png\n";
+            let frontmatter = parser::parse(raw, Format::Yaml).unwrap();
+
+            let err = SecurityPolicy::strict()
+                .check(raw, Format::Yaml, &frontmatter)
+                .unwrap_err();
+
+            assert!(matches!(
+                &err,
+                Error::PolicyViolation { key, rule: PolicyRule::PathTraversal, .. }
+                    if key == "image"
+            ));
+            assert!(err.to_string().contains("`image`"));
+            assert!(err.to_string().contains("path-traversal"));
+        }
+    }
+
+    mod from_str_tests {
+        use super::*;
+
+        #[test]
+        fn test_parse_rule_list() {
+            let policy: SecurityPolicy =
+                "path-traversal, yaml-tag,max-string-length=10"
+                    .parse()
+                    .unwrap();
+
+            assert_eq!(
+                policy,
+                SecurityPolicy {
+                    deny_path_traversal: true,
+                    deny_yaml_tags: true,
+                    max_string_length: NonZeroUsize::new(10),
+                    ..SecurityPolicy::default()
+                }
+            );
+        }
+
+        #[test]
+        fn test_parse_strict_and_none() {
+            assert_eq!(
+                "strict".parse::<SecurityPolicy>().unwrap(),
+                SecurityPolicy::strict()
+            );
+            assert!("strict,none"
+                .parse::<SecurityPolicy>()
+                .unwrap()
+                .is_empty());
+            assert!("".parse::<SecurityPolicy>().unwrap().is_empty());
+        }
+
+        #[test]
+        fn test_parse_errors() {
+            for spec in
+                ["no-such-rule", "max-string-length=0", "null-byte=1"]
+            {
+                assert!(
+                    matches!(
+                        spec.parse::<SecurityPolicy>(),
+                        Err(Error::ValidationError(_))
+                    ),
+                    "{spec}"
+                );
+            }
+        }
+    }
+}
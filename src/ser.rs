@@ -0,0 +1,763 @@
+// ser.rs
+
+//! Serialisation of typed values into front matter.
+//!
+//! [`ValueSerializer`] is a [`serde::Serializer`] whose output is a
+//! `Value`, so front matter can be generated from any `T: Serialize`.
+//! [`Datetime`] fields become `Value::DateTime` rather than strings.
+
+use serde::ser::{self, Impossible, Serialize};
+
+use crate::{
+    error::Error,
+    types::{
+        Datetime, Frontmatter, Value, TAGGED_NAME, TAGGED_TAG_FIELD,
+        TAGGED_VALUE_FIELD, TOML_DATETIME_NAME,
+    },
+};
+
+impl ser::Error for Error {
+    fn custom<T: std::fmt::Display>(msg: T) -> Self {
+        Self::ConversionError(msg.to_string())
+    }
+}
+
+impl Frontmatter {
+    /// Builds front matter from a typed value.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ConversionError` if `value` does not serialise to
+    /// a map, if a map key is not a string, number, boolean or
+    /// character, or if the `Serialize` implementation fails.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use frontmatter_gen::{to_format, Format, Frontmatter};
+    /// use serde::Serialize;
+    ///
+    /// #[derive(Serialize)]
+    /// struct Post {
+    ///     title: String,
+    ///     draft: bool,
+    /// }
+    ///
+    /// let post = Post { title: "Hello".into(), draft: true };
+    /// let fm = Frontmatter::from_serialize(&post)?;
+    /// assert_eq!(to_format(&fm, Format::Toml)?, "title = \"Hello\"\ndraft = true\n");
+    /// # Ok::<(), frontmatter_gen::Error>(())
+    /// ```
+    pub fn from_serialize<T: Serialize + ?Sized>(
+        value: &T,
+    ) -> Result<Self, Error> {
+        match value.serialize(ValueSerializer)? {
+            Value::Object(obj) => Ok(*obj),
+            other => Err(Error::ConversionError(format!(
+                "front matter must serialise to a map, found {}",
+                other.type_name()
+            ))),
+        }
+    }
+}
+
+fn parse_datetime(s: &str) -> Result<Datetime, Error> {
+    s.parse().map_err(|e| {
+        Error::ConversionError(format!("invalid datetime `{s}`: {e}"))
+    })
+}
+
+/// A `Serializer` that produces a `Value`.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct ValueSerializer;
+
+impl ser::Serializer for ValueSerializer {
+    type Ok = Value;
+    type Error = Error;
+
+    type SerializeSeq = SerializeVec;
+    type SerializeTuple = SerializeVec;
+    type SerializeTupleStruct = SerializeVec;
+    type SerializeTupleVariant = SerializeVariant<SerializeVec>;
+    type SerializeMap = SerializeMap;
+    type SerializeStruct = SerializeStruct;
+    type SerializeStructVariant = SerializeVariant<SerializeMap>;
+
+    fn serialize_bool(self, v: bool) -> Result<Value, Error> {
+        Ok(Value::Boolean(v))
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<Value, Error> {
+        self.serialize_i64(v.into())
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<Value, Error> {
+        self.serialize_i64(v.into())
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<Value, Error> {
+        self.serialize_i64(v.into())
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<Value, Error> {
+        Ok(Value::Integer(v))
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<Value, Error> {
+        self.serialize_i64(v.into())
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<Value, Error> {
+        self.serialize_i64(v.into())
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<Value, Error> {
+        self.serialize_i64(v.into())
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<Value, Error> {
+        // Matches JSON parsing, where integers beyond `i64` become floats.
+        #[allow(clippy::cast_precision_loss)]
+        Ok(i64::try_from(v)
+            .map_or(Value::Float(v as f64), Value::Integer))
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<Value, Error> {
+        self.serialize_f64(v.into())
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<Value, Error> {
+        Ok(Value::Float(v))
+    }
+
+    fn serialize_char(self, v: char) -> Result<Value, Error> {
+        Ok(Value::String(v.to_string()))
+    }
+
+    fn serialize_str(self, v: &str) -> Result<Value, Error> {
+        Ok(Value::String(v.to_string()))
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<Value, Error> {
+        Ok(v.iter().map(|&b| Value::Integer(b.into())).collect())
+    }
+
+    fn serialize_none(self) -> Result<Value, Error> {
+        Ok(Value::Null)
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(
+        self,
+        value: &T,
+    ) -> Result<Value, Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<Value, Error> {
+        Ok(Value::Null)
+    }
+
+    fn serialize_unit_struct(
+        self,
+        _name: &'static str,
+    ) -> Result<Value, Error> {
+        Ok(Value::Null)
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+    ) -> Result<Value, Error> {
+        Ok(Value::String(variant.to_string()))
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<Value, Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<Value, Error> {
+        let mut fm = Frontmatter::new();
+        let _ = fm.insert(variant.to_string(), value.serialize(self)?);
+        Ok(Value::Object(Box::new(fm)))
+    }
+
+    fn serialize_seq(
+        self,
+        len: Option<usize>,
+    ) -> Result<SerializeVec, Error> {
+        Ok(SerializeVec(Vec::with_capacity(len.unwrap_or_default())))
+    }
+
+    fn serialize_tuple(
+        self,
+        len: usize,
+    ) -> Result<SerializeVec, Error> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> Result<SerializeVec, Error> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleVariant, Error> {
+        Ok(SerializeVariant {
+            variant,
+            inner: self.serialize_seq(Some(len))?,
+        })
+    }
+
+    fn serialize_map(
+        self,
+        _len: Option<usize>,
+    ) -> Result<SerializeMap, Error> {
+        Ok(SerializeMap {
+            map: Frontmatter::new(),
+            next_key: None,
+        })
+    }
+
+    fn serialize_struct(
+        self,
+        name: &'static str,
+        _len: usize,
+    ) -> Result<SerializeStruct, Error> {
+        match name {
+            TOML_DATETIME_NAME => Ok(SerializeStruct::Datetime(None)),
+            TAGGED_NAME => {
+                Ok(SerializeStruct::Tagged(self.serialize_map(None)?))
+            }
+            _ => Ok(SerializeStruct::Map(self.serialize_map(None)?)),
+        }
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant, Error> {
+        Ok(SerializeVariant {
+            variant,
+            inner: self.serialize_map(None)?,
+        })
+    }
+}
+
+/// Collects the elements of a sequence or tuple.
+#[derive(Debug)]
+pub(crate) struct SerializeVec(Vec<Value>);
+
+impl ser::SerializeSeq for SerializeVec {
+    type Ok = Value;
+    type Error = Error;
+
+    fn serialize_element<T: Serialize + ?Sized>(
+        &mut self,
+        value: &T,
+    ) -> Result<(), Error> {
+        self.0.push(value.serialize(ValueSerializer)?);
+        Ok(())
+    }
+
+    fn end(self) -> Result<Value, Error> {
+        Ok(Value::Array(self.0))
+    }
+}
+
+impl ser::SerializeTuple for SerializeVec {
+    type Ok = Value;
+    type Error = Error;
+
+    fn serialize_element<T: Serialize + ?Sized>(
+        &mut self,
+        value: &T,
+    ) -> Result<(), Error> {
+        ser::SerializeSeq::serialize_element(self, value)
+    }
+
+    fn end(self) -> Result<Value, Error> {
+        ser::SerializeSeq::end(self)
+    }
+}
+
+impl ser::SerializeTupleStruct for SerializeVec {
+    type Ok = Value;
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        value: &T,
+    ) -> Result<(), Error> {
+        ser::SerializeSeq::serialize_element(self, value)
+    }
+
+    fn end(self) -> Result<Value, Error> {
+        ser::SerializeSeq::end(self)
+    }
+}
+
+/// Collects the entries of a map or struct.
+#[derive(Debug)]
+pub(crate) struct SerializeMap {
+    map: Frontmatter,
+    next_key: Option<String>,
+}
+
+impl ser::SerializeMap for SerializeMap {
+    type Ok = Value;
+    type Error = Error;
+
+    fn serialize_key<T: Serialize + ?Sized>(
+        &mut self,
+        key: &T,
+    ) -> Result<(), Error> {
+        self.next_key = Some(key.serialize(KeySerializer)?);
+        Ok(())
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(
+        &mut self,
+        value: &T,
+    ) -> Result<(), Error> {
+        let key = self.next_key.take().ok_or_else(|| {
+            Error::ConversionError(
+                "map value serialised before its key".to_string(),
+            )
+        })?;
+        let _ = self.map.insert(key, value.serialize(ValueSerializer)?);
+        Ok(())
+    }
+
+    fn end(self) -> Result<Value, Error> {
+        Ok(Value::Object(Box::new(self.map)))
+    }
+}
+
+/// Collects the fields of a struct, the string form of a `Datetime`,
+/// or the tag and value of a `Value::Tagged`.
+#[derive(Debug)]
+pub(crate) enum SerializeStruct {
+    /// An ordinary struct, written as an object.
+    Map(SerializeMap),
+    /// A `Datetime`, whose only field is its string form.
+    Datetime(Option<Datetime>),
+    /// A `Value::Tagged`, whose fields are its tag and value.
+    Tagged(SerializeMap),
+}
+
+impl ser::SerializeStruct for SerializeStruct {
+    type Ok = Value;
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        match self {
+            Self::Map(map) | Self::Tagged(map) => {
+                ser::SerializeMap::serialize_entry(map, key, value)
+            }
+            Self::Datetime(dt) => {
+                let Value::String(s) =
+                    value.serialize(ValueSerializer)?
+                else {
+                    return Err(Error::ConversionError(
+                        "datetime did not serialise as a string"
+                            .to_string(),
+                    ));
+                };
+                *dt = Some(parse_datetime(&s)?);
+                Ok(())
+            }
+        }
+    }
+
+    fn end(self) -> Result<Value, Error> {
+        match self {
+            Self::Map(map) => ser::SerializeMap::end(map),
+            Self::Datetime(Some(dt)) => Ok(Value::DateTime(dt)),
+            Self::Datetime(None) => Err(Error::ConversionError(
+                "datetime serialised without a value".to_string(),
+            )),
+            Self::Tagged(mut map) => match (
+                map.map.remove(TAGGED_TAG_FIELD),
+                map.map.remove(TAGGED_VALUE_FIELD),
+            ) {
+                (Some(Value::String(tag)), Some(value)) => {
+                    Ok(Value::Tagged(tag, Box::new(value)))
+                }
+                _ => Err(Error::ConversionError(
+                    "tagged value serialised without a tag and value"
+                        .to_string(),
+                )),
+            },
+        }
+    }
+}
+
+/// Wraps the content of an enum variant in a single-key object.
+#[derive(Debug)]
+pub(crate) struct SerializeVariant<S> {
+    variant: &'static str,
+    inner: S,
+}
+
+impl SerializeVariant<SerializeVec> {
+    fn finish(self) -> Value {
+        let mut fm = Frontmatter::new();
+        let _ = fm.insert(
+            self.variant.to_string(),
+            Value::Array(self.inner.0),
+        );
+        Value::Object(Box::new(fm))
+    }
+}
+
+impl ser::SerializeTupleVariant for SerializeVariant<SerializeVec> {
+    type Ok = Value;
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        value: &T,
+    ) -> Result<(), Error> {
+        ser::SerializeSeq::serialize_element(&mut self.inner, value)
+    }
+
+    fn end(self) -> Result<Value, Error> {
+        Ok(self.finish())
+    }
+}
+
+impl ser::SerializeStructVariant for SerializeVariant<SerializeMap> {
+    type Ok = Value;
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        ser::SerializeMap::serialize_entry(&mut self.inner, key, value)
+    }
+
+    fn end(self) -> Result<Value, Error> {
+        let mut fm = Frontmatter::new();
+        let _ = fm.insert(
+            self.variant.to_string(),
+            Value::Object(Box::new(self.inner.map)),
+        );
+        Ok(Value::Object(Box::new(fm)))
+    }
+}
+
+/// Serialises map keys, which must be strings or scalars.
+#[derive(Clone, Copy, Debug)]
+struct KeySerializer;
+
+impl KeySerializer {
+    fn unsupported(kind: &str) -> Error {
+        Error::ConversionError(format!(
+            "front matter keys must be strings, found {kind}"
+        ))
+    }
+}
+
+impl ser::Serializer for KeySerializer {
+    type Ok = String;
+    type Error = Error;
+
+    type SerializeSeq = Impossible<String, Error>;
+    type SerializeTuple = Impossible<String, Error>;
+    type SerializeTupleStruct = Impossible<String, Error>;
+    type SerializeTupleVariant = Impossible<String, Error>;
+    type SerializeMap = Impossible<String, Error>;
+    type SerializeStruct = Impossible<String, Error>;
+    type SerializeStructVariant = Impossible<String, Error>;
+
+    fn serialize_bool(self, v: bool) -> Result<String, Error> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<String, Error> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<String, Error> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<String, Error> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<String, Error> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<String, Error> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<String, Error> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<String, Error> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<String, Error> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_f32(self, _v: f32) -> Result<String, Error> {
+        Err(Self::unsupported("a float"))
+    }
+
+    fn serialize_f64(self, _v: f64) -> Result<String, Error> {
+        Err(Self::unsupported("a float"))
+    }
+
+    fn serialize_char(self, v: char) -> Result<String, Error> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_str(self, v: &str) -> Result<String, Error> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_bytes(self, _v: &[u8]) -> Result<String, Error> {
+        Err(Self::unsupported("bytes"))
+    }
+
+    fn serialize_none(self) -> Result<String, Error> {
+        Err(Self::unsupported("null"))
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(
+        self,
+        value: &T,
+    ) -> Result<String, Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<String, Error> {
+        Err(Self::unsupported("null"))
+    }
+
+    fn serialize_unit_struct(
+        self,
+        _name: &'static str,
+    ) -> Result<String, Error> {
+        Err(Self::unsupported("null"))
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+    ) -> Result<String, Error> {
+        Ok(variant.to_string())
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<String, Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        _index: u32,
+        _variant: &'static str,
+        _value: &T,
+    ) -> Result<String, Error> {
+        Err(Self::unsupported("an enum variant with data"))
+    }
+
+    fn serialize_seq(
+        self,
+        _len: Option<usize>,
+    ) -> Result<Self::SerializeSeq, Error> {
+        Err(Self::unsupported("an array"))
+    }
+
+    fn serialize_tuple(
+        self,
+        _len: usize,
+    ) -> Result<Self::SerializeTuple, Error> {
+        Err(Self::unsupported("an array"))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleStruct, Error> {
+        Err(Self::unsupported("an array"))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleVariant, Error> {
+        Err(Self::unsupported("an enum variant with data"))
+    }
+
+    fn serialize_map(
+        self,
+        _len: Option<usize>,
+    ) -> Result<Self::SerializeMap, Error> {
+        Err(Self::unsupported("a map"))
+    }
+
+    fn serialize_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStruct, Error> {
+        Err(Self::unsupported("a struct"))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant, Error> {
+        Err(Self::unsupported("an enum variant with data"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::{parse, to_string};
+    use crate::types::Format;
+    use serde::{Deserialize, Serialize};
+    use std::collections::BTreeMap;
+
+    #[derive(Debug, Serialize, Deserialize, PartialEq)]
+    struct Post {
+        title: String,
+        weight: u8,
+        rating: f32,
+        date: Datetime,
+        summary: Option<String>,
+        tags: Vec<String>,
+        kind: Kind,
+        extra: BTreeMap<String, bool>,
+    }
+
+    #[derive(Debug, Serialize, Deserialize, PartialEq)]
+    enum Kind {
+        Article,
+        Series { part: i64 },
+        Link(String),
+    }
+
+    fn post() -> Post {
+        Post {
+            title: "Hello".to_string(),
+            weight: 3,
+            rating: 4.5,
+            date: "2024-01-02T03:04:05Z".parse().unwrap(),
+            summary: None,
+            tags: vec!["rust".to_string()],
+            kind: Kind::Series { part: 2 },
+            extra: BTreeMap::from([("featured".to_string(), true)]),
+        }
+    }
+
+    #[test]
+    fn test_from_serialize_builds_values() {
+        let fm = Frontmatter::from_serialize(&post()).unwrap();
+        let keys: Vec<&str> = fm.0.keys().map(String::as_str).collect();
+        assert_eq!(
+            keys,
+            [
+                "title", "weight", "rating", "date", "summary", "tags",
+                "kind", "extra"
+            ]
+        );
+        assert_eq!(fm.get("weight"), Some(&Value::Integer(3)));
+        assert_eq!(fm.get("rating"), Some(&Value::Float(4.5)));
+        assert!(fm.get("date").unwrap().is_datetime());
+        assert!(fm.is_null("summary"));
+        assert_eq!(
+            fm.get("kind").unwrap().as_object().unwrap().get("Series"),
+            Some(&Value::Object(Box::new(Frontmatter::from_iter([(
+                "part".to_string(),
+                Value::Integer(2)
+            )]))))
+        );
+
+        let numeric_keys =
+            Frontmatter::from_serialize(&BTreeMap::from([(7, true)]))
+                .unwrap();
+        assert_eq!(numeric_keys.get("7"), Some(&Value::Boolean(true)));
+    }
+
+    #[test]
+    fn test_typed_round_trip_through_each_format() {
+        let mut original = post();
+        original.kind = Kind::Link("https://example.com".to_string());
+        original.summary = Some("Short".to_string());
+        let fm = Frontmatter::from_serialize(&original).unwrap();
+
+        for format in [Format::Yaml, Format::Toml, Format::Json] {
+            let text = to_string(&fm, format).unwrap();
+            let parsed = parse(&text, format).unwrap();
+            let decoded: Post = parsed.deserialize_into().unwrap();
+            assert_eq!(decoded, original, "{format} round trip");
+        }
+    }
+
+    #[test]
+    fn test_from_serialize_rejects_non_maps() {
+        let err = Frontmatter::from_serialize(&[1, 2]).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Failed to convert front matter: front matter must serialise to a map, found array"
+        );
+
+        let map = BTreeMap::from([(vec![1], true)]);
+        assert!(matches!(
+            Frontmatter::from_serialize(&map),
+            Err(Error::ConversionError(_))
+        ));
+    }
+}
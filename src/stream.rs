@@ -0,0 +1,475 @@
+// stream.rs
+
+//! # Stream Module
+//!
+//! This module extracts front matter from a reader instead of a string,
+//! so a large Markdown or MDX file never has to be read into memory as a
+//! whole. Reading stops at the end of the line that closes the front
+//! matter, and the body is handed back as a reader that starts there.
+//!
+//! `ParseOptions::max_size` limits the bytes read for the front matter,
+//! fences included; the body is not counted and is never read. A
+//! document whose front matter is never closed is read no further than
+//! the limit.
+//!
+//! ## Usage Example
+//!
+//! ```rust
+//! use frontmatter_gen::extract_from_reader;
+//! use std::io::Read;
+//!
+//! let file = "---\ntitle: My Post\n---\nBody of any length";
+//! let (frontmatter, mut body) = extract_from_reader(file.as_bytes())?;
+//! assert_eq!(frontmatter.get("title").and_then(|v| v.as_str()), Some("My Post"));
+//!
+//! let mut rest = String::new();
+//! body.read_to_string(&mut rest)?;
+//! assert_eq!(rest, "Body of any length");
+//! # Ok::<(), frontmatter_gen::Error>(())
+//! ```
+
+use std::io::{self, BufRead, Cursor, Read};
+
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, AsyncReadExt};
+
+use crate::{
+    error::Error,
+    extractor::{
+        line_fence, Block, Delimiter, BOM, DEFAULT_DELIMITERS,
+    },
+    parser::parse_with_options,
+    validate_input, Frontmatter, ParseOptions, Result,
+};
+
+/// The body of a document read by [`extract_from_reader`]: any bytes
+/// read past the front matter, followed by the rest of the reader.
+pub type Body<R> = io::Chain<Cursor<Vec<u8>>, R>;
+
+/// The body of a document read by [`extract_from_async_reader`].
+pub type AsyncBody<R> = tokio::io::Chain<Cursor<Vec<u8>>, R>;
+
+/// Extracts and parses front matter from a reader, returning the body
+/// as a reader positioned after the closing fence.
+///
+/// The format comes from the fence, as in [`extract`](crate::extract),
+/// and the same input checks and security policy apply to the front
+/// matter. Unlike `extract`, the body is not trimmed: it starts right
+/// after the line break that ends the closing fence.
+///
+/// # Arguments
+///
+/// * `reader` - The document, from its first byte.
+///
+/// # Errors
+///
+/// Returns `Error` if:
+/// - Reading fails
+/// - The front matter is larger than `ParseOptions::max_size`
+/// - The front matter is not valid UTF-8, is unclosed, or fails the
+///   input checks
+/// - Parsing fails
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use frontmatter_gen::extract_from_reader;
+/// use std::{fs::File, io::{self, BufReader}};
+///
+/// let file = BufReader::new(File::open("post.md")?);
+/// let (frontmatter, mut body) = extract_from_reader(file)?;
+/// println!("{:?}", frontmatter.get("title"));
+/// io::copy(&mut body, &mut io::stdout())?;
+/// # Ok::<(), frontmatter_gen::Error>(())
+/// ```
+pub fn extract_from_reader<R: BufRead>(
+    reader: R,
+) -> Result<(Frontmatter, Body<R>)> {
+    extract_from_reader_with_options(reader, &ParseOptions::from_env())
+}
+
+/// Extracts and parses front matter from a reader under the limits and
+/// security policy of `options`.
+///
+/// This is [`extract_from_reader`] with `options` in place of those read
+/// from the environment, so `options.max_size` also bounds how much of
+/// the reader is read.
+///
+/// # Errors
+///
+/// Returns the same errors as [`extract_from_reader`], against the
+/// limits of `options`.
+///
+/// # Examples
+///
+/// ```rust
+/// use frontmatter_gen::{extract_from_reader_with_options, Error, ParseOptions};
+///
+/// let options = ParseOptions::builder().max_size(8).build()?;
+/// let file = "---\ntitle: My Post\n---\nBody";
+/// let result = extract_from_reader_with_options(file.as_bytes(), &options);
+/// assert!(matches!(result, Err(Error::ContentTooLarge { .. })));
+/// # Ok::<(), frontmatter_gen::Error>(())
+/// ```
+pub fn extract_from_reader_with_options<R: BufRead>(
+    mut reader: R,
+    options: &ParseOptions,
+) -> Result<(Frontmatter, Body<R>)> {
+    let mut scanner = Scanner::new(*options);
+    let block = loop {
+        let start = scanner.head.len();
+        let _ = (&mut reader)
+            .take(scanner.budget())
+            .read_until(b'\n', &mut scanner.head)?;
+        if let Some(block) = scanner.scan(start)? {
+            break block;
+        }
+    };
+
+    let (frontmatter, rest) = scanner.finish(block)?;
+    Ok((frontmatter, Read::chain(Cursor::new(rest), reader)))
+}
+
+/// Extracts and parses front matter from an asynchronous reader,
+/// returning the body as a reader positioned after the closing fence.
+///
+/// This is the `tokio` counterpart of [`extract_from_reader`].
+///
+/// # Arguments
+///
+/// * `reader` - The document, from its first byte.
+///
+/// # Errors
+///
+/// Returns the same errors as [`extract_from_reader`].
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use frontmatter_gen::extract_from_async_reader;
+/// use tokio::{fs::File, io::BufReader};
+///
+/// # async fn run() -> frontmatter_gen::Result<()> {
+/// let file = BufReader::new(File::open("post.md").await?);
+/// let (frontmatter, mut body) = extract_from_async_reader(file).await?;
+/// println!("{:?}", frontmatter.get("title"));
+/// tokio::io::copy(&mut body, &mut tokio::io::stdout()).await?;
+/// # Ok(())
+/// # }
+/// ```
+pub async fn extract_from_async_reader<R: AsyncBufRead + Unpin>(
+    reader: R,
+) -> Result<(Frontmatter, AsyncBody<R>)> {
+    extract_from_async_reader_with_options(
+        reader,
+        &ParseOptions::from_env(),
+    )
+    .await
+}
+
+/// Extracts and parses front matter from an asynchronous reader under
+/// the limits and security policy of `options`.
+///
+/// This is the `tokio` counterpart of
+/// [`extract_from_reader_with_options`].
+///
+/// # Errors
+///
+/// Returns the same errors as [`extract_from_reader`], against the
+/// limits of `options`.
+pub async fn extract_from_async_reader_with_options<
+    R: AsyncBufRead + Unpin,
+>(
+    mut reader: R,
+    options: &ParseOptions,
+) -> Result<(Frontmatter, AsyncBody<R>)> {
+    let mut scanner = Scanner::new(*options);
+    let block = loop {
+        let start = scanner.head.len();
+        let _ = (&mut reader)
+            .take(scanner.budget())
+            .read_until(b'\n', &mut scanner.head)
+            .await?;
+        if let Some(block) = scanner.scan(start)? {
+            break block;
+        }
+    };
+
+    let (frontmatter, rest) = scanner.finish(block)?;
+    Ok((frontmatter, AsyncReadExt::chain(Cursor::new(rest), reader)))
+}
+
+/// What the first line of the document opened.
+enum Opening {
+    /// Nothing has been read yet.
+    Unknown,
+    /// A JSON object, which ends at the brace that balances the first.
+    Json,
+    /// A block closed by one of the delimiter's fences.
+    Fence(&'static Delimiter),
+}
+
+/// The bytes read so far and what they opened.
+struct Scanner {
+    options: ParseOptions,
+    head: Vec<u8>,
+    opening: Opening,
+}
+
+impl Scanner {
+    const fn new(options: ParseOptions) -> Self {
+        Self {
+            options,
+            head: Vec::new(),
+            opening: Opening::Unknown,
+        }
+    }
+
+    /// Returns how many bytes the next read may take: one more than the
+    /// front matter has left, so that going over the limit is seen.
+    fn budget(&self) -> u64 {
+        let left = self.options.max_size.get() - self.head.len();
+        u64::try_from(left)
+            .map_or(u64::MAX, |left| left.saturating_add(1))
+    }
+
+    /// Looks at the line read from byte `start` of the head, returning
+    /// the block once it is complete.
+    fn scan(&mut self, start: usize) -> Result<Option<Block>> {
+        let max = self.options.max_size.get();
+        if self.head.len() > max {
+            return Err(Error::ContentTooLarge {
+                size: self.head.len(),
+                max,
+            });
+        }
+        let end_of_input = self.head.len() == start;
+        let line = utf8(&self.head[start..])?;
+
+        let complete = match self.opening {
+            _ if end_of_input => true,
+            Opening::Unknown => {
+                let line = line.strip_prefix(BOM).unwrap_or(line);
+                if line.starts_with('{') {
+                    self.opening = Opening::Json;
+                    line.contains('}')
+                } else {
+                    let delimiter = DEFAULT_DELIMITERS
+                        .opening(line_fence(line))
+                        .ok_or(Error::InvalidFormat)?;
+                    self.opening = Opening::Fence(delimiter);
+                    false
+                }
+            }
+            Opening::Json => line.contains('}'),
+            Opening::Fence(delimiter) => {
+                delimiter.closes(line_fence(line))
+            }
+        };
+        if !complete {
+            return Ok(None);
+        }
+
+        match DEFAULT_DELIMITERS.find_block(utf8(&self.head)?) {
+            Ok(block) => Ok(Some(block)),
+            // The brace on this line did not balance the first one.
+            Err(Error::InvalidJson) if !end_of_input => Ok(None),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Checks and parses the front matter of `block`, returning it with
+    /// the bytes read past the block.
+    fn finish(self, block: Block) -> Result<(Frontmatter, Vec<u8>)> {
+        let head = utf8(&self.head)?;
+        validate_input(&head[..block.body.start], &self.options)?;
+
+        let raw_frontmatter = &head[block.frontmatter];
+        let frontmatter = parse_with_options(
+            raw_frontmatter,
+            block.format,
+            Some(self.options),
+        )
+        .map_err(|e| e.relocate(raw_frontmatter, head))?;
+        self.options.security.check(
+            raw_frontmatter,
+            block.format,
+            &frontmatter,
+        )?;
+
+        Ok((frontmatter, self.head[block.body].to_vec()))
+    }
+}
+
+/// Reads bytes as UTF-8 text.
+fn utf8(bytes: &[u8]) -> Result<&str> {
+    std::str::from_utf8(bytes).map_err(|_| {
+        Error::ValidationError(
+            "Front matter is not valid UTF-8".to_string(),
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Value;
+    use std::num::NonZeroUsize;
+
+    /// Extracts from `content`, returning the front matter, the body
+    /// and how far the underlying reader was read.
+    fn extract(content: &str) -> Result<(Frontmatter, String, u64)> {
+        let (frontmatter, body) =
+            extract_from_reader(Cursor::new(content.as_bytes()))?;
+        let (rest, mut reader) = body.into_inner();
+        let position = reader.position();
+        let mut body = String::from_utf8(rest.into_inner()).unwrap();
+        let _ = Read::read_to_string(&mut reader, &mut body)?;
+        Ok((frontmatter, body, position))
+    }
+
+    fn title(frontmatter: &Frontmatter) -> Option<&str> {
+        frontmatter.get("title").and_then(Value::as_str)
+    }
+
+    fn limited(max_size: usize) -> ParseOptions {
+        ParseOptions {
+            max_size: NonZeroUsize::new(max_size).unwrap(),
+            ..ParseOptions::default()
+        }
+    }
+
+    mod reader_tests {
+        use super::*;
+
+        #[test]
+        fn test_stops_after_the_closing_fence() {
+            let content = "---\ntitle: Post\n---\n\nBody\n---\n";
+            let (frontmatter, body, position) =
+                extract(content).unwrap();
+            assert_eq!(title(&frontmatter), Some("Post"));
+            assert_eq!(body, "\nBody\n---\n");
+            assert_eq!(position, 20);
+        }
+
+        #[test]
+        fn test_fences_and_line_endings() {
+            let content =
+                "\u{feff}+++ \r\ntitle = \"A: B\"\r\n+++\r\nBody";
+            let (frontmatter, body, _) = extract(content).unwrap();
+            assert_eq!(title(&frontmatter), Some("A: B"));
+            assert_eq!(body, "Body");
+
+            let (frontmatter, body, _) =
+                extract("---\ntitle: Post\n---").unwrap();
+            assert_eq!(title(&frontmatter), Some("Post"));
+            assert_eq!(body, "");
+        }
+
+        #[test]
+        fn test_json_keeps_the_rest_of_its_last_line() {
+            let content = "{\n  \"title\": \"}\"\n} Body\nMore";
+            let (frontmatter, body, _) = extract(content).unwrap();
+            assert_eq!(title(&frontmatter), Some("}"));
+            assert_eq!(body, " Body\nMore");
+        }
+
+        #[test]
+        fn test_body_is_not_limited() {
+            let body = "x".repeat(2 * 1024 * 1024);
+            let content = format!("---\ntitle: Post\n---\n{body}");
+            let (_, read, position) = extract(&content).unwrap();
+            assert_eq!(read.len(), body.len());
+            assert_eq!(position, 20);
+        }
+
+        #[test]
+        fn test_front_matter_is_limited() {
+            let content = format!("---\ntitle: {}\n", "x".repeat(100));
+            let mut reader = Cursor::new(content.as_bytes());
+            let err = extract_from_reader_with_options(
+                &mut reader,
+                &limited(64),
+            )
+            .err()
+            .unwrap();
+            assert!(matches!(
+                err,
+                Error::ContentTooLarge { size: 65, max: 64 }
+            ));
+            assert_eq!(reader.position(), 65);
+
+            let content = "---\ntitle: Post\n---\nBody";
+            assert!(extract_from_reader_with_options(
+                content.as_bytes(),
+                &limited(20)
+            )
+            .is_ok());
+            assert!(extract_from_reader_with_options(
+                content.as_bytes(),
+                &limited(19)
+            )
+            .is_err());
+        }
+
+        #[test]
+        fn test_errors() {
+            let err = extract("---\ntitle: Post\n").unwrap_err();
+            assert!(matches!(err.inner(), Error::ExtractionError(_)));
+            assert_eq!(err.context().and_then(|c| c.line), Some(1));
+
+            let mut reader = Cursor::new("# Title\n---\n".as_bytes());
+            assert!(matches!(
+                extract_from_reader(&mut reader),
+                Err(Error::InvalidFormat)
+            ));
+            assert_eq!(reader.position(), 8);
+
+            assert!(matches!(extract(""), Err(Error::InvalidFormat)));
+            assert!(matches!(
+                extract_from_reader(&b"---\ntitle: \xff\n---\n"[..]),
+                Err(Error::ValidationError(_))
+            ));
+
+            let err = extract("---\ntitle: Post\nbad: : x\n---\n")
+                .unwrap_err();
+            assert_eq!(err.context().and_then(|c| c.line), Some(3));
+        }
+    }
+
+    mod async_reader_tests {
+        use super::*;
+        use tokio::io::BufReader;
+
+        #[tokio::test]
+        async fn test_async_extraction() {
+            let content = "---\ntitle: Post\n---\nBody\n";
+            let (frontmatter, mut body) = extract_from_async_reader(
+                BufReader::new(content.as_bytes()),
+            )
+            .await
+            .unwrap();
+            assert_eq!(title(&frontmatter), Some("Post"));
+
+            let mut rest = String::new();
+            let _ = body.read_to_string(&mut rest).await.unwrap();
+            assert_eq!(rest, "Body\n");
+        }
+
+        #[tokio::test]
+        async fn test_async_limit_and_errors() {
+            let content = format!("---\ntitle: {}\n", "x".repeat(100));
+            assert!(matches!(
+                extract_from_async_reader_with_options(
+                    content.as_bytes(),
+                    &limited(64),
+                )
+                .await,
+                Err(Error::ContentTooLarge { .. })
+            ));
+            assert!(matches!(
+                extract_from_async_reader(&b"{\"title\": 1"[..]).await,
+                Err(Error::InvalidJson)
+            ));
+        }
+    }
+}
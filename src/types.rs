@@ -3,12 +3,19 @@
 //! This module defines the core types used throughout the frontmatter-gen crate.
 //! It includes the `Format` enum for representing different frontmatter formats, the `Value` enum for representing various data types that can be stored in frontmatter, and the `Frontmatter` struct which is the main container for frontmatter data.
 
-use serde::{Deserialize, Serialize};
-use std::{
-    collections::{BTreeMap, HashMap},
-    fmt,
-    str::FromStr,
+use indexmap::IndexMap;
+use serde::{
+    de::{self, MapAccess, SeqAccess, Visitor},
+    ser::{SerializeMap, SerializeStruct},
+    Deserialize, Deserializer, Serialize, Serializer,
 };
+use std::{fmt, str::FromStr};
+
+/// A date, time or date-time value as defined by RFC 3339.
+///
+/// This is the TOML datetime type, which covers offset date-times,
+/// local date-times, local dates and local times.
+pub use toml::value::Datetime;
 
 /// Represents the different formats supported for frontmatter serialization/deserialization.
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
@@ -21,6 +28,16 @@ pub enum Format {
     /// JSON format.
     #[default]
     Json,
+    /// JSON5 format, JSON with comments, trailing commas and unquoted
+    /// keys.
+    Json5,
+    /// RON (Rusty Object Notation) format.
+    Ron,
+    /// Org-mode `#+KEYWORD: value` header lines.
+    Org,
+    /// AsciiDoc document header, with a `= Title` line and
+    /// `:name: value` attribute entries.
+    AsciiDoc,
     /// Unsupported format.
     Unsupported,
 }
@@ -31,23 +48,69 @@ fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
             Format::Yaml => "YAML",
             Format::Toml => "TOML",
             Format::Json => "JSON",
+            Format::Json5 => "JSON5",
+            Format::Ron => "RON",
+            Format::Org => "Org",
+            Format::AsciiDoc => "AsciiDoc",
             Format::Unsupported => "Unsupported",
         };
         write!(f, "{}", format_str)
     }
 }
 
+/// The struct name `toml::value::Datetime` (de)serialises itself through.
+pub(crate) const TOML_DATETIME_NAME: &str = "$__toml_private_Datetime";
+
+/// The single field of the TOML `Datetime` struct representation.
+pub(crate) const TOML_DATETIME_FIELD: &str = "$__toml_private_datetime";
+
+/// The struct name a `Value::Tagged` is serialised through.
+pub(crate) const TAGGED_NAME: &str =
+    "$__frontmatter_gen_private_Tagged";
+
+/// The field of the `Value::Tagged` struct representation holding the
+/// tag.
+pub(crate) const TAGGED_TAG_FIELD: &str =
+    "$__frontmatter_gen_private_tag";
+
+/// The field of the `Value::Tagged` struct representation holding the
+/// value.
+pub(crate) const TAGGED_VALUE_FIELD: &str =
+    "$__frontmatter_gen_private_value";
+
 /// A flexible value type that can hold various types of data found in frontmatter.
-#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
-#[serde(untagged)]
+///
+/// `Value` has hand-written serde implementations so that its shape
+/// survives a round trip through `serde_json`, `toml` or `noyalib`:
+///
+/// - `DateTime` serialises the way `Datetime` does, so `toml` writes a
+///   native datetime. Other formats see a map with one private field,
+///   which deserialises back into `DateTime`.
+/// - `Tagged` serialises as a struct with a private tag field and value
+///   field, which deserialises back into `Tagged`. A map whose key
+///   merely starts with `!` stays an `Object`. The YAML that
+///   [`crate::parser::to_string`] writes uses native tags instead.
+/// - `Object` serialises as a map, keeping key order.
+#[derive(Debug, Clone, Default, PartialEq)]
 pub enum Value {
     /// Represents a null value.
     #[default]
     Null,
     /// Represents a string value.
     String(String),
-    /// Represents a numeric value.
-    Number(f64),
+    /// Represents a signed integer value.
+    ///
+    /// YAML and JSON integers outside the `i64` range are read as a
+    /// `Float` holding the nearest value, as most JSON parsers do; TOML
+    /// rejects them.
+    Integer(i64),
+    /// Represents a floating-point value.
+    Float(f64),
+    /// Represents a date, time or date-time value.
+    ///
+    /// Parsed from native TOML datetimes and YAML `!!timestamp` scalars.
+    /// JSON has no datetime type, so it is written as an RFC 3339 string.
+    DateTime(Datetime),
     /// Represents a boolean value.
     Boolean(bool),
     /// Represents an array of values.
@@ -74,7 +137,7 @@ impl Value {
     /// let string_value = Value::String("Hello".to_string());
     /// assert_eq!(string_value.as_str(), Some("Hello"));
     ///
-    /// let number_value = Value::Number(42.0);
+    /// let number_value = Value::Integer(42);
     /// assert_eq!(number_value.as_str(), None);
     /// ```
     pub fn as_str(&self) -> Option<&str> {
@@ -85,27 +148,83 @@ pub fn as_str(&self) -> Option<&str> {
         }
     }
 
-    /// Returns the value as a float, if it is of type `Number`.
+    /// Returns the value as a float, if it is numeric.
+    ///
+    /// Integers are widened to `f64`, which may lose precision for
+    /// magnitudes above 2^53.
     ///
     /// # Returns
     ///
-    /// - `Some(f64)` if the value is a `Number`.
-    /// - `None` if the value is not a `Number`.
+    /// - `Some(f64)` if the value is an `Integer` or a `Float`.
+    /// - `None` if the value is not numeric.
     ///
     /// # Examples
     ///
     /// ```
     /// use frontmatter_gen::Value;
     ///
-    /// let number_value = Value::Number(3.14);
+    /// let number_value = Value::Float(3.14);
     /// assert_eq!(number_value.as_f64(), Some(3.14));
+    /// assert_eq!(Value::Integer(2).as_f64(), Some(2.0));
     ///
     /// let string_value = Value::String("Not a number".to_string());
     /// assert_eq!(string_value.as_f64(), None);
     /// ```
     pub const fn as_f64(&self) -> Option<f64> {
-        if let Value::Number(n) = self {
-            Some(*n)
+        match self {
+            Value::Integer(i) => Some(*i as f64),
+            Value::Float(f) => Some(*f),
+            _ => None,
+        }
+    }
+
+    /// Returns the value as an integer, if it is of type `Integer`.
+    ///
+    /// # Returns
+    ///
+    /// - `Some(i64)` if the value is an `Integer`.
+    /// - `None` otherwise, including for `Float` values.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use frontmatter_gen::Value;
+    ///
+    /// let id = Value::Integer(9_007_199_254_740_993);
+    /// assert_eq!(id.as_i64(), Some(9_007_199_254_740_993));
+    ///
+    /// assert_eq!(Value::Float(1.0).as_i64(), None);
+    /// ```
+    pub const fn as_i64(&self) -> Option<i64> {
+        if let Value::Integer(i) = self {
+            Some(*i)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the value as a datetime, if it is of type `DateTime`.
+    ///
+    /// # Returns
+    ///
+    /// - `Some(&Datetime)` if the value is a `DateTime`.
+    /// - `None` if the value is not a `DateTime`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use frontmatter_gen::{types::Datetime, Value};
+    ///
+    /// let dt: Datetime = "1979-05-27T07:32:00Z".parse().unwrap();
+    /// let value = Value::DateTime(dt);
+    /// assert_eq!(value.as_datetime(), Some(&dt));
+    ///
+    /// let string_value = Value::String("1979-05-27".to_string());
+    /// assert_eq!(string_value.as_datetime(), None);
+    /// ```
+    pub const fn as_datetime(&self) -> Option<&Datetime> {
+        if let Value::DateTime(dt) = self {
+            Some(dt)
         } else {
             None
         }
@@ -149,7 +268,7 @@ pub const fn as_bool(&self) -> Option<bool> {
     /// ```
     /// use frontmatter_gen::Value;
     ///
-    /// let array_value = Value::Array(vec![Value::Number(1.0), Value::Number(2.0)]);
+    /// let array_value = Value::Array(vec![Value::Integer(1), Value::Integer(2)]);
     /// assert!(array_value.as_array().is_some());
     /// assert_eq!(array_value.as_array().unwrap().len(), 2);
     ///
@@ -204,8 +323,8 @@ pub fn as_object(&self) -> Option<&Frontmatter> {
     /// ```
     /// use frontmatter_gen::Value;
     ///
-    /// let tagged_value = Value::Tagged("tag".to_string(), Box::new(Value::Number(42.0)));
-    /// assert_eq!(tagged_value.as_tagged(), Some(("tag", &Value::Number(42.0))));
+    /// let tagged_value = Value::Tagged("tag".to_string(), Box::new(Value::Integer(42)));
+    /// assert_eq!(tagged_value.as_tagged(), Some(("tag", &Value::Integer(42))));
     ///
     /// let string_value = Value::String("Not tagged".to_string());
     /// assert_eq!(string_value.as_tagged(), None);
@@ -253,32 +372,87 @@ pub const fn is_null(&self) -> bool {
     /// let string_value = Value::String("Hello".to_string());
     /// assert!(string_value.is_string());
     ///
-    /// let number_value = Value::Number(42.0);
+    /// let number_value = Value::Integer(42);
     /// assert!(!number_value.is_string());
     /// ```
     pub const fn is_string(&self) -> bool {
         matches!(self, Value::String(_))
     }
 
-    /// Checks if the value is of type `Number`.
+    /// Checks if the value is numeric.
     ///
     /// # Returns
     ///
-    /// `true` if the value is a `Number`, otherwise `false`.
+    /// `true` if the value is an `Integer` or a `Float`, otherwise `false`.
     ///
     /// # Examples
     ///
     /// ```
     /// use frontmatter_gen::Value;
     ///
-    /// let number_value = Value::Number(3.14);
-    /// assert!(number_value.is_number());
+    /// assert!(Value::Float(3.14).is_number());
+    /// assert!(Value::Integer(3).is_number());
     ///
     /// let string_value = Value::String("Not a number".to_string());
     /// assert!(!string_value.is_number());
     /// ```
     pub const fn is_number(&self) -> bool {
-        matches!(self, Value::Number(_))
+        matches!(self, Value::Integer(_) | Value::Float(_))
+    }
+
+    /// Checks if the value is of type `Integer`.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the value is an `Integer`, otherwise `false`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use frontmatter_gen::Value;
+    ///
+    /// assert!(Value::Integer(3).is_integer());
+    /// assert!(!Value::Float(3.0).is_integer());
+    /// ```
+    pub const fn is_integer(&self) -> bool {
+        matches!(self, Value::Integer(_))
+    }
+
+    /// Checks if the value is of type `Float`.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the value is a `Float`, otherwise `false`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use frontmatter_gen::Value;
+    ///
+    /// assert!(Value::Float(3.0).is_float());
+    /// assert!(!Value::Integer(3).is_float());
+    /// ```
+    pub const fn is_float(&self) -> bool {
+        matches!(self, Value::Float(_))
+    }
+
+    /// Checks if the value is of type `DateTime`.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the value is a `DateTime`, otherwise `false`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use frontmatter_gen::Value;
+    ///
+    /// let value = Value::DateTime("2024-01-01".parse().unwrap());
+    /// assert!(value.is_datetime());
+    /// assert!(!Value::String("2024-01-01".to_string()).is_datetime());
+    /// ```
+    pub const fn is_datetime(&self) -> bool {
+        matches!(self, Value::DateTime(_))
     }
 
     /// Checks if the value is of type `Boolean`.
@@ -313,7 +487,7 @@ pub const fn is_boolean(&self) -> bool {
     /// ```
     /// use frontmatter_gen::Value;
     ///
-    /// let array_value = Value::Array(vec![Value::Number(1.0), Value::Number(2.0)]);
+    /// let array_value = Value::Array(vec![Value::Integer(1), Value::Integer(2)]);
     /// assert!(array_value.is_array());
     ///
     /// let string_value = Value::String("Not an array".to_string());
@@ -355,7 +529,7 @@ pub const fn is_object(&self) -> bool {
     /// ```
     /// use frontmatter_gen::Value;
     ///
-    /// let tagged_value = Value::Tagged("tag".to_string(), Box::new(Value::Number(42.0)));
+    /// let tagged_value = Value::Tagged("tag".to_string(), Box::new(Value::Integer(42)));
     /// assert!(tagged_value.is_tagged());
     ///
     /// let string_value = Value::String("Not tagged".to_string());
@@ -365,6 +539,30 @@ pub const fn is_tagged(&self) -> bool {
         matches!(self, Value::Tagged(_, _))
     }
 
+    /// Returns the name of the value's type, as used in error messages.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use frontmatter_gen::Value;
+    ///
+    /// assert_eq!(Value::Integer(42).type_name(), "integer");
+    /// assert_eq!(Value::String("text".to_string()).type_name(), "string");
+    /// ```
+    pub const fn type_name(&self) -> &'static str {
+        match self {
+            Value::Null => "null",
+            Value::String(_) => "string",
+            Value::Integer(_) => "integer",
+            Value::Float(_) => "float",
+            Value::DateTime(_) => "datetime",
+            Value::Boolean(_) => "boolean",
+            Value::Array(_) => "array",
+            Value::Object(_) => "object",
+            Value::Tagged(_, _) => "tagged",
+        }
+    }
+
     /// Returns the length of the array if the value is an array, otherwise returns `None`.
     ///
     /// # Returns
@@ -377,7 +575,7 @@ pub const fn is_tagged(&self) -> bool {
     /// ```
     /// use frontmatter_gen::Value;
     ///
-    /// let array_value = Value::Array(vec![Value::Number(1.0), Value::Number(2.0)]);
+    /// let array_value = Value::Array(vec![Value::Integer(1), Value::Integer(2)]);
     /// assert_eq!(array_value.array_len(), Some(2));
     ///
     /// let string_value = Value::String("Not an array".to_string());
@@ -428,7 +626,7 @@ pub fn to_object(self) -> Result<Frontmatter, String> {
     /// ```
     /// use frontmatter_gen::Value;
     ///
-    /// let number_value = Value::Number(3.14);
+    /// let number_value = Value::Float(3.14);
     /// assert_eq!(number_value.to_string_representation(), "3.14");
     ///
     /// let string_value = Value::String("Hello".to_string());
@@ -453,7 +651,7 @@ pub fn to_string_representation(&self) -> String {
     /// let string_value = Value::String("Hello".to_string());
     /// assert_eq!(string_value.into_string(), Ok("Hello".to_string()));
     ///
-    /// let number_value = Value::Number(42.0);
+    /// let number_value = Value::Integer(42);
     /// assert!(number_value.into_string().is_err());
     /// ```
     pub fn into_string(self) -> Result<String, String> {
@@ -468,25 +666,44 @@ pub fn into_string(self) -> Result<String, String> {
     ///
     /// # Returns
     ///
-    /// - `Ok(f64)` if the value is a `Number`.
-    /// - `Err(String)` with an error message if the value is not a `Number`.
+    /// - `Ok(f64)` if the value is an `Integer` or a `Float`.
+    /// - `Err(String)` with an error message if the value is not numeric.
     ///
     /// # Examples
     ///
     /// ```
     /// use frontmatter_gen::Value;
     ///
-    /// let number_value = Value::Number(3.14);
+    /// let number_value = Value::Float(3.14);
     /// assert_eq!(number_value.into_f64(), Ok(3.14));
     ///
     /// let string_value = Value::String("Not a number".to_string());
     /// assert!(string_value.into_f64().is_err());
     /// ```
     pub fn into_f64(self) -> Result<f64, String> {
-        if let Value::Number(n) = self {
-            Ok(n)
+        self.as_f64().ok_or_else(|| "Value is not a number".into())
+    }
+
+    /// Attempts to convert the value into an `i64`.
+    ///
+    /// # Returns
+    ///
+    /// - `Ok(i64)` if the value is an `Integer`.
+    /// - `Err(String)` with an error message otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use frontmatter_gen::Value;
+    ///
+    /// assert_eq!(Value::Integer(42).into_i64(), Ok(42));
+    /// assert!(Value::Float(42.5).into_i64().is_err());
+    /// ```
+    pub fn into_i64(self) -> Result<i64, String> {
+        if let Value::Integer(i) = self {
+            Ok(i)
         } else {
-            Err("Value is not a number".into())
+            Err("Value is not an integer".into())
         }
     }
 
@@ -528,9 +745,9 @@ pub fn into_bool(self) -> Result<bool, String> {
     /// ```
     /// use frontmatter_gen::Value;
     ///
-    /// let mut array_value = Value::Array(vec![Value::Number(1.0), Value::Number(2.0)]);
+    /// let mut array_value = Value::Array(vec![Value::Integer(1), Value::Integer(2)]);
     /// if let Some(arr) = array_value.get_mut_array() {
-    ///     arr.push(Value::Number(3.0));
+    ///     arr.push(Value::Integer(3));
     /// }
     /// assert_eq!(array_value.array_len(), Some(3));
     ///
@@ -558,9 +775,21 @@ fn from(s: String) -> Self {
     }
 }
 
+impl From<i64> for Value {
+    fn from(n: i64) -> Self {
+        Value::Integer(n)
+    }
+}
+
 impl From<f64> for Value {
     fn from(n: f64) -> Self {
-        Value::Number(n)
+        Value::Float(n)
+    }
+}
+
+impl From<Datetime> for Value {
+    fn from(dt: Datetime) -> Self {
+        Value::DateTime(dt)
     }
 }
 
@@ -586,8 +815,10 @@ fn from_str(s: &str) -> Result<Self, Self::Err> {
             Ok(Value::Boolean(true))
         } else if s.eq_ignore_ascii_case("false") {
             Ok(Value::Boolean(false))
+        } else if let Ok(i) = s.parse::<i64>() {
+            Ok(Value::Integer(i))
         } else if let Ok(n) = s.parse::<f64>() {
-            Ok(Value::Number(n))
+            Ok(Value::Float(n))
         } else {
             Ok(Value::String(s.to_string()))
         }
@@ -595,8 +826,14 @@ fn from_str(s: &str) -> Result<Self, Self::Err> {
 }
 
 /// Represents the frontmatter, a collection of key-value pairs.
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
-pub struct Frontmatter(pub HashMap<String, Value>);
+///
+/// Entries are stored in insertion order, so keys parsed from a source
+/// document are iterated and serialised in the order they were written.
+///
+/// Frontmatter serialises as a map and deserialises from one, keeping
+/// key order.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Frontmatter(pub IndexMap<String, Value>);
 
 impl Frontmatter {
     /// Creates a new, empty frontmatter.
@@ -615,7 +852,7 @@ impl Frontmatter {
     /// ```
     #[must_use]
     pub fn new() -> Self {
-        Frontmatter(HashMap::new())
+        Frontmatter(IndexMap::new())
     }
 
     /// Inserts a key-value pair into the frontmatter.
@@ -636,7 +873,7 @@ pub fn new() -> Self {
     ///
     /// let mut fm = Frontmatter::new();
     /// assert_eq!(fm.insert("key".to_string(), Value::String("value".to_string())), None);
-    /// assert_eq!(fm.insert("key".to_string(), Value::Number(42.0)), Some(Value::String("value".to_string())));
+    /// assert_eq!(fm.insert("key".to_string(), Value::Integer(42)), Some(Value::String("value".to_string())));
     /// ```
     pub fn insert(
         &mut self,
@@ -688,9 +925,9 @@ pub fn get(&self, key: &str) -> Option<&Value> {
     /// let mut fm = Frontmatter::new();
     /// fm.insert("key".to_string(), Value::String("value".to_string()));
     /// if let Some(value) = fm.get_mut("key") {
-    ///     *value = Value::Number(42.0);
+    ///     *value = Value::Integer(42);
     /// }
-    /// assert_eq!(fm.get("key"), Some(&Value::Number(42.0)));
+    /// assert_eq!(fm.get("key"), Some(&Value::Integer(42)));
     /// ```
     pub fn get_mut(&mut self, key: &str) -> Option<&mut Value> {
         self.0.get_mut(key)
@@ -698,6 +935,8 @@ pub fn get_mut(&mut self, key: &str) -> Option<&mut Value> {
 
     /// Removes a key-value pair from the frontmatter.
     ///
+    /// The relative order of the remaining entries is preserved.
+    ///
     /// # Arguments
     ///
     /// * `key` - The key to remove.
@@ -717,7 +956,7 @@ pub fn get_mut(&mut self, key: &str) -> Option<&mut Value> {
     /// assert_eq!(fm.remove("key"), None);
     /// ```
     pub fn remove(&mut self, key: &str) -> Option<Value> {
-        self.0.remove(key)
+        self.0.shift_remove(key)
     }
 
     /// Checks if the frontmatter contains a given key.
@@ -788,7 +1027,7 @@ pub fn is_empty(&self) -> bool {
     ///
     /// # Returns
     ///
-    /// An iterator over references to the key-value pairs.
+    /// An iterator over references to the key-value pairs, in insertion order.
     ///
     /// # Examples
     ///
@@ -797,16 +1036,14 @@ pub fn is_empty(&self) -> bool {
     ///
     /// let mut fm = Frontmatter::new();
     /// fm.insert("key1".to_string(), Value::String("value1".to_string()));
-    /// fm.insert("key2".to_string(), Value::Number(42.0));
+    /// fm.insert("key2".to_string(), Value::Integer(42));
     ///
     /// for (key, value) in fm.iter() {
     ///     println!("{}: {:?}", key, value);
     /// }
     /// ```
     #[must_use]
-    pub fn iter(
-        &self,
-    ) -> std::collections::hash_map::Iter<'_, String, Value> {
+    pub fn iter(&self) -> indexmap::map::Iter<'_, String, Value> {
         self.0.iter()
     }
 
@@ -823,24 +1060,30 @@ pub fn iter(
     ///
     /// let mut fm = Frontmatter::new();
     /// fm.insert("key1".to_string(), Value::String("value1".to_string()));
-    /// fm.insert("key2".to_string(), Value::Number(42.0));
+    /// fm.insert("key2".to_string(), Value::Integer(42));
     ///
     /// for (_, value) in fm.iter_mut() {
-    ///     if let Value::Number(n) = value {
-    ///         *n += 1.0;
+    ///     if let Value::Integer(n) = value {
+    ///         *n += 1;
     ///     }
     /// }
     ///
-    /// assert_eq!(fm.get("key2"), Some(&Value::Number(43.0)));
+    /// assert_eq!(fm.get("key2"), Some(&Value::Integer(43)));
     /// ```
     pub fn iter_mut(
         &mut self,
-    ) -> std::collections::hash_map::IterMut<'_, String, Value> {
+    ) -> indexmap::map::IterMut<'_, String, Value> {
         self.0.iter_mut()
     }
 
     /// Merges another frontmatter into this one. If a key exists, it will be overwritten.
     ///
+    /// Overwritten keys keep their original position; new keys are appended
+    /// in the order they appear in `other`.
+    ///
+    /// Nested objects are replaced as a whole; use
+    /// [`Frontmatter::merge_deep`] to merge them key by key.
+    ///
     /// # Arguments
     ///
     /// * `other` - The frontmatter to merge into this one.
@@ -854,11 +1097,11 @@ pub fn iter_mut(
     /// fm1.insert("key1".to_string(), Value::String("value1".to_string()));
     ///
     /// let mut fm2 = Frontmatter::new();
-    /// fm2.insert("key2".to_string(), Value::Number(42.0));
+    /// fm2.insert("key2".to_string(), Value::Integer(42));
     ///
     /// fm1.merge(fm2);
     /// assert_eq!(fm1.len(), 2);
-    /// assert_eq!(fm1.get("key2"), Some(&Value::Number(42.0)));
+    /// assert_eq!(fm1.get("key2"), Some(&Value::Integer(42)));
     /// ```
     pub fn merge(&mut self, other: Frontmatter) {
         self.0.extend(other.0);
@@ -896,7 +1139,7 @@ pub fn clear(&mut self) {
         self.0.clear();
     }
 
-    /// Returns the current capacity of the underlying HashMap
+    /// Returns the current capacity of the underlying map
     pub fn capacity(&self) -> usize {
         self.0.capacity()
     }
@@ -909,14 +1152,14 @@ pub fn reserve(&mut self, additional: usize) {
 
 impl Default for Frontmatter {
     fn default() -> Self {
-        Self(HashMap::with_capacity(8))
+        Self(IndexMap::with_capacity(8))
     }
 }
 
 /// Implement `IntoIterator` for `Frontmatter` to allow idiomatic iteration.
 impl IntoIterator for Frontmatter {
     type Item = (String, Value);
-    type IntoIter = std::collections::hash_map::IntoIter<String, Value>;
+    type IntoIter = indexmap::map::IntoIter<String, Value>;
 
     fn into_iter(self) -> Self::IntoIter {
         self.0.into_iter()
@@ -943,13 +1186,13 @@ impl FromIterator<(String, Value)> for Frontmatter {
     ///
     /// let pairs = vec![
     ///     ("key1".to_string(), Value::String("value1".to_string())),
-    ///     ("key2".to_string(), Value::Number(42.0)),
+    ///     ("key2".to_string(), Value::Integer(42)),
     /// ];
     ///
     /// let fm = Frontmatter::from_iter(pairs);
     /// assert_eq!(fm.len(), 2);
     /// assert_eq!(fm.get("key1"), Some(&Value::String("value1".to_string())));
-    /// assert_eq!(fm.get("key2"), Some(&Value::Number(42.0)));
+    /// assert_eq!(fm.get("key2"), Some(&Value::Integer(42)));
     /// ```
     fn from_iter<I: IntoIterator<Item = (String, Value)>>(
         iter: I,
@@ -967,13 +1210,8 @@ impl fmt::Display for Frontmatter {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "{{")?;
 
-        // Use a BTreeMap to ensure consistent key order (sorted by key)
-        let mut sorted_map = BTreeMap::new();
-        for (key, value) in &self.0 {
-            let _ = sorted_map.insert(key, value);
-        }
-
-        for (i, (key, value)) in sorted_map.iter().enumerate() {
+        // Keys are written in insertion order, which is stable
+        for (i, (key, value)) in self.0.iter().enumerate() {
             if i > 0 {
                 write!(f, ", ")?;
             }
@@ -990,13 +1228,15 @@ fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             Value::Null => write!(f, "null"),
             Value::String(s) => write!(f, "\"{}\"", escape_str(s)),
-            Value::Number(n) => {
+            Value::Integer(i) => write!(f, "{}", i),
+            Value::Float(n) => {
                 if n.fract() == 0.0 {
                     write!(f, "{:.0}", n)
                 } else {
                     write!(f, "{}", n)
                 }
             }
+            Value::DateTime(dt) => write!(f, "\"{}\"", dt),
             Value::Boolean(b) => write!(f, "{}", b),
             Value::Array(arr) => {
                 write!(f, "[")?;
@@ -1016,6 +1256,243 @@ fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
     }
 }
 
+impl Serialize for Value {
+    fn serialize<S: Serializer>(
+        &self,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        match self {
+            Value::Null => serializer.serialize_none(),
+            Value::String(s) => serializer.serialize_str(s),
+            Value::Integer(i) => serializer.serialize_i64(*i),
+            Value::Float(f) => serializer.serialize_f64(*f),
+            Value::DateTime(dt) => dt.serialize(serializer),
+            Value::Boolean(b) => serializer.serialize_bool(*b),
+            Value::Array(arr) => arr.serialize(serializer),
+            Value::Object(obj) => obj.serialize(serializer),
+            Value::Tagged(tag, value) => {
+                let mut tagged =
+                    serializer.serialize_struct(TAGGED_NAME, 2)?;
+                tagged.serialize_field(TAGGED_TAG_FIELD, tag)?;
+                tagged.serialize_field(TAGGED_VALUE_FIELD, value)?;
+                tagged.end()
+            }
+        }
+    }
+}
+
+impl Serialize for Frontmatter {
+    fn serialize<S: Serializer>(
+        &self,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(self.0.len()))?;
+        for (key, value) in &self.0 {
+            map.serialize_entry(key, value)?;
+        }
+        map.end()
+    }
+}
+
+/// Serialises a value the way JSON front matter writes it.
+///
+/// Datetimes are written as RFC 3339 strings and tagged values as a
+/// single-entry map from the tag to the value, such as
+/// `{"!custom": "x"}`, rather than through their private
+/// representations.
+pub(crate) struct Plain<'a, T>(pub(crate) &'a T);
+
+impl Serialize for Plain<'_, Frontmatter> {
+    fn serialize<S: Serializer>(
+        &self,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_map(
+            self.0.iter().map(|(key, value)| (key, Plain(value))),
+        )
+    }
+}
+
+impl Serialize for Plain<'_, Value> {
+    fn serialize<S: Serializer>(
+        &self,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        match self.0 {
+            Value::DateTime(dt) => serializer.collect_str(dt),
+            Value::Array(arr) => {
+                serializer.collect_seq(arr.iter().map(Plain))
+            }
+            Value::Object(obj) => Plain(&**obj).serialize(serializer),
+            Value::Tagged(tag, value) => serializer
+                .collect_map(std::iter::once((tag, Plain(&**value)))),
+            value => value.serialize(serializer),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Value {
+    fn deserialize<D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(ValueVisitor)
+    }
+}
+
+impl<'de> Deserialize<'de> for Frontmatter {
+    fn deserialize<D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Self, D::Error> {
+        deserializer.deserialize_map(FrontmatterVisitor)
+    }
+}
+
+/// Builds a `Value` from any self-describing format.
+struct ValueVisitor;
+
+impl<'de> Visitor<'de> for ValueVisitor {
+    type Value = Value;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a front matter value")
+    }
+
+    fn visit_unit<E>(self) -> Result<Value, E> {
+        Ok(Value::Null)
+    }
+
+    fn visit_none<E>(self) -> Result<Value, E> {
+        Ok(Value::Null)
+    }
+
+    fn visit_some<D: Deserializer<'de>>(
+        self,
+        deserializer: D,
+    ) -> Result<Value, D::Error> {
+        Value::deserialize(deserializer)
+    }
+
+    fn visit_newtype_struct<D: Deserializer<'de>>(
+        self,
+        deserializer: D,
+    ) -> Result<Value, D::Error> {
+        Value::deserialize(deserializer)
+    }
+
+    fn visit_bool<E>(self, b: bool) -> Result<Value, E> {
+        Ok(Value::Boolean(b))
+    }
+
+    fn visit_i64<E>(self, i: i64) -> Result<Value, E> {
+        Ok(Value::Integer(i))
+    }
+
+    // Integers beyond `i64` become floats, as they do when parsing JSON.
+    #[allow(clippy::cast_precision_loss)]
+    fn visit_u64<E>(self, u: u64) -> Result<Value, E> {
+        Ok(i64::try_from(u)
+            .map_or(Value::Float(u as f64), Value::Integer))
+    }
+
+    #[allow(clippy::cast_precision_loss)]
+    fn visit_i128<E>(self, i: i128) -> Result<Value, E> {
+        Ok(i64::try_from(i)
+            .map_or(Value::Float(i as f64), Value::Integer))
+    }
+
+    #[allow(clippy::cast_precision_loss)]
+    fn visit_u128<E>(self, u: u128) -> Result<Value, E> {
+        Ok(i64::try_from(u)
+            .map_or(Value::Float(u as f64), Value::Integer))
+    }
+
+    fn visit_f64<E>(self, f: f64) -> Result<Value, E> {
+        Ok(Value::Float(f))
+    }
+
+    fn visit_str<E>(self, s: &str) -> Result<Value, E> {
+        Ok(Value::String(s.to_string()))
+    }
+
+    fn visit_string<E>(self, s: String) -> Result<Value, E> {
+        Ok(Value::String(s))
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(
+        self,
+        mut seq: A,
+    ) -> Result<Value, A::Error> {
+        let mut arr = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+        while let Some(value) = seq.next_element()? {
+            arr.push(value);
+        }
+        Ok(Value::Array(arr))
+    }
+
+    fn visit_map<A: MapAccess<'de>>(
+        self,
+        mut map: A,
+    ) -> Result<Value, A::Error> {
+        let Some(first) = map.next_key::<String>()? else {
+            return Ok(Value::Object(Box::default()));
+        };
+
+        // TOML datetimes arrive as a map with a single private field.
+        if first == TOML_DATETIME_FIELD {
+            let s: String = map.next_value()?;
+            return s.parse().map(Value::DateTime).map_err(|_| {
+                de::Error::invalid_value(
+                    de::Unexpected::Str(&s),
+                    &"an RFC 3339 datetime",
+                )
+            });
+        }
+
+        // Tagged values arrive as a map with a private tag field
+        // followed by a private value field.
+        if first == TAGGED_TAG_FIELD {
+            let tag: String = map.next_value()?;
+            return match map.next_key::<String>()? {
+                Some(key) if key == TAGGED_VALUE_FIELD => {
+                    Ok(Value::Tagged(tag, Box::new(map.next_value()?)))
+                }
+                _ => Err(de::Error::missing_field(TAGGED_VALUE_FIELD)),
+            };
+        }
+
+        let mut obj = Frontmatter::new();
+        let _ = obj.insert(first, map.next_value()?);
+        while let Some((key, value)) = map.next_entry()? {
+            let _ = obj.insert(key, value);
+        }
+        Ok(Value::Object(Box::new(obj)))
+    }
+}
+
+/// Builds a `Frontmatter` from a map.
+struct FrontmatterVisitor;
+
+impl<'de> Visitor<'de> for FrontmatterVisitor {
+    type Value = Frontmatter;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a front matter map")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(
+        self,
+        mut map: A,
+    ) -> Result<Frontmatter, A::Error> {
+        let mut fm = Frontmatter(IndexMap::with_capacity(
+            map.size_hint().unwrap_or(0),
+        ));
+        while let Some((key, value)) = map.next_entry()? {
+            let _ = fm.insert(key, value);
+        }
+        Ok(fm)
+    }
+}
+
 /// Escapes special characters in a string (e.g., backslashes and quotes).
 ///
 /// # Arguments
@@ -1073,13 +1550,13 @@ fn test_value_as_str() {
             let value = Value::String("Hello".to_string());
             assert_eq!(value.as_str(), Some("Hello"));
 
-            let value = Value::Number(42.0);
+            let value = Value::Integer(42);
             assert_eq!(value.as_str(), None);
         }
 
         #[test]
         fn test_value_as_f64() {
-            let value = Value::Number(42.0);
+            let value = Value::Integer(42);
             assert_eq!(value.as_f64(), Some(42.0));
 
             let value = Value::String("Not a number".to_string());
@@ -1104,12 +1581,12 @@ fn test_value_is_null() {
         #[test]
         fn test_value_is_string() {
             assert!(Value::String("test".to_string()).is_string());
-            assert!(!Value::Number(42.0).is_string());
+            assert!(!Value::Integer(42).is_string());
         }
 
         #[test]
         fn test_value_is_number() {
-            assert!(Value::Number(42.0).is_number());
+            assert!(Value::Integer(42).is_number());
             assert!(!Value::String("42".to_string()).is_number());
         }
 
@@ -1165,7 +1642,7 @@ fn test_value_to_string_representation() {
                 "\"test\""
             );
             assert_eq!(
-                Value::Number(42.0).to_string_representation(),
+                Value::Integer(42).to_string_representation(),
                 "42"
             );
             assert_eq!(
@@ -1182,7 +1659,7 @@ fn test_value_display() {
                 "\"test\""
             );
             assert_eq!(
-                format!("{}", Value::Number(PI)),
+                format!("{}", Value::Float(PI)),
                 format!("{}", PI)
             );
             assert_eq!(format!("{}", Value::Boolean(true)), "true");
@@ -1232,11 +1709,11 @@ fn test_frontmatter_merge() {
             );
 
             let mut fm2 = Frontmatter::new();
-            let _ = fm2.insert("key2".to_string(), Value::Number(42.0));
+            let _ = fm2.insert("key2".to_string(), Value::Integer(42));
 
             fm1.merge(fm2);
             assert_eq!(fm1.len(), 2);
-            assert_eq!(fm1.get("key2"), Some(&Value::Number(42.0)));
+            assert_eq!(fm1.get("key2"), Some(&Value::Integer(42)));
         }
 
         #[test]
@@ -1246,13 +1723,34 @@ fn test_frontmatter_display() {
                 "key1".to_string(),
                 Value::String("value1".to_string()),
             );
-            let _ = fm.insert("key2".to_string(), Value::Number(42.0));
+            let _ = fm.insert("key2".to_string(), Value::Integer(42));
             let display = format!("{}", fm);
 
             assert!(display.contains("\"key1\": \"value1\""));
             assert!(display.contains("\"key2\": 42"));
         }
 
+        #[test]
+        fn test_frontmatter_preserves_insertion_order() {
+            let mut fm: Frontmatter = [
+                ("c".to_string(), Value::Null),
+                ("a".to_string(), Value::Null),
+                ("b".to_string(), Value::Null),
+            ]
+            .into_iter()
+            .collect();
+            let _ = fm.remove("a");
+
+            let mut other = Frontmatter::new();
+            let _ = other.insert("c".to_string(), Value::Boolean(true));
+            let _ = other.insert("d".to_string(), Value::Null);
+            fm.merge(other);
+
+            let keys: Vec<String> =
+                fm.into_iter().map(|(k, _)| k).collect();
+            assert_eq!(keys, ["c", "b", "d"]);
+        }
+
         #[test]
         fn test_frontmatter_is_null() {
             let mut fm = Frontmatter::new();
@@ -1294,7 +1792,7 @@ fn test_frontmatter_clear() {
                 "key1".to_string(),
                 Value::String("value1".to_string()),
             );
-            let _ = fm.insert("key2".to_string(), Value::Number(42.0));
+            let _ = fm.insert("key2".to_string(), Value::Integer(42));
 
             fm.clear();
             assert!(fm.is_empty());
@@ -1314,12 +1812,12 @@ fn test_frontmatter_capacity_and_reserve() {
         fn test_value_tagged() {
             let tagged_value = Value::Tagged(
                 "tag".to_string(),
-                Box::new(Value::Number(42.0)),
+                Box::new(Value::Integer(42)),
             );
 
             if let Value::Tagged(tag, value) = tagged_value {
                 assert_eq!(tag, "tag");
-                assert_eq!(*value, Value::Number(42.0));
+                assert_eq!(*value, Value::Integer(42));
             } else {
                 panic!("Expected Value::Tagged");
             }
@@ -1328,19 +1826,19 @@ fn test_value_tagged() {
         #[test]
         fn test_value_array_mutation() {
             let mut value = Value::Array(vec![
-                Value::Number(1.0),
-                Value::Number(2.0),
+                Value::Integer(1),
+                Value::Integer(2),
             ]);
 
             if let Some(array) = value.get_mut_array() {
-                array.push(Value::Number(3.0));
+                array.push(Value::Integer(3));
             }
 
             assert_eq!(value.array_len(), Some(3));
             assert!(value
                 .as_array()
                 .unwrap()
-                .contains(&Value::Number(3.0)));
+                .contains(&Value::Integer(3)));
         }
 
         #[test]
@@ -1349,7 +1847,7 @@ fn test_value_conversion_errors() {
             assert!(value.clone().into_f64().is_err());
             assert!(value.into_string().is_err());
 
-            let value = Value::Number(42.0);
+            let value = Value::Integer(42);
             assert!(value.into_bool().is_err());
         }
 
@@ -1426,4 +1924,146 @@ fn test_escape_str_edge_cases() {
             );
         }
     }
+
+    mod serde_tests {
+        use super::*;
+
+        /// Front matter holding one value of every variant.
+        fn sample() -> Frontmatter {
+            let mut author = Frontmatter::new();
+            let _ = author.insert("name".into(), "Jane".into());
+            let _ = author.insert("age".into(), Value::Integer(40));
+
+            let mut fm = Frontmatter::new();
+            let _ = fm.insert("title".into(), "Hello".into());
+            let _ = fm.insert("count".into(), Value::Integer(i64::MAX));
+            let _ = fm.insert("ratio".into(), Value::Float(0.5));
+            let _ = fm.insert("draft".into(), Value::Boolean(false));
+            let _ = fm.insert(
+                "pair".into(),
+                Value::Array(vec!["!not-a-tag".into(), 1.into()]),
+            );
+            let _ = fm.insert(
+                "custom".into(),
+                Value::Tagged("!custom".into(), Box::new("x".into())),
+            );
+            let _ = fm.insert(
+                "date".into(),
+                Value::DateTime(
+                    "2024-01-02T03:04:05Z".parse().unwrap(),
+                ),
+            );
+            let mut flags = Frontmatter::new();
+            let _ = flags.insert("!important".into(), true.into());
+            let _ = fm
+                .insert("flags".into(), Value::Object(Box::new(flags)));
+            let _ = fm.insert(
+                "author".into(),
+                Value::Object(Box::new(author)),
+            );
+            fm
+        }
+
+        #[test]
+        fn test_json_shape() {
+            let json =
+                serde_json::to_string(&Plain(&sample())).unwrap();
+            assert_eq!(
+                json,
+                r#"{"title":"Hello","count":9223372036854775807,"ratio":0.5,"draft":false,"pair":["!not-a-tag",1],"custom":{"!custom":"x"},"date":"2024-01-02T03:04:05Z","flags":{"!important":true},"author":{"name":"Jane","age":40}}"#
+            );
+        }
+
+        #[test]
+        fn test_round_trips_keep_shape() {
+            let mut fm = sample();
+            let _ = fm.insert("empty".into(), Value::Null);
+
+            let json = serde_json::to_string(&fm).unwrap();
+            assert_eq!(
+                serde_json::from_str::<Frontmatter>(&json).unwrap(),
+                fm
+            );
+
+            let yaml = noyalib::to_string(&fm).unwrap();
+            assert_eq!(
+                noyalib::from_str::<Frontmatter>(&yaml).unwrap(),
+                fm
+            );
+
+            assert_eq!(Frontmatter::from_serialize(&fm).unwrap(), fm);
+
+            // TOML has no null, so the entry is dropped.
+            let _ = fm.remove("empty");
+            let toml = toml::to_string(&fm).unwrap();
+            assert!(toml.contains("date = 2024-01-02T03:04:05Z\n"));
+            assert_eq!(
+                toml::from_str::<Frontmatter>(&toml).unwrap(),
+                fm
+            );
+        }
+
+        #[test]
+        fn test_yaml_output_uses_native_tags() {
+            let fm = sample();
+            let yaml =
+                crate::parser::to_string(&fm, Format::Yaml).unwrap();
+            assert!(yaml.contains("custom: !custom x\n"));
+            assert_eq!(
+                crate::parser::parse(&yaml, Format::Yaml).unwrap(),
+                fm
+            );
+        }
+
+        #[test]
+        fn test_datetimes() {
+            let dt: Datetime = "2024-01-02T03:04:05Z".parse().unwrap();
+            let mut fm = Frontmatter::new();
+            let _ = fm.insert("date".into(), Value::DateTime(dt));
+
+            assert_eq!(
+                serde_json::to_string(&Plain(&fm)).unwrap(),
+                r#"{"date":"2024-01-02T03:04:05Z"}"#
+            );
+            assert_eq!(
+                toml::to_string(&fm).unwrap(),
+                "date = 2024-01-02T03:04:05Z\n"
+            );
+            assert_eq!(
+                toml::from_str::<Frontmatter>(
+                    "date = 2024-01-02T03:04:05Z"
+                )
+                .unwrap(),
+                fm
+            );
+
+            // The crate's own serde conversions keep the datetime.
+            assert_eq!(Frontmatter::from_serialize(&fm).unwrap(), fm);
+            assert_eq!(
+                fm.deserialize_into::<Frontmatter>().unwrap(),
+                fm
+            );
+        }
+
+        #[test]
+        fn test_single_entry_maps() {
+            let value: Value =
+                serde_json::from_str(r#"{"!custom": [1]}"#).unwrap();
+            assert!(value.is_object());
+
+            let value: Value =
+                serde_json::from_str(r#"{"custom": [1]}"#).unwrap();
+            assert!(value.is_object());
+        }
+
+        #[test]
+        fn test_frontmatter_rejects_non_maps() {
+            assert!(
+                serde_json::from_str::<Frontmatter>("[1, 2]").is_err()
+            );
+            assert!(serde_json::from_str::<Value>("[1, 2]")
+                .unwrap()
+                .is_array());
+        }
+    }
 }